@@ -33,6 +33,9 @@ pub async fn run(execution: Execution) -> anyhow::Result<()> {
     Execution::Deploy(data) => {
       println!("{}: {data:?}", "Data".dimmed())
     }
+    Execution::RollbackDeployment(data) => {
+      println!("{}: {data:?}", "Data".dimmed())
+    }
     Execution::StartDeployment(data) => {
       println!("{}: {data:?}", "Data".dimmed())
     }
@@ -174,6 +177,9 @@ pub async fn run(execution: Execution) -> anyhow::Result<()> {
     Execution::Deploy(request) => {
       komodo_client().execute(request).await
     }
+    Execution::RollbackDeployment(request) => {
+      komodo_client().execute(request).await
+    }
     Execution::StartDeployment(request) => {
       komodo_client().execute(request).await
     }
@@ -16,6 +16,7 @@ use crate::{
     get_user_id_from_headers,
     github::{self, client::github_oauth_client},
     google::{self, client::google_oauth_client},
+    oidc::{self, client::oidc_client},
   },
   config::core_config,
   helpers::query::get_user,
@@ -32,6 +33,8 @@ pub enum AuthRequest {
   GetLoginOptions(GetLoginOptions),
   CreateLocalUser(CreateLocalUser),
   LoginLocalUser(LoginLocalUser),
+  LoginTotp(LoginTotp),
+  LoginLdapUser(LoginLdapUser),
   ExchangeForJwt(ExchangeForJwt),
   GetUser(GetUser),
 }
@@ -47,6 +50,10 @@ pub fn router() -> Router {
     router = router.nest("/google", google::router())
   }
 
+  if oidc_client().is_some() {
+    router = router.nest("/oidc", oidc::router())
+  }
+
   router
 }
 
@@ -91,7 +98,12 @@ fn login_options_reponse() -> &'static GetLoginOptionsResponse {
       google: config.google_oauth.enabled
         && !config.google_oauth.id.is_empty()
         && !config.google_oauth.secret.is_empty(),
+      oidc: oidc_client().is_some(),
+      ldap: config.ldap.enabled
+        && !config.ldap.url.is_empty()
+        && !config.ldap.bind_dn.is_empty(),
       registration_disabled: config.disable_user_registration,
+      require_admin_totp: config.require_admin_totp,
     }
   })
 }
@@ -127,7 +139,7 @@ impl Resolve<GetUser, HeaderMap> for State {
     GetUser {}: GetUser,
     headers: HeaderMap,
   ) -> anyhow::Result<User> {
-    let user_id = get_user_id_from_headers(&headers).await?;
+    let (user_id, _) = get_user_id_from_headers(&headers).await?;
     get_user(&user_id).await
   }
 }
@@ -8,11 +8,14 @@ use komodo_client::{
   entities::{
     alert::{Alert, AlertData, SeverityLevel},
     all_logs_success,
-    build::{Build, ImageRegistry, StandardRegistryConfig},
+    build::{
+      Build, ImageRegistry, ImageScanner, StandardRegistryConfig,
+      VulnerabilityScan,
+    },
     builder::{Builder, BuilderConfig},
     config::core::{AwsEcrConfig, AwsEcrConfigWithCredentials},
     deployment::DeploymentState,
-    komodo_timestamp,
+    get_image_name, komodo_timestamp,
     permission::PermissionLevel,
     to_komodo_name,
     update::{Log, Update},
@@ -27,7 +30,10 @@ use mungos::{
     options::FindOneOptions,
   },
 };
-use periphery_client::api;
+use periphery_client::{
+  api::{self, build::ScanImageResponse},
+  PeripheryClient,
+};
 use resolver_api::Resolve;
 use tokio_util::sync::CancellationToken;
 
@@ -35,7 +41,7 @@ use crate::{
   cloud::aws::ecr,
   config::core_config,
   helpers::{
-    alert::send_alerts,
+    alert::{send_alerts, silence_alerts},
     builder::{cleanup_builder_instance, get_builder_periphery},
     channel::build_cancel_channel,
     git_token,
@@ -47,7 +53,10 @@ use crate::{
     },
     query::{get_deployment_state, get_variables_and_secrets},
     registry_token,
-    update::{init_execution_update, update_update},
+    update::{
+      init_execution_update, periphery_request_with_live_output,
+      update_update,
+    },
   },
   resource::{self, refresh_build_state_cache},
   state::{action_states, db_client, State},
@@ -100,7 +109,22 @@ impl Resolve<RunBuild, (User, Update)> for State {
     )?;
 
     let (registry_token, aws_ecr) =
-      validate_account_extract_registry_token_aws_ecr(&build).await?;
+      validate_account_extract_registry_token_aws_ecr(
+        &build.config.image_registry,
+        Some(&build.name),
+      )
+      .await?;
+    // The cache refs are given explicitly,
+    // so no ecr repo is created for the cache registry.
+    let (cache_registry_token, cache_aws_ecr) =
+      validate_account_extract_registry_token_aws_ecr(
+        &build.config.cache_registry,
+        None,
+      )
+      .await
+      .context("Invalid cache registry")?;
+    // Needed to scan the image after the build.
+    let image_name = get_image_name(&build, |_| aws_ecr.clone()).ok();
 
     let cancel = CancellationToken::new();
     let cancel_clone = cancel.clone();
@@ -278,11 +302,14 @@ impl Resolve<RunBuild, (User, Update)> for State {
       };
 
       let res = tokio::select! {
-        res = periphery
-          .request(api::build::Build {
+        res = periphery_request_with_live_output(
+          &periphery,
+          api::build::Build {
             build: build.clone(),
             registry_token,
             aws_ecr,
+            cache_registry_token,
+            cache_aws_ecr,
             replacers: secret_replacers.into_iter().collect(),
             // Push a commit hash tagged image
             additional_tags: if update.commit_hash.is_empty() {
@@ -290,7 +317,9 @@ impl Resolve<RunBuild, (User, Update)> for State {
             } else {
               vec![update.commit_hash.clone()]
             },
-          }) => res.context("failed at call to periphery to build"),
+          },
+          update.clone(),
+        ) => res.context("failed at call to periphery to build"),
         _ = cancel.cancelled() => {
           info!("build cancelled during build, cleaning up builder");
           update.push_error_log("build cancelled", String::from("user cancelled build during docker build"));
@@ -317,6 +346,24 @@ impl Resolve<RunBuild, (User, Update)> for State {
 
     update.finalize();
 
+    // Only scan images which were successfully pushed.
+    // The build result is already final, so the scan
+    // (or any scan failure) can't change it.
+    let pushed = update.success
+      && !matches!(
+        build.config.image_registry,
+        ImageRegistry::None(_)
+      );
+    let scan = match image_name {
+      Some(image_name)
+        if pushed && build.config.scanner != ImageScanner::None =>
+      {
+        scan_built_image(&periphery, &build, image_name, &mut update)
+          .await
+      }
+      _ => None,
+    };
+
     let db = db_client().await;
 
     if update.success {
@@ -329,7 +376,9 @@ impl Resolve<RunBuild, (User, Update)> for State {
               .context("failed at converting version to bson")?,
             "info.last_built_at": komodo_timestamp(),
             "info.built_hash": &update.commit_hash,
-            "info.built_message": commit_message
+            "info.built_message": commit_message,
+            "info.latest_scan": to_bson(&scan)
+              .context("failed at converting scan to bson")?,
           }},
         )
         .await;
@@ -359,6 +408,9 @@ impl Resolve<RunBuild, (User, Update)> for State {
     update_update(update.clone()).await?;
 
     if update.success {
+      if let Some(scan) = scan {
+        maybe_alert_vulnerabilities(&build, &update, scan);
+      }
       // don't hold response up for user
       tokio::spawn(async move {
         handle_post_build_redeploy(&build.id).await;
@@ -368,11 +420,12 @@ impl Resolve<RunBuild, (User, Update)> for State {
       let target = update.target.clone();
       let version = update.version;
       tokio::spawn(async move {
-        let alert = Alert {
+        let mut alert = Alert {
           id: Default::default(),
           target,
           ts: komodo_timestamp(),
           resolved_ts: Some(komodo_timestamp()),
+          silenced: false,
           resolved: true,
           level: SeverityLevel::Warning,
           data: AlertData::BuildFailed {
@@ -381,6 +434,7 @@ impl Resolve<RunBuild, (User, Update)> for State {
             version,
           },
         };
+        silence_alerts(vec![&mut alert]).await;
         send_alerts(&[alert]).await
       });
     }
@@ -389,6 +443,93 @@ impl Resolve<RunBuild, (User, Update)> for State {
   }
 }
 
+/// Scans the pushed image on the builder, and stores the result
+/// on the Update as `other_data` if it is not already used.
+/// Must be called after the Update is finalized, the scan logs
+/// don't affect the build success.
+async fn scan_built_image(
+  periphery: &PeripheryClient,
+  build: &Build,
+  image_name: String,
+  update: &mut Update,
+) -> Option<VulnerabilityScan> {
+  let custom_tag = if build.config.image_tag.is_empty() {
+    String::new()
+  } else {
+    format!("-{}", build.config.image_tag)
+  };
+  let res = periphery
+    .request(api::build::ScanImage {
+      image_name,
+      tag: format!("{}{custom_tag}", build.config.version),
+      scanner: build.config.scanner,
+      command: build.config.scan_command.clone(),
+      sbom: build.config.sbom,
+    })
+    .await
+    .context("failed at call to periphery to scan image");
+  match res {
+    Ok(ScanImageResponse { logs, scan }) => {
+      update.logs.extend(logs);
+      match &scan {
+        Some(scan) if update.other_data.is_empty() => {
+          update.other_data =
+            serde_json::to_string(scan).unwrap_or_default();
+        }
+        _ => {}
+      }
+      scan
+    }
+    Err(e) => {
+      update.push_error_log("scan image", format_serror(&e.into()));
+      None
+    }
+  }
+}
+
+fn maybe_alert_vulnerabilities(
+  build: &Build,
+  update: &Update,
+  scan: VulnerabilityScan,
+) {
+  let severity = build.config.scan_alert_severity;
+  let threshold = build.config.scan_alert_threshold;
+  let count = scan.count_at_least(severity);
+  if count <= threshold {
+    return;
+  }
+  warn!(
+    "build {} image has {count} vulnerabilities at or above {severity}, alerting...",
+    build.name
+  );
+  let mut alert = Alert {
+    id: Default::default(),
+    target: update.target.clone(),
+    ts: komodo_timestamp(),
+    resolved_ts: Some(komodo_timestamp()),
+    silenced: false,
+    resolved: true,
+    level: if scan.critical > 0 {
+      SeverityLevel::Critical
+    } else {
+      SeverityLevel::Warning
+    },
+    data: AlertData::BuildVulnerabilities {
+      id: build.id.clone(),
+      name: build.name.clone(),
+      version: update.version,
+      image: scan.image,
+      severity,
+      count,
+      threshold,
+    },
+  };
+  tokio::spawn(async move {
+    silence_alerts(vec![&mut alert]).await;
+    send_alerts(&[alert]).await
+  });
+}
+
 #[instrument(skip(update))]
 async fn handle_early_return(
   mut update: Update,
@@ -417,11 +558,12 @@ async fn handle_early_return(
     let target = update.target.clone();
     let version = update.version;
     tokio::spawn(async move {
-      let alert = Alert {
+      let mut alert = Alert {
         id: Default::default(),
         target,
         ts: komodo_timestamp(),
         resolved_ts: Some(komodo_timestamp()),
+        silenced: false,
         resolved: true,
         level: SeverityLevel::Warning,
         data: AlertData::BuildFailed {
@@ -430,6 +572,7 @@ async fn handle_early_return(
           version,
         },
       };
+      silence_alerts(vec![&mut alert]).await;
       send_alerts(&[alert]).await
     });
   }
@@ -599,13 +742,16 @@ async fn handle_post_build_redeploy(build_id: &str) {
   }
 }
 
-/// This will make sure that a build with non-none image registry has an account attached,
+/// This will make sure that a non-none image registry has an account attached,
 /// and will check the core config for a token / aws ecr config matching requirements.
 /// Otherwise it is left to periphery.
+///
+/// For aws ecr, the repo for `build_name` is created if given.
 async fn validate_account_extract_registry_token_aws_ecr(
-  build: &Build,
+  registry: &ImageRegistry,
+  build_name: Option<&str>,
 ) -> anyhow::Result<(Option<String>, Option<AwsEcrConfig>)> {
-  let (domain, account) = match &build.config.image_registry {
+  let (domain, account) = match registry {
     // Early return for None
     ImageRegistry::None(_) => return Ok((None, None)),
     // Early return for AwsEcr
@@ -629,14 +775,16 @@ async fn validate_account_extract_registry_token_aws_ecr(
           )
           .await
           .context("failed to get aws ecr token")?;
-          ecr::maybe_create_repo(
-            &to_komodo_name(&build.name),
-            region.to_string(),
-            access_key_id,
-            secret_access_key,
-          )
-          .await
-          .context("failed to create aws ecr repo")?;
+          if let Some(build_name) = build_name {
+            ecr::maybe_create_repo(
+              &to_komodo_name(build_name),
+              region.to_string(),
+              access_key_id,
+              secret_access_key,
+            )
+            .await
+            .context("failed to create aws ecr repo")?;
+          }
           Some(token)
         }
         None => None,
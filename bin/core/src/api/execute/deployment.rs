@@ -1,30 +1,42 @@
 use std::collections::HashSet;
 
 use anyhow::{anyhow, Context};
-use formatting::format_serror;
+use formatting::{bold, format_serror, muted};
+use hex::ToHex;
 use komodo_client::{
   api::execute::*,
   entities::{
     build::{Build, ImageRegistry},
     config::core::AwsEcrConfig,
     deployment::{
-      extract_registry_domain, Deployment, DeploymentImage,
+      extract_registry_domain, Deployment, DeploymentActionState,
+      DeploymentConfig, DeploymentImage, DeploymentRecord,
+      DeploymentStrategy, HealthGate,
     },
-    get_image_name,
+    get_image_name, komodo_timestamp,
     permission::PermissionLevel,
     server::Server,
     update::{Log, Update},
     user::User,
-    Version,
+    TerminationSignal, Version,
   },
 };
-use periphery_client::api;
+use mungos::{by_id::find_one_by_id, mongodb::bson::doc};
+use periphery_client::{
+  api::{
+    self,
+    container::{blue_green_container_name, WaitContainerHealthy},
+  },
+  PeripheryClient,
+};
 use resolver_api::Resolve;
+use sha2::{Digest, Sha256};
 
 use crate::{
   cloud::aws::ecr,
   config::core_config,
   helpers::{
+    action_state::ActionState,
     interpolate::{
       add_interp_update_log,
       interpolate_variables_secrets_into_container_command,
@@ -34,11 +46,11 @@ use crate::{
     periphery_client,
     query::get_variables_and_secrets,
     registry_token,
-    update::update_update,
+    update::{periphery_request_with_live_output, update_update},
   },
   monitor::update_cache_for_server,
   resource,
-  state::{action_states, State},
+  state::{action_states, db_client, State},
 };
 
 async fn setup_deployment_execution(
@@ -97,6 +109,19 @@ impl Resolve<Deploy, (User, Update)> for State {
       .await
       .context("Failed server health check, stopping run.")?;
 
+    if deployment.config.strategy == DeploymentStrategy::BlueGreen
+      && (deployment.config.network == "host"
+        || !deployment.config.ports.is_empty())
+    {
+      return Err(anyhow!(
+        "BlueGreen deployments can't use the host network or publish ports, as the old and new containers run at the same time"
+      ));
+    }
+
+    // The config as configured, before the image is resolved and
+    // variables / secrets are interpolated. Recorded in the history.
+    let mut record_config = deployment.config.clone();
+
     // This block resolves the attached Build to an actual versioned image
     let (version, registry_token, aws_ecr) = match &deployment
       .config
@@ -234,16 +259,53 @@ impl Resolve<Deploy, (User, Update)> for State {
     update.version = version;
     update_update(update.clone()).await?;
 
-    match periphery
-      .request(api::container::Deploy {
+    // Pin any attached Build to the deployed version
+    if let DeploymentImage::Build { version: v, .. } =
+      &mut record_config.image
+    {
+      *v = version;
+    }
+    let deployment_id = deployment.id.clone();
+    // Any attached Build is resolved to its image above.
+    let DeploymentImage::Image { image } = &deployment.config.image
+    else {
+      return Err(anyhow!("deployment image was not resolved"));
+    };
+    let image = image.clone();
+    let blue_green =
+      deployment.config.strategy == DeploymentStrategy::BlueGreen;
+    let health_gate = WaitContainerHealthy {
+      name: if blue_green {
+        blue_green_container_name(&deployment.name)
+      } else {
+        deployment.name.clone()
+      },
+      gate: deployment.config.health_gate,
+      target: deployment.config.health_gate_target.clone(),
+      timeout: deployment.config.health_gate_timeout,
+    };
+    let restore_previous = health_gate.gate != HealthGate::None
+      && deployment.config.health_gate_restore;
+    let name = deployment.name.clone();
+    let network = deployment.config.network.clone();
+
+    if blue_green {
+      action_state.set(|state| state.deploying_next = true)?;
+    }
+
+    match periphery_request_with_live_output(
+      &periphery,
+      api::container::Deploy {
         deployment,
         stop_signal,
         stop_time,
         registry_token,
         aws_ecr,
         replacers: secret_replacers.into_iter().collect(),
-      })
-      .await
+      },
+      update.clone(),
+    )
+    .await
     {
       Ok(log) => update.logs.push(log),
       Err(e) => {
@@ -256,11 +318,330 @@ impl Resolve<Deploy, (User, Update)> for State {
       }
     };
 
+    if blue_green {
+      finish_blue_green(
+        &periphery,
+        name,
+        network,
+        health_gate,
+        stop_signal,
+        stop_time,
+        &action_state,
+        &mut update,
+      )
+      .await?;
+    } else if health_gate.gate != HealthGate::None {
+      let mut healthy = update.logs.iter().all(|log| log.success);
+      if healthy {
+        // Show the deploy logs while waiting on the health gate.
+        update_update(update.clone()).await?;
+        let log = match periphery.request(health_gate).await {
+          Ok(log) => log,
+          Err(e) => Log::error(
+            "health gate",
+            format_serror(
+              &e.context("failed to wait for container health")
+                .into(),
+            ),
+          ),
+        };
+        healthy = log.success;
+        update.logs.push(log);
+      }
+      if restore_previous {
+        let res = if healthy {
+          periphery
+            .request(api::container::RemovePreviousContainer { name })
+            .await
+        } else {
+          periphery
+            .request(api::container::RestorePreviousContainer {
+              name,
+            })
+            .await
+        };
+        let log = match res {
+          Ok(log) => log,
+          Err(e) => Log::error(
+            "previous container",
+            format_serror(
+              &e.context("failed to clean up previous container")
+                .into(),
+            ),
+          ),
+        };
+        update.logs.push(log);
+      }
+    }
+
     update_cache_for_server(&server).await;
 
     update.finalize();
     update_update(update.clone()).await?;
 
+    if update.success {
+      if let Err(e) = add_deployment_record(
+        deployment_id,
+        image,
+        version,
+        record_config,
+        update.id.clone(),
+      )
+      .await
+      {
+        warn!(
+          "failed to record deploy in deployment history | {e:#}"
+        );
+      }
+    }
+
+    Ok(update)
+  }
+}
+
+/// Takes a BlueGreen deploy from the started new container
+/// to replacing the old one, reporting each phase in the action state.
+/// If the new container fails along the way, it is removed
+/// and the old container is left running.
+#[allow(clippy::too_many_arguments)]
+async fn finish_blue_green(
+  periphery: &PeripheryClient,
+  name: String,
+  network: String,
+  health_gate: WaitContainerHealthy,
+  stop_signal: Option<TerminationSignal>,
+  stop_time: Option<i32>,
+  action_state: &ActionState<DeploymentActionState>,
+  update: &mut Update,
+) -> anyhow::Result<()> {
+  let next = health_gate.name.clone();
+
+  if update.logs.iter().all(|log| log.success) {
+    action_state.set(|state| {
+      state.deploying_next = false;
+      state.health_checking = true;
+    })?;
+    update_update(update.clone()).await?;
+    let log = match periphery.request(health_gate).await {
+      Ok(log) => log,
+      Err(e) => Log::error(
+        "health gate",
+        format_serror(
+          &e.context("failed to wait for container health").into(),
+        ),
+      ),
+    };
+    update.logs.push(log);
+  }
+
+  if update.logs.iter().all(|log| log.success) {
+    action_state.set(|state| {
+      state.health_checking = false;
+      state.switching = true;
+    })?;
+    update_update(update.clone()).await?;
+    let log = match periphery
+      .request(api::container::SwitchContainerAlias {
+        name: name.clone(),
+        network,
+      })
+      .await
+    {
+      Ok(log) => log,
+      Err(e) => Log::error(
+        "switch network alias",
+        format_serror(
+          &e.context("failed to switch network alias").into(),
+        ),
+      ),
+    };
+    update.logs.push(log);
+  }
+
+  if !update.logs.iter().all(|log| log.success) {
+    // Leave the old container running
+    let log = match periphery
+      .request(api::container::RemoveContainer {
+        name: next,
+        signal: None,
+        time: None,
+      })
+      .await
+    {
+      Ok(log) => log,
+      Err(e) => Log::error(
+        "remove new container",
+        format_serror(
+          &e.context("failed to remove new container").into(),
+        ),
+      ),
+    };
+    update.logs.push(log);
+    return Ok(());
+  }
+
+  action_state.set(|state| {
+    state.switching = false;
+    state.removing_old = true;
+  })?;
+  update_update(update.clone()).await?;
+  let log = match periphery
+    .request(api::container::ReplaceContainer {
+      name,
+      signal: stop_signal,
+      time: stop_time,
+    })
+    .await
+  {
+    Ok(log) => log,
+    Err(e) => Log::error(
+      "replace container",
+      format_serror(
+        &e.context("failed to replace old container").into(),
+      ),
+    ),
+  };
+  update.logs.push(log);
+
+  Ok(())
+}
+
+async fn add_deployment_record(
+  deployment_id: String,
+  image: String,
+  version: Version,
+  config: DeploymentConfig,
+  update_id: String,
+) -> anyhow::Result<()> {
+  let record = DeploymentRecord {
+    id: Default::default(),
+    deployment_id,
+    ts: komodo_timestamp(),
+    image,
+    version,
+    config_hash: config_hash(&config)?,
+    config,
+    update_id,
+    rolled_back: false,
+  };
+  db_client()
+    .await
+    .deployment_history
+    .insert_one(record)
+    .await
+    .context("failed to add deployment record to db")?;
+  Ok(())
+}
+
+fn config_hash(config: &DeploymentConfig) -> anyhow::Result<String> {
+  let bytes = serde_json::to_vec(config)
+    .context("failed to serialize deployment config")?;
+  Ok(Sha256::digest(bytes).encode_hex())
+}
+
+impl Resolve<RollbackDeployment, (User, Update)> for State {
+  #[instrument(name = "RollbackDeployment", skip(self, user, update), fields(user_id = user.id, update_id = update.id))]
+  async fn resolve(
+    &self,
+    RollbackDeployment { deployment, record }: RollbackDeployment,
+    (user, mut update): (User, Update),
+  ) -> anyhow::Result<Update> {
+    // Write is required, as the deployment config is restored.
+    let deployment = resource::get_check_permissions::<Deployment>(
+      &deployment,
+      &user,
+      PermissionLevel::Write,
+    )
+    .await?;
+
+    let history = &db_client().await.deployment_history;
+
+    let target = match record {
+      Some(record) => find_one_by_id(history, &record)
+        .await
+        .context("failed to query db for deployment record")?
+        .filter(|r| r.deployment_id == deployment.id)
+        .with_context(|| {
+          format!(
+            "no deployment record {record} found for deployment {}",
+            deployment.name
+          )
+        })?,
+      None => {
+        let latest = history
+          .find_one(doc! { "deployment_id": &deployment.id })
+          .sort(doc! { "ts": -1 })
+          .await
+          .context("failed to query db for latest deployment record")?
+          .context("deployment has no recorded deploys")?;
+        history
+          .find_one(doc! {
+            "deployment_id": &deployment.id,
+            "rolled_back": { "$ne": true },
+            "config_hash": { "$ne": &latest.config_hash },
+          })
+          .sort(doc! { "ts": -1 })
+          .await
+          .context("failed to query db for deployment record")?
+          .context(
+            "no previous deploy found with a different image or config",
+          )?
+      }
+    };
+
+    update.push_simple_log(
+      "rollback",
+      format!(
+        "Rolling back to {} {}",
+        bold(&target.image),
+        muted(format!(
+          "(record {}, deployed in update {})",
+          target.id, target.update_id
+        ))
+      ),
+    );
+
+    // Restore the recorded config, if it differs from the current one.
+    if config_hash(&deployment.config)? != target.config_hash {
+      resource::update::<Deployment>(
+        &deployment.id,
+        target.config.clone().into(),
+        &user,
+      )
+      .await
+      .context("failed to restore deployment config")?;
+    }
+
+    let update = self
+      .resolve(
+        Deploy {
+          deployment: deployment.id.clone(),
+          stop_signal: None,
+          stop_time: None,
+        },
+        (user, update),
+      )
+      .await?;
+
+    if update.success {
+      // Skip the records rolled back past when finding the next target.
+      if let Err(e) = history
+        .update_many(
+          doc! {
+            "deployment_id": &deployment.id,
+            "ts": { "$gt": target.ts },
+            "config_hash": { "$ne": &target.config_hash },
+          },
+          doc! { "$set": { "rolled_back": true } },
+        )
+        .await
+      {
+        warn!(
+          "failed to mark deployment records as rolled back | {e:#}"
+        );
+      }
+    }
+
     Ok(update)
   }
 }
@@ -6,6 +6,7 @@ use formatting::format_serror;
 use komodo_client::{
   api::execute::*,
   entities::{
+    api_key::ApiKeyRequestType,
     update::{Log, Update},
     user::User,
   },
@@ -18,7 +19,7 @@ use typeshare::typeshare;
 use uuid::Uuid;
 
 use crate::{
-  auth::auth_request,
+  auth::{auth_request, with_api_key_scope, RequestApiKeyScope},
   helpers::update::{init_execution_update, update_update},
   state::{db_client, State},
 };
@@ -63,6 +64,7 @@ pub enum ExecuteRequest {
 
   // ==== DEPLOYMENT ====
   Deploy(Deploy),
+  RollbackDeployment(RollbackDeployment),
   StartDeployment(StartDeployment),
   RestartDeployment(RestartDeployment),
   PauseDeployment(PauseDeployment),
@@ -100,13 +102,17 @@ pub enum ExecuteRequest {
 }
 
 pub fn router() -> Router {
-  Router::new()
-    .route("/", post(handler))
-    .layer(middleware::from_fn(auth_request))
+  Router::new().route("/", post(handler)).layer(
+    middleware::from_fn_with_state(
+      ApiKeyRequestType::Execute,
+      auth_request,
+    ),
+  )
 }
 
 async fn handler(
   Extension(user): Extension<User>,
+  Extension(scope): Extension<RequestApiKeyScope>,
   Json(request): Json<ExecuteRequest>,
 ) -> serror::Result<Json<Update>> {
   let req_id = Uuid::new_v4();
@@ -116,8 +122,10 @@ async fn handler(
 
   let update = init_execution_update(&request, &user).await?;
 
-  let handle =
-    tokio::spawn(task(req_id, request, user, update.clone()));
+  let handle = tokio::spawn(with_api_key_scope(
+    scope,
+    task(req_id, request, user, update.clone()),
+  ));
 
   tokio::spawn({
     let update_id = update.id.clone();
@@ -1,4 +1,4 @@
-use std::pin::Pin;
+use std::{collections::HashMap, pin::Pin};
 
 use formatting::{bold, colored, format_serror, muted, Color};
 use komodo_client::{
@@ -22,15 +22,16 @@ impl Resolve<RunProcedure, (User, Update)> for State {
   #[instrument(name = "RunProcedure", skip(self, user, update), fields(user_id = user.id, update_id = update.id))]
   async fn resolve(
     &self,
-    RunProcedure { procedure }: RunProcedure,
+    RunProcedure { procedure, args }: RunProcedure,
     (user, update): (User, Update),
   ) -> anyhow::Result<Update> {
-    resolve_inner(procedure, user, update).await
+    resolve_inner(procedure, args, user, update).await
   }
 }
 
 fn resolve_inner(
   procedure: String,
+  args: HashMap<String, String>,
   user: User,
   mut update: Update,
 ) -> Pin<
@@ -57,6 +58,18 @@ fn resolve_inner(
         bold(&procedure.name)
       ),
     );
+    if !args.is_empty() {
+      let mut args = args
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>();
+      args.sort();
+      update.logs[0].stdout.push_str(&format!(
+        "\n{}: with args: {}",
+        muted("INFO"),
+        args.join(", ")
+      ));
+    }
 
     // get the action state for the procedure (or insert default).
     let action_state = action_states()
@@ -73,7 +86,8 @@ fn resolve_inner(
 
     let update = Mutex::new(update);
 
-    let res = execute_procedure(&procedure, &update).await;
+    let res =
+      execute_procedure(&procedure, &args, &user, &update).await;
 
     let mut update = update.into_inner();
 
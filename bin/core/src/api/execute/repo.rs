@@ -28,7 +28,7 @@ use tokio_util::sync::CancellationToken;
 
 use crate::{
   helpers::{
-    alert::send_alerts,
+    alert::{send_alerts, silence_alerts},
     builder::{cleanup_builder_instance, get_builder_periphery},
     channel::repo_cancel_channel,
     git_token,
@@ -439,11 +439,12 @@ impl Resolve<BuildRepo, (User, Update)> for State {
       warn!("repo build unsuccessful, alerting...");
       let target = update.target.clone();
       tokio::spawn(async move {
-        let alert = Alert {
+        let mut alert = Alert {
           id: Default::default(),
           target,
           ts: komodo_timestamp(),
           resolved_ts: Some(komodo_timestamp()),
+          silenced: false,
           resolved: true,
           level: SeverityLevel::Warning,
           data: AlertData::RepoBuildFailed {
@@ -451,6 +452,7 @@ impl Resolve<BuildRepo, (User, Update)> for State {
             name: repo.name,
           },
         };
+        silence_alerts(vec![&mut alert]).await;
         send_alerts(&[alert]).await
       });
     }
@@ -486,11 +488,12 @@ async fn handle_builder_early_return(
     warn!("repo build unsuccessful, alerting...");
     let target = update.target.clone();
     tokio::spawn(async move {
-      let alert = Alert {
+      let mut alert = Alert {
         id: Default::default(),
         target,
         ts: komodo_timestamp(),
         resolved_ts: Some(komodo_timestamp()),
+        silenced: false,
         resolved: true,
         level: SeverityLevel::Warning,
         data: AlertData::RepoBuildFailed {
@@ -498,6 +501,7 @@ async fn handle_builder_early_return(
           name: repo_name,
         },
       };
+      silence_alerts(vec![&mut alert]).await;
       send_alerts(&[alert]).await
     });
   }
@@ -26,7 +26,7 @@ use crate::{
       execute::execute_compose, get_stack_and_server,
       services::extract_services_into_res,
     },
-    update::update_update,
+    update::{periphery_request_with_live_output, update_update},
   },
   monitor::update_cache_for_server,
   state::{action_states, db_client, State},
@@ -121,15 +121,18 @@ impl Resolve<DeployStack, (User, Update)> for State {
       remote_errors,
       commit_hash,
       commit_message,
-    } = periphery_client(&server)?
-      .request(ComposeUp {
+    } = periphery_request_with_live_output(
+      &periphery_client(&server)?,
+      ComposeUp {
         stack: stack.clone(),
         service: None,
         git_token,
         registry_token,
         replacers: secret_replacers.into_iter().collect(),
-      })
-      .await?;
+      },
+      update.clone(),
+    )
+    .await?;
 
     update.logs.extend(logs);
 
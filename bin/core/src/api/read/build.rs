@@ -22,6 +22,7 @@ use resolver_api::Resolve;
 
 use crate::{
   config::core_config,
+  helpers::webhook::GitWebhookClient,
   resource,
   state::{
     action_states, build_state_cache, db_client, github_client, State,
@@ -278,13 +279,6 @@ impl Resolve<GetBuildWebhookEnabled, User> for State {
     GetBuildWebhookEnabled { build }: GetBuildWebhookEnabled,
     user: User,
   ) -> anyhow::Result<GetBuildWebhookEnabledResponse> {
-    let Some(github) = github_client() else {
-      return Ok(GetBuildWebhookEnabledResponse {
-        managed: false,
-        enabled: false,
-      });
-    };
-
     let build = resource::get_check_permissions::<Build>(
       &build,
       &user,
@@ -292,6 +286,32 @@ impl Resolve<GetBuildWebhookEnabled, User> for State {
     )
     .await?;
 
+    if !build.config.repo.is_empty() {
+      if let Some(client) = GitWebhookClient::new(
+        &build.config.git_provider,
+        &build.config.git_account,
+      )
+      .await?
+      {
+        return Ok(GetBuildWebhookEnabledResponse {
+          managed: true,
+          enabled: client
+            .webhook_exists(
+              &build.config.repo,
+              &client.listener_url(&format!("build/{}", build.id)),
+            )
+            .await?,
+        });
+      }
+    }
+
+    let Some(github) = github_client() else {
+      return Ok(GetBuildWebhookEnabledResponse {
+        managed: false,
+        enabled: false,
+      });
+    };
+
     if build.config.git_provider != "github.com"
       || build.config.repo.is_empty()
     {
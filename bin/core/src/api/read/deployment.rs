@@ -15,13 +15,17 @@ use komodo_client::{
     user::User,
   },
 };
+use mungos::{
+  find::find_collect,
+  mongodb::{bson::doc, options::FindOptions},
+};
 use periphery_client::api;
 use resolver_api::Resolve;
 
 use crate::{
   helpers::periphery_client,
   resource,
-  state::{action_states, deployment_status_cache, State},
+  state::{action_states, db_client, deployment_status_cache, State},
 };
 
 impl Resolve<GetDeployment, User> for State {
@@ -201,6 +205,33 @@ impl Resolve<GetDeploymentActionState, User> for State {
   }
 }
 
+const MAX_HISTORY_LIMIT: u64 = 100;
+
+impl Resolve<ListDeploymentHistory, User> for State {
+  async fn resolve(
+    &self,
+    ListDeploymentHistory { deployment, limit }: ListDeploymentHistory,
+    user: User,
+  ) -> anyhow::Result<ListDeploymentHistoryResponse> {
+    let deployment = resource::get_check_permissions::<Deployment>(
+      &deployment,
+      &user,
+      PermissionLevel::Read,
+    )
+    .await?;
+    find_collect(
+      &db_client().await.deployment_history,
+      doc! { "deployment_id": &deployment.id },
+      FindOptions::builder()
+        .sort(doc! { "ts": -1 })
+        .limit(cmp::min(limit, MAX_HISTORY_LIMIT) as i64)
+        .build(),
+    )
+    .await
+    .context("failed to pull deployment history from mongo")
+  }
+}
+
 impl Resolve<GetDeploymentsSummary, User> for State {
   async fn resolve(
     &self,
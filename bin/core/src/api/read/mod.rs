@@ -6,6 +6,7 @@ use axum_extra::{headers::ContentType, TypedHeader};
 use komodo_client::{
   api::read::*,
   entities::{
+    api_key::ApiKeyRequestType,
     build::Build,
     builder::{Builder, BuilderConfig},
     config::{DockerRegistry, GitProvider},
@@ -25,8 +26,11 @@ use typeshare::typeshare;
 use uuid::Uuid;
 
 use crate::{
-  auth::auth_request, config::core_config, helpers::periphery_client,
-  resource, state::State,
+  auth::{auth_request, with_api_key_scope, RequestApiKeyScope},
+  config::core_config,
+  helpers::periphery_client,
+  resource,
+  state::State,
 };
 
 mod alert;
@@ -41,6 +45,7 @@ mod repo;
 mod search;
 mod server;
 mod server_template;
+mod silence;
 mod stack;
 mod sync;
 mod tag;
@@ -129,6 +134,7 @@ enum ReadRequest {
   GetDeployment(GetDeployment),
   GetDeploymentContainer(GetDeploymentContainer),
   GetDeploymentActionState(GetDeploymentActionState),
+  ListDeploymentHistory(ListDeploymentHistory),
   GetDeploymentStats(GetDeploymentStats),
   GetDeploymentLog(GetDeploymentLog),
   SearchDeploymentLog(SearchDeploymentLog),
@@ -204,6 +210,10 @@ enum ReadRequest {
   ListAlerts(ListAlerts),
   GetAlert(GetAlert),
 
+  // ==== SILENCE ====
+  GetSilence(GetSilence),
+  ListSilences(ListSilences),
+
   // ==== SERVER STATS ====
   #[to_string_resolver]
   GetSystemInformation(GetSystemInformation),
@@ -224,22 +234,25 @@ enum ReadRequest {
 }
 
 pub fn router() -> Router {
-  Router::new()
-    .route("/", post(handler))
-    .layer(middleware::from_fn(auth_request))
+  Router::new().route("/", post(handler)).layer(
+    middleware::from_fn_with_state(
+      ApiKeyRequestType::Read,
+      auth_request,
+    ),
+  )
 }
 
 #[instrument(name = "ReadHandler", level = "debug", skip(user), fields(user_id = user.id))]
 async fn handler(
   Extension(user): Extension<User>,
+  Extension(scope): Extension<RequestApiKeyScope>,
   Json(request): Json<ReadRequest>,
 ) -> serror::Result<(TypedHeader<ContentType>, String)> {
   let timer = Instant::now();
   let req_id = Uuid::new_v4();
   debug!("/read request | user: {}", user.username);
   let res =
-    State
-      .resolve_request(request, user)
+    with_api_key_scope(scope, State.resolve_request(request, user))
       .await
       .map_err(|e| match e {
         resolver_api::Error::Serialization(e) => {
@@ -443,6 +456,7 @@ impl Resolve<ListGitProvidersFromConfig, User> for State {
         providers.push(GitProvider {
           domain: build.config.git_provider,
           https: build.config.git_https,
+          provider_type: Default::default(),
           accounts: Default::default(),
         });
       }
@@ -455,6 +469,7 @@ impl Resolve<ListGitProvidersFromConfig, User> for State {
         providers.push(GitProvider {
           domain: repo.config.git_provider,
           https: repo.config.git_https,
+          provider_type: Default::default(),
           accounts: Default::default(),
         });
       }
@@ -467,6 +482,7 @@ impl Resolve<ListGitProvidersFromConfig, User> for State {
         providers.push(GitProvider {
           domain: sync.config.git_provider,
           https: sync.config.git_https,
+          provider_type: Default::default(),
           accounts: Default::default(),
         });
       }
@@ -12,6 +12,7 @@ use resolver_api::Resolve;
 
 use crate::{
   config::core_config,
+  helpers::webhook::GitWebhookClient,
   resource,
   state::{action_states, github_client, repo_state_cache, State},
 };
@@ -132,6 +133,46 @@ impl Resolve<GetRepoWebhooksEnabled, User> for State {
     GetRepoWebhooksEnabled { repo }: GetRepoWebhooksEnabled,
     user: User,
   ) -> anyhow::Result<GetRepoWebhooksEnabledResponse> {
+    let repo = resource::get_check_permissions::<Repo>(
+      &repo,
+      &user,
+      PermissionLevel::Read,
+    )
+    .await?;
+
+    if !repo.config.repo.is_empty() {
+      if let Some(client) = GitWebhookClient::new(
+        &repo.config.git_provider,
+        &repo.config.git_account,
+      )
+      .await?
+      {
+        return Ok(GetRepoWebhooksEnabledResponse {
+          managed: true,
+          clone_enabled: client
+            .webhook_exists(
+              &repo.config.repo,
+              &client
+                .listener_url(&format!("repo/{}/clone", repo.id)),
+            )
+            .await?,
+          pull_enabled: client
+            .webhook_exists(
+              &repo.config.repo,
+              &client.listener_url(&format!("repo/{}/pull", repo.id)),
+            )
+            .await?,
+          build_enabled: client
+            .webhook_exists(
+              &repo.config.repo,
+              &client
+                .listener_url(&format!("repo/{}/build", repo.id)),
+            )
+            .await?,
+        });
+      }
+    }
+
     let Some(github) = github_client() else {
       return Ok(GetRepoWebhooksEnabledResponse {
         managed: false,
@@ -141,13 +182,6 @@ impl Resolve<GetRepoWebhooksEnabled, User> for State {
       });
     };
 
-    let repo = resource::get_check_permissions::<Repo>(
-      &repo,
-      &user,
-      PermissionLevel::Read,
-    )
-    .await?;
-
     if repo.config.git_provider != "github.com"
       || repo.config.repo.is_empty()
     {
@@ -0,0 +1,48 @@
+use anyhow::Context;
+use komodo_client::{
+  api::read::{
+    GetSilence, GetSilenceResponse, ListSilences,
+    ListSilencesResponse,
+  },
+  entities::{komodo_timestamp, user::User},
+};
+use mungos::{
+  find::find_collect,
+  mongodb::{bson::doc, options::FindOptions},
+};
+use resolver_api::Resolve;
+
+use crate::{
+  helpers::query::get_silence,
+  state::{db_client, State},
+};
+
+impl Resolve<GetSilence, User> for State {
+  async fn resolve(
+    &self,
+    GetSilence { id }: GetSilence,
+    _: User,
+  ) -> anyhow::Result<GetSilenceResponse> {
+    get_silence(&id).await
+  }
+}
+
+impl Resolve<ListSilences, User> for State {
+  async fn resolve(
+    &self,
+    ListSilences { active }: ListSilences,
+    _: User,
+  ) -> anyhow::Result<ListSilencesResponse> {
+    let filter = active.then(|| {
+      let ts = komodo_timestamp();
+      doc! { "start_ts": { "$lte": ts }, "end_ts": { "$gt": ts } }
+    });
+    find_collect(
+      &db_client().await.silences,
+      filter,
+      FindOptions::builder().sort(doc! { "start_ts": -1 }).build(),
+    )
+    .await
+    .context("failed to query db for silences")
+  }
+}
@@ -17,7 +17,10 @@ use resolver_api::Resolve;
 
 use crate::{
   config::core_config,
-  helpers::{periphery_client, stack::get_stack_and_server},
+  helpers::{
+    periphery_client, stack::get_stack_and_server,
+    webhook::GitWebhookClient,
+  },
   resource,
   state::{action_states, github_client, stack_status_cache, State},
 };
@@ -258,6 +261,40 @@ impl Resolve<GetStackWebhooksEnabled, User> for State {
     GetStackWebhooksEnabled { stack }: GetStackWebhooksEnabled,
     user: User,
   ) -> anyhow::Result<GetStackWebhooksEnabledResponse> {
+    let stack = resource::get_check_permissions::<Stack>(
+      &stack,
+      &user,
+      PermissionLevel::Read,
+    )
+    .await?;
+
+    if !stack.config.repo.is_empty() {
+      if let Some(client) = GitWebhookClient::new(
+        &stack.config.git_provider,
+        &stack.config.git_account,
+      )
+      .await?
+      {
+        return Ok(GetStackWebhooksEnabledResponse {
+          managed: true,
+          refresh_enabled: client
+            .webhook_exists(
+              &stack.config.repo,
+              &client
+                .listener_url(&format!("stack/{}/refresh", stack.id)),
+            )
+            .await?,
+          deploy_enabled: client
+            .webhook_exists(
+              &stack.config.repo,
+              &client
+                .listener_url(&format!("stack/{}/deploy", stack.id)),
+            )
+            .await?,
+        });
+      }
+    }
+
     let Some(github) = github_client() else {
       return Ok(GetStackWebhooksEnabledResponse {
         managed: false,
@@ -266,13 +303,6 @@ impl Resolve<GetStackWebhooksEnabled, User> for State {
       });
     };
 
-    let stack = resource::get_check_permissions::<Stack>(
-      &stack,
-      &user,
-      PermissionLevel::Read,
-    )
-    .await?;
-
     if stack.config.git_provider != "github.com"
       || stack.config.repo.is_empty()
     {
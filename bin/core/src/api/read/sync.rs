@@ -15,6 +15,7 @@ use resolver_api::Resolve;
 
 use crate::{
   config::core_config,
+  helpers::webhook::GitWebhookClient,
   resource,
   state::{
     action_states, github_client, resource_sync_state_cache, State,
@@ -148,6 +149,39 @@ impl Resolve<GetSyncWebhooksEnabled, User> for State {
     GetSyncWebhooksEnabled { sync }: GetSyncWebhooksEnabled,
     user: User,
   ) -> anyhow::Result<GetSyncWebhooksEnabledResponse> {
+    let sync = resource::get_check_permissions::<ResourceSync>(
+      &sync,
+      &user,
+      PermissionLevel::Read,
+    )
+    .await?;
+
+    if !sync.config.repo.is_empty() {
+      if let Some(client) = GitWebhookClient::new(
+        &sync.config.git_provider,
+        &sync.config.git_account,
+      )
+      .await?
+      {
+        return Ok(GetSyncWebhooksEnabledResponse {
+          managed: true,
+          refresh_enabled: client
+            .webhook_exists(
+              &sync.config.repo,
+              &client
+                .listener_url(&format!("sync/{}/refresh", sync.id)),
+            )
+            .await?,
+          sync_enabled: client
+            .webhook_exists(
+              &sync.config.repo,
+              &client.listener_url(&format!("sync/{}/sync", sync.id)),
+            )
+            .await?,
+        });
+      }
+    }
+
     let Some(github) = github_client() else {
       return Ok(GetSyncWebhooksEnabledResponse {
         managed: false,
@@ -156,13 +190,6 @@ impl Resolve<GetSyncWebhooksEnabled, User> for State {
       });
     };
 
-    let sync = resource::get_check_permissions::<ResourceSync>(
-      &sync,
-      &user,
-      PermissionLevel::Read,
-    )
-    .await?;
-
     if sync.config.git_provider != "github.com"
       || sync.config.repo.is_empty()
     {
@@ -20,7 +20,7 @@ use komodo_client::{
     },
     environment_vars_to_string,
     permission::{PermissionLevel, UserTarget},
-    procedure::Procedure,
+    procedure::{Procedure, StageCondition},
     repo::Repo,
     resource::{Resource, ResourceQuery},
     server::Server,
@@ -389,184 +389,156 @@ async fn add_procedure(
   for stage in &mut procedure.config.stages {
     for execution in &mut stage.executions {
       match &mut execution.execution {
-        Execution::RunProcedure(exec) => exec.procedure.clone_from(
-          names
-            .procedures
-            .get(&exec.procedure)
-            .unwrap_or(&String::new()),
-        ),
-        Execution::RunBuild(exec) => exec.build.clone_from(
-          names.builds.get(&exec.build).unwrap_or(&String::new()),
-        ),
-        Execution::CancelBuild(exec) => exec.build.clone_from(
-          names.builds.get(&exec.build).unwrap_or(&String::new()),
-        ),
-        Execution::Deploy(exec) => exec.deployment.clone_from(
-          names
-            .deployments
-            .get(&exec.deployment)
-            .unwrap_or(&String::new()),
-        ),
+        Execution::RunProcedure(exec) => {
+          id_to_name(&names.procedures, &mut exec.procedure)
+        }
+        Execution::RunBuild(exec) => {
+          id_to_name(&names.builds, &mut exec.build)
+        }
+        Execution::CancelBuild(exec) => {
+          id_to_name(&names.builds, &mut exec.build)
+        }
+        Execution::Deploy(exec) => {
+          id_to_name(&names.deployments, &mut exec.deployment)
+        }
+        Execution::RollbackDeployment(exec) => {
+          id_to_name(&names.deployments, &mut exec.deployment)
+        }
         Execution::StartDeployment(exec) => {
-          exec.deployment.clone_from(
-            names
-              .deployments
-              .get(&exec.deployment)
-              .unwrap_or(&String::new()),
-          )
+          id_to_name(&names.deployments, &mut exec.deployment)
         }
         Execution::RestartDeployment(exec) => {
-          exec.deployment.clone_from(
-            names
-              .deployments
-              .get(&exec.deployment)
-              .unwrap_or(&String::new()),
-          )
+          id_to_name(&names.deployments, &mut exec.deployment)
         }
         Execution::PauseDeployment(exec) => {
-          exec.deployment.clone_from(
-            names
-              .deployments
-              .get(&exec.deployment)
-              .unwrap_or(&String::new()),
-          )
+          id_to_name(&names.deployments, &mut exec.deployment)
         }
         Execution::UnpauseDeployment(exec) => {
-          exec.deployment.clone_from(
-            names
-              .deployments
-              .get(&exec.deployment)
-              .unwrap_or(&String::new()),
-          )
+          id_to_name(&names.deployments, &mut exec.deployment)
         }
         Execution::StopDeployment(exec) => {
-          exec.deployment.clone_from(
-            names
-              .deployments
-              .get(&exec.deployment)
-              .unwrap_or(&String::new()),
-          )
+          id_to_name(&names.deployments, &mut exec.deployment)
         }
         Execution::DestroyDeployment(exec) => {
-          exec.deployment.clone_from(
-            names
-              .deployments
-              .get(&exec.deployment)
-              .unwrap_or(&String::new()),
-          )
+          id_to_name(&names.deployments, &mut exec.deployment)
+        }
+        Execution::CloneRepo(exec) => {
+          id_to_name(&names.repos, &mut exec.repo)
+        }
+        Execution::PullRepo(exec) => {
+          id_to_name(&names.repos, &mut exec.repo)
+        }
+        Execution::BuildRepo(exec) => {
+          id_to_name(&names.repos, &mut exec.repo)
+        }
+        Execution::CancelRepoBuild(exec) => {
+          id_to_name(&names.repos, &mut exec.repo)
+        }
+        Execution::StartContainer(exec) => {
+          id_to_name(&names.servers, &mut exec.server)
+        }
+        Execution::RestartContainer(exec) => {
+          id_to_name(&names.servers, &mut exec.server)
+        }
+        Execution::PauseContainer(exec) => {
+          id_to_name(&names.servers, &mut exec.server)
+        }
+        Execution::UnpauseContainer(exec) => {
+          id_to_name(&names.servers, &mut exec.server)
+        }
+        Execution::StopContainer(exec) => {
+          id_to_name(&names.servers, &mut exec.server)
+        }
+        Execution::DestroyContainer(exec) => {
+          id_to_name(&names.servers, &mut exec.server)
         }
-        Execution::CloneRepo(exec) => exec.repo.clone_from(
-          names.repos.get(&exec.repo).unwrap_or(&String::new()),
-        ),
-        Execution::PullRepo(exec) => exec.repo.clone_from(
-          names.repos.get(&exec.repo).unwrap_or(&String::new()),
-        ),
-        Execution::BuildRepo(exec) => exec.repo.clone_from(
-          names.repos.get(&exec.repo).unwrap_or(&String::new()),
-        ),
-        Execution::CancelRepoBuild(exec) => exec.repo.clone_from(
-          names.repos.get(&exec.repo).unwrap_or(&String::new()),
-        ),
-        Execution::StartContainer(exec) => exec.server.clone_from(
-          names.servers.get(&exec.server).unwrap_or(&String::new()),
-        ),
-        Execution::RestartContainer(exec) => exec.server.clone_from(
-          names.servers.get(&exec.server).unwrap_or(&String::new()),
-        ),
-        Execution::PauseContainer(exec) => exec.server.clone_from(
-          names.servers.get(&exec.server).unwrap_or(&String::new()),
-        ),
-        Execution::UnpauseContainer(exec) => exec.server.clone_from(
-          names.servers.get(&exec.server).unwrap_or(&String::new()),
-        ),
-        Execution::StopContainer(exec) => exec.server.clone_from(
-          names.servers.get(&exec.server).unwrap_or(&String::new()),
-        ),
-        Execution::DestroyContainer(exec) => exec.server.clone_from(
-          names.servers.get(&exec.server).unwrap_or(&String::new()),
-        ),
         Execution::StartAllContainers(exec) => {
-          exec.server.clone_from(
-            names.servers.get(&exec.server).unwrap_or(&String::new()),
-          )
+          id_to_name(&names.servers, &mut exec.server)
         }
         Execution::RestartAllContainers(exec) => {
-          exec.server.clone_from(
-            names.servers.get(&exec.server).unwrap_or(&String::new()),
-          )
+          id_to_name(&names.servers, &mut exec.server)
         }
         Execution::PauseAllContainers(exec) => {
-          exec.server.clone_from(
-            names.servers.get(&exec.server).unwrap_or(&String::new()),
-          )
+          id_to_name(&names.servers, &mut exec.server)
         }
         Execution::UnpauseAllContainers(exec) => {
-          exec.server.clone_from(
-            names.servers.get(&exec.server).unwrap_or(&String::new()),
-          )
+          id_to_name(&names.servers, &mut exec.server)
+        }
+        Execution::StopAllContainers(exec) => {
+          id_to_name(&names.servers, &mut exec.server)
+        }
+        Execution::PruneContainers(exec) => {
+          id_to_name(&names.servers, &mut exec.server)
+        }
+        Execution::DeleteNetwork(exec) => {
+          id_to_name(&names.servers, &mut exec.server)
+        }
+        Execution::PruneNetworks(exec) => {
+          id_to_name(&names.servers, &mut exec.server)
+        }
+        Execution::DeleteImage(exec) => {
+          id_to_name(&names.servers, &mut exec.server)
+        }
+        Execution::PruneImages(exec) => {
+          id_to_name(&names.servers, &mut exec.server)
+        }
+        Execution::DeleteVolume(exec) => {
+          id_to_name(&names.servers, &mut exec.server)
+        }
+        Execution::PruneVolumes(exec) => {
+          id_to_name(&names.servers, &mut exec.server)
         }
-        Execution::StopAllContainers(exec) => exec.server.clone_from(
-          names.servers.get(&exec.server).unwrap_or(&String::new()),
-        ),
-        Execution::PruneContainers(exec) => exec.server.clone_from(
-          names.servers.get(&exec.server).unwrap_or(&String::new()),
-        ),
-        Execution::DeleteNetwork(exec) => exec.server.clone_from(
-          names.servers.get(&exec.server).unwrap_or(&String::new()),
-        ),
-        Execution::PruneNetworks(exec) => exec.server.clone_from(
-          names.servers.get(&exec.server).unwrap_or(&String::new()),
-        ),
-        Execution::DeleteImage(exec) => exec.server.clone_from(
-          names.servers.get(&exec.server).unwrap_or(&String::new()),
-        ),
-        Execution::PruneImages(exec) => exec.server.clone_from(
-          names.servers.get(&exec.server).unwrap_or(&String::new()),
-        ),
-        Execution::DeleteVolume(exec) => exec.server.clone_from(
-          names.servers.get(&exec.server).unwrap_or(&String::new()),
-        ),
-        Execution::PruneVolumes(exec) => exec.server.clone_from(
-          names.servers.get(&exec.server).unwrap_or(&String::new()),
-        ),
         Execution::PruneDockerBuilders(exec) => {
-          exec.server.clone_from(
-            names.servers.get(&exec.server).unwrap_or(&String::new()),
-          )
+          id_to_name(&names.servers, &mut exec.server)
+        }
+        Execution::PruneBuildx(exec) => {
+          id_to_name(&names.servers, &mut exec.server)
+        }
+        Execution::PruneSystem(exec) => {
+          id_to_name(&names.servers, &mut exec.server)
+        }
+        Execution::RunSync(exec) => {
+          id_to_name(&names.syncs, &mut exec.sync)
+        }
+        Execution::DeployStack(exec) => {
+          id_to_name(&names.stacks, &mut exec.stack)
+        }
+        Execution::StartStack(exec) => {
+          id_to_name(&names.stacks, &mut exec.stack)
+        }
+        Execution::RestartStack(exec) => {
+          id_to_name(&names.stacks, &mut exec.stack)
+        }
+        Execution::PauseStack(exec) => {
+          id_to_name(&names.stacks, &mut exec.stack)
+        }
+        Execution::UnpauseStack(exec) => {
+          id_to_name(&names.stacks, &mut exec.stack)
+        }
+        Execution::StopStack(exec) => {
+          id_to_name(&names.stacks, &mut exec.stack)
+        }
+        Execution::DestroyStack(exec) => {
+          id_to_name(&names.stacks, &mut exec.stack)
         }
-        Execution::PruneBuildx(exec) => exec.server.clone_from(
-          names.servers.get(&exec.server).unwrap_or(&String::new()),
-        ),
-        Execution::PruneSystem(exec) => exec.server.clone_from(
-          names.servers.get(&exec.server).unwrap_or(&String::new()),
-        ),
-        Execution::RunSync(exec) => exec.sync.clone_from(
-          names.syncs.get(&exec.sync).unwrap_or(&String::new()),
-        ),
-        Execution::DeployStack(exec) => exec.stack.clone_from(
-          names.stacks.get(&exec.stack).unwrap_or(&String::new()),
-        ),
-        Execution::StartStack(exec) => exec.stack.clone_from(
-          names.stacks.get(&exec.stack).unwrap_or(&String::new()),
-        ),
-        Execution::RestartStack(exec) => exec.stack.clone_from(
-          names.stacks.get(&exec.stack).unwrap_or(&String::new()),
-        ),
-        Execution::PauseStack(exec) => exec.stack.clone_from(
-          names.stacks.get(&exec.stack).unwrap_or(&String::new()),
-        ),
-        Execution::UnpauseStack(exec) => exec.stack.clone_from(
-          names.stacks.get(&exec.stack).unwrap_or(&String::new()),
-        ),
-        Execution::StopStack(exec) => exec.stack.clone_from(
-          names.stacks.get(&exec.stack).unwrap_or(&String::new()),
-        ),
-        Execution::DestroyStack(exec) => exec.stack.clone_from(
-          names.stacks.get(&exec.stack).unwrap_or(&String::new()),
-        ),
         Execution::Sleep(_) | Execution::None(_) => {}
       }
     }
+    match &mut stage.condition {
+      StageCondition::None => {}
+      StageCondition::BuildNewVersion { build } => {
+        id_to_name(&names.builds, build)
+      }
+      StageCondition::ServerState { server, .. } => {
+        id_to_name(&names.servers, server)
+      }
+      StageCondition::DeploymentState { deployment, .. } => {
+        id_to_name(&names.deployments, deployment)
+      }
+      StageCondition::StackState { stack, .. } => {
+        id_to_name(&names.stacks, stack)
+      }
+    }
   }
 
   res
@@ -744,6 +716,13 @@ async fn add_user_groups(
         .filter_map(|user_id| usernames.get(&user_id).cloned())
         .collect(),
       all: ug.all,
+      tags: ug
+        .tags
+        .into_iter()
+        .filter_map(|(tag_id, level)| {
+          names.tags.get(&tag_id).map(|tag| (tag.clone(), level))
+        })
+        .collect(),
       permissions,
     });
   }
@@ -1035,3 +1014,12 @@ fn serialize_resources_toml(
 
   Ok(res)
 }
+
+/// Replaces the id with the resource name. `[[arg]]` templates
+/// are passed through, other unknown ids are cleared.
+fn id_to_name(names: &HashMap<String, String>, id: &mut String) {
+  if id.contains("[[") {
+    return;
+  }
+  *id = names.get(id.as_str()).cloned().unwrap_or_default();
+}
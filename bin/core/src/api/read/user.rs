@@ -34,6 +34,7 @@ impl Resolve<GetUsername, User> for State {
     let avatar = match user.config {
       UserConfig::Github { avatar, .. } => Some(avatar),
       UserConfig::Google { avatar, .. } => Some(avatar),
+      UserConfig::Oidc { avatar, .. } => Some(avatar),
       _ => None,
     };
 
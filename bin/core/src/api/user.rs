@@ -5,12 +5,19 @@ use axum::{middleware, routing::post, Extension, Json, Router};
 use axum_extra::{headers::ContentType, TypedHeader};
 use komodo_client::{
   api::user::{
+    BeginTotpEnrollment, BeginTotpEnrollmentResponse,
+    ConfirmTotpEnrollment, ConfirmTotpEnrollmentResponse,
     CreateApiKey, CreateApiKeyResponse, DeleteApiKey,
-    DeleteApiKeyResponse, PushRecentlyViewed,
-    PushRecentlyViewedResponse, SetLastSeenUpdate,
-    SetLastSeenUpdateResponse,
+    DeleteApiKeyResponse, DisableTotp, DisableTotpResponse,
+    PushRecentlyViewed, PushRecentlyViewedResponse,
+    SetLastSeenUpdate, SetLastSeenUpdateResponse,
+  },
+  entities::{
+    api_key::ApiKey,
+    komodo_timestamp,
+    user::{User, UserConfig},
+    ResourceTarget,
   },
-  entities::{api_key::ApiKey, komodo_timestamp, user::User},
 };
 use mongo_indexed::doc;
 use mungos::{by_id::update_one_by_id, mongodb::bson::to_bson};
@@ -20,8 +27,11 @@ use typeshare::typeshare;
 use uuid::Uuid;
 
 use crate::{
-  auth::auth_request,
-  helpers::{query::get_user, random_string},
+  auth::{totp, user_auth_request},
+  helpers::{
+    query::{get_tag, get_target_with_id, get_user},
+    random_string,
+  },
   state::{db_client, State},
 };
 
@@ -35,12 +45,15 @@ enum UserRequest {
   SetLastSeenUpdate(SetLastSeenUpdate),
   CreateApiKey(CreateApiKey),
   DeleteApiKey(DeleteApiKey),
+  BeginTotpEnrollment(BeginTotpEnrollment),
+  ConfirmTotpEnrollment(ConfirmTotpEnrollment),
+  DisableTotp(DisableTotp),
 }
 
 pub fn router() -> Router {
   Router::new()
     .route("/", post(handler))
-    .layer(middleware::from_fn(auth_request))
+    .layer(middleware::from_fn(user_auth_request))
 }
 
 #[instrument(name = "UserHandler", level = "debug", skip(user))]
@@ -153,11 +166,25 @@ impl Resolve<CreateApiKey, User> for State {
   )]
   async fn resolve(
     &self,
-    CreateApiKey { name, expires }: CreateApiKey,
+    CreateApiKey {
+      name,
+      expires,
+      mut scope,
+    }: CreateApiKey,
     user: User,
   ) -> anyhow::Result<CreateApiKeyResponse> {
     let user = get_user(&user.id).await?;
 
+    for target in &mut scope.resources {
+      if let ResourceTarget::System(_) = target {
+        return Err(anyhow!("api key scope cannot target System"));
+      }
+      *target = get_target_with_id(target).await?;
+    }
+    for tag in &mut scope.tags {
+      *tag = get_tag(tag).await?.id;
+    }
+
     let key = format!("K-{}", random_string(SECRET_LENGTH));
     let secret = format!("S-{}", random_string(SECRET_LENGTH));
     let secret_hash = bcrypt::hash(&secret, BCRYPT_COST)
@@ -170,6 +197,7 @@ impl Resolve<CreateApiKey, User> for State {
       user_id: user.id.clone(),
       created_at: komodo_timestamp(),
       expires,
+      scope,
     };
     db_client()
       .await
@@ -210,3 +238,115 @@ impl Resolve<DeleteApiKey, User> for State {
     Ok(DeleteApiKeyResponse {})
   }
 }
+
+impl Resolve<BeginTotpEnrollment, User> for State {
+  #[instrument(name = "BeginTotpEnrollment", skip(self, user))]
+  async fn resolve(
+    &self,
+    BeginTotpEnrollment {}: BeginTotpEnrollment,
+    user: User,
+  ) -> anyhow::Result<BeginTotpEnrollmentResponse> {
+    let UserConfig::Local { totp_enabled, .. } = &user.config else {
+      return Err(anyhow!(
+        "Two-factor authentication is only available for local users"
+      ));
+    };
+    if *totp_enabled {
+      return Err(anyhow!(
+        "Two-factor authentication is already enabled. Disable it first to enroll again."
+      ));
+    }
+    let secret = totp::generate_secret();
+    update_one_by_id(
+      &db_client().await.users,
+      &user.id,
+      mungos::update::Update::Set(doc! {
+        "config.data.totp_secret": &secret,
+      }),
+      None,
+    )
+    .await
+    .context("failed to store totp secret")?;
+    let uri = totp::provisioning_uri(&secret, &user.username);
+    Ok(BeginTotpEnrollmentResponse { secret, uri })
+  }
+}
+
+impl Resolve<ConfirmTotpEnrollment, User> for State {
+  #[instrument(name = "ConfirmTotpEnrollment", skip_all)]
+  async fn resolve(
+    &self,
+    ConfirmTotpEnrollment { code }: ConfirmTotpEnrollment,
+    user: User,
+  ) -> anyhow::Result<ConfirmTotpEnrollmentResponse> {
+    let UserConfig::Local {
+      totp_enabled,
+      totp_secret,
+      ..
+    } = &user.config
+    else {
+      return Err(anyhow!(
+        "Two-factor authentication is only available for local users"
+      ));
+    };
+    if *totp_enabled {
+      return Err(anyhow!(
+        "Two-factor authentication is already enabled"
+      ));
+    }
+    if totp_secret.is_empty() {
+      return Err(anyhow!("Must begin enrollment first"));
+    }
+    let Some(step) = totp::verify_code(totp_secret, &code)? else {
+      return Err(anyhow!("invalid code"));
+    };
+    let (recovery_codes, hashes) = totp::generate_recovery_codes();
+    update_one_by_id(
+      &db_client().await.users,
+      &user.id,
+      mungos::update::Update::Set(doc! {
+        "config.data.totp_enabled": true,
+        "config.data.totp_recovery_codes": hashes,
+        "config.data.totp_last_step": step,
+      }),
+      None,
+    )
+    .await
+    .context("failed to enable totp")?;
+    Ok(ConfirmTotpEnrollmentResponse { recovery_codes })
+  }
+}
+
+impl Resolve<DisableTotp, User> for State {
+  #[instrument(name = "DisableTotp", skip_all)]
+  async fn resolve(
+    &self,
+    DisableTotp { code }: DisableTotp,
+    user: User,
+  ) -> anyhow::Result<DisableTotpResponse> {
+    let UserConfig::Local { totp_enabled, .. } = &user.config else {
+      return Err(anyhow!(
+        "Two-factor authentication is only available for local users"
+      ));
+    };
+    if !*totp_enabled {
+      return Err(anyhow!(
+        "Two-factor authentication is not enabled"
+      ));
+    }
+    totp::verify_user_code(&user, &code).await?;
+    update_one_by_id(
+      &db_client().await.users,
+      &user.id,
+      mungos::update::Update::Set(doc! {
+        "config.data.totp_enabled": false,
+        "config.data.totp_secret": "",
+        "config.data.totp_recovery_codes": [],
+      }),
+      None,
+    )
+    .await
+    .context("failed to disable totp")?;
+    Ok(DisableTotpResponse {})
+  }
+}
@@ -18,7 +18,7 @@ use resolver_api::Resolve;
 
 use crate::{
   config::core_config,
-  helpers::{git_token, random_string},
+  helpers::{git_token, random_string, webhook::GitWebhookClient},
   resource,
   state::{db_client, github_client, State},
 };
@@ -147,6 +147,7 @@ impl Resolve<RefreshBuildCache, User> for State {
       built_message: build.info.built_message,
       latest_hash,
       latest_message,
+      latest_scan: build.info.latest_scan,
     };
 
     let info = to_document(&info)
@@ -179,12 +180,6 @@ impl Resolve<CreateBuildWebhook, User> for State {
     CreateBuildWebhook { build }: CreateBuildWebhook,
     user: User,
   ) -> anyhow::Result<CreateBuildWebhookResponse> {
-    let Some(github) = github_client() else {
-      return Err(anyhow!(
-        "github_webhook_app is not configured in core config toml"
-      ));
-    };
-
     let build = resource::get_check_permissions::<Build>(
       &build,
       &user,
@@ -192,6 +187,49 @@ impl Resolve<CreateBuildWebhook, User> for State {
     )
     .await?;
 
+    if let Some(client) = GitWebhookClient::new(
+      &build.config.git_provider,
+      &build.config.git_account,
+    )
+    .await?
+    {
+      let path = format!("build/{}", build.id);
+      let webhook_secret = if build.config.webhook_secret.is_empty() {
+        &core_config().webhook_secret
+      } else {
+        &build.config.webhook_secret
+      };
+      client
+        .create_webhook(
+          &build.config.repo,
+          &client.listener_url(&path),
+          webhook_secret,
+        )
+        .await?;
+      if !build.config.webhook_enabled {
+        self
+          .resolve(
+            UpdateBuild {
+              id: build.id,
+              config: PartialBuildConfig {
+                webhook_enabled: Some(true),
+                ..Default::default()
+              },
+            },
+            user,
+          )
+          .await
+          .context("failed to update build to enable webhook")?;
+      }
+      return Ok(NoData {});
+    }
+
+    let Some(github) = github_client() else {
+      return Err(anyhow!(
+        "github_webhook_app is not configured in core config toml"
+      ));
+    };
+
     if build.config.repo.is_empty() {
       return Err(anyhow!(
         "No repo configured, can't create webhook"
@@ -287,12 +325,6 @@ impl Resolve<DeleteBuildWebhook, User> for State {
     DeleteBuildWebhook { build }: DeleteBuildWebhook,
     user: User,
   ) -> anyhow::Result<DeleteBuildWebhookResponse> {
-    let Some(github) = github_client() else {
-      return Err(anyhow!(
-        "github_webhook_app is not configured in core config toml"
-      ));
-    };
-
     let build = resource::get_check_permissions::<Build>(
       &build,
       &user,
@@ -300,6 +332,28 @@ impl Resolve<DeleteBuildWebhook, User> for State {
     )
     .await?;
 
+    if let Some(client) = GitWebhookClient::new(
+      &build.config.git_provider,
+      &build.config.git_account,
+    )
+    .await?
+    {
+      let path = format!("build/{}", build.id);
+      client
+        .delete_webhook(
+          &build.config.repo,
+          &client.listener_url(&path),
+        )
+        .await?;
+      return Ok(NoData {});
+    }
+
+    let Some(github) = github_client() else {
+      return Err(anyhow!(
+        "github_webhook_app is not configured in core config toml"
+      ));
+    };
+
     if build.config.git_provider != "github.com" {
       return Err(anyhow!(
         "Can only manage github.com repo webhooks"
@@ -4,14 +4,20 @@ use anyhow::{anyhow, Context};
 use axum::{middleware, routing::post, Extension, Router};
 use axum_extra::{headers::ContentType, TypedHeader};
 use derive_variants::{EnumVariants, ExtractVariant};
-use komodo_client::{api::write::*, entities::user::User};
+use komodo_client::{
+  api::write::*,
+  entities::{api_key::ApiKeyRequestType, user::User},
+};
 use resolver_api::{derive::Resolver, Resolver};
 use serde::{Deserialize, Serialize};
 use serror::Json;
 use typeshare::typeshare;
 use uuid::Uuid;
 
-use crate::{auth::auth_request, state::State};
+use crate::{
+  auth::{auth_request, with_api_key_scope, RequestApiKeyScope},
+  state::State,
+};
 
 mod alerter;
 mod build;
@@ -25,6 +31,7 @@ mod repo;
 mod server;
 mod server_template;
 mod service_user;
+mod silence;
 mod stack;
 mod sync;
 mod tag;
@@ -57,6 +64,7 @@ pub enum WriteRequest {
   // ==== PERMISSIONS ====
   UpdateUserBasePermissions(UpdateUserBasePermissions),
   UpdatePermissionOnResourceType(UpdatePermissionOnResourceType),
+  UpdatePermissionOnTag(UpdatePermissionOnTag),
   UpdatePermissionOnTarget(UpdatePermissionOnTarget),
 
   // ==== DESCRIPTION ====
@@ -150,6 +158,11 @@ pub enum WriteRequest {
   UpdateVariableIsSecret(UpdateVariableIsSecret),
   DeleteVariable(DeleteVariable),
 
+  // ==== SILENCE ====
+  CreateSilence(CreateSilence),
+  UpdateSilence(UpdateSilence),
+  DeleteSilence(DeleteSilence),
+
   // ==== PROVIDERS ====
   CreateGitProviderAccount(CreateGitProviderAccount),
   UpdateGitProviderAccount(UpdateGitProviderAccount),
@@ -160,20 +173,27 @@ pub enum WriteRequest {
 }
 
 pub fn router() -> Router {
-  Router::new()
-    .route("/", post(handler))
-    .layer(middleware::from_fn(auth_request))
+  Router::new().route("/", post(handler)).layer(
+    middleware::from_fn_with_state(
+      ApiKeyRequestType::Write,
+      auth_request,
+    ),
+  )
 }
 
 async fn handler(
   Extension(user): Extension<User>,
+  Extension(scope): Extension<RequestApiKeyScope>,
   Json(request): Json<WriteRequest>,
 ) -> serror::Result<(TypedHeader<ContentType>, String)> {
   let req_id = Uuid::new_v4();
 
-  let res = tokio::spawn(task(req_id, request, user))
-    .await
-    .context("failure in spawned task");
+  let res = tokio::spawn(with_api_key_scope(
+    scope,
+    task(req_id, request, user),
+  ))
+  .await
+  .context("failure in spawned task");
 
   if let Err(e) = &res {
     warn!("/write request {req_id} spawn error: {e:#}");
@@ -4,7 +4,8 @@ use anyhow::{anyhow, Context};
 use komodo_client::{
   api::write::{
     UpdatePermissionOnResourceType,
-    UpdatePermissionOnResourceTypeResponse, UpdatePermissionOnTarget,
+    UpdatePermissionOnResourceTypeResponse, UpdatePermissionOnTag,
+    UpdatePermissionOnTagResponse, UpdatePermissionOnTarget,
     UpdatePermissionOnTargetResponse, UpdateUserBasePermissions,
     UpdateUserBasePermissionsResponse,
   },
@@ -24,7 +25,7 @@ use mungos::{
 use resolver_api::Resolve;
 
 use crate::{
-  helpers::query::get_user,
+  helpers::query::{get_tag, get_user},
   state::{db_client, State},
 };
 
@@ -143,6 +144,72 @@ impl Resolve<UpdatePermissionOnResourceType, User> for State {
   }
 }
 
+impl Resolve<UpdatePermissionOnTag, User> for State {
+  #[instrument(name = "UpdatePermissionOnTag", skip(self, admin))]
+  async fn resolve(
+    &self,
+    UpdatePermissionOnTag {
+      user_target,
+      tag,
+      permission,
+    }: UpdatePermissionOnTag,
+    admin: User,
+  ) -> anyhow::Result<UpdatePermissionOnTagResponse> {
+    if !admin.admin {
+      return Err(anyhow!("this method is admin only"));
+    }
+
+    // Some extra checks if user target is an actual User
+    if let UserTarget::User(user_id) = &user_target {
+      let user = get_user(user_id).await?;
+      if user.admin {
+        return Err(anyhow!(
+          "cannot use this method to update other admins permissions"
+        ));
+      }
+      if !user.enabled {
+        return Err(anyhow!("user not enabled"));
+      }
+    }
+
+    let tag = get_tag(&tag).await?;
+
+    let (user_target_variant, user_target_id) =
+      extract_user_target_with_validation(&user_target).await?;
+
+    let id = ObjectId::from_str(&user_target_id)
+      .context("id is not ObjectId")?;
+    let field = format!("tags.{}", tag.id);
+    let filter = doc! { "_id": id };
+    let update = doc! { "$set": { &field: permission.as_ref() } };
+
+    match user_target_variant {
+      UserTargetVariant::User => {
+        db_client()
+          .await
+          .users
+          .update_one(filter, update)
+          .await
+          .with_context(|| {
+            format!("failed to set {field}: {permission} on db")
+          })?;
+      }
+      UserTargetVariant::UserGroup => {
+        db_client()
+          .await
+          .user_groups
+          .update_one(filter, update)
+          .await
+          .with_context(|| {
+            format!("failed to set {field}: {permission} on db")
+          })?;
+      }
+    }
+
+    Ok(UpdatePermissionOnTagResponse {})
+  }
+}
+
 impl Resolve<UpdatePermissionOnTarget, User> for State {
   #[instrument(name = "UpdatePermissionOnTarget", skip(self, admin))]
   async fn resolve(
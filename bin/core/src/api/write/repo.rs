@@ -18,7 +18,7 @@ use resolver_api::Resolve;
 
 use crate::{
   config::core_config,
-  helpers::{git_token, random_string},
+  helpers::{git_token, random_string, webhook::GitWebhookClient},
   resource,
   state::{db_client, github_client, State},
 };
@@ -180,12 +180,6 @@ impl Resolve<CreateRepoWebhook, User> for State {
     CreateRepoWebhook { repo, action }: CreateRepoWebhook,
     user: User,
   ) -> anyhow::Result<CreateRepoWebhookResponse> {
-    let Some(github) = github_client() else {
-      return Err(anyhow!(
-        "github_webhook_app is not configured in core config toml"
-      ));
-    };
-
     let repo = resource::get_check_permissions::<Repo>(
       &repo,
       &user,
@@ -193,6 +187,59 @@ impl Resolve<CreateRepoWebhook, User> for State {
     )
     .await?;
 
+    if let Some(client) = GitWebhookClient::new(
+      &repo.config.git_provider,
+      &repo.config.git_account,
+    )
+    .await?
+    {
+      let path = match action {
+        RepoWebhookAction::Clone => {
+          format!("repo/{}/clone", repo.id)
+        }
+        RepoWebhookAction::Pull => {
+          format!("repo/{}/pull", repo.id)
+        }
+        RepoWebhookAction::Build => {
+          format!("repo/{}/build", repo.id)
+        }
+      };
+      let webhook_secret = if repo.config.webhook_secret.is_empty() {
+        &core_config().webhook_secret
+      } else {
+        &repo.config.webhook_secret
+      };
+      client
+        .create_webhook(
+          &repo.config.repo,
+          &client.listener_url(&path),
+          webhook_secret,
+        )
+        .await?;
+      if !repo.config.webhook_enabled {
+        self
+          .resolve(
+            UpdateRepo {
+              id: repo.id,
+              config: PartialRepoConfig {
+                webhook_enabled: Some(true),
+                ..Default::default()
+              },
+            },
+            user,
+          )
+          .await
+          .context("failed to update repo to enable webhook")?;
+      }
+      return Ok(NoData {});
+    }
+
+    let Some(github) = github_client() else {
+      return Err(anyhow!(
+        "github_webhook_app is not configured in core config toml"
+      ));
+    };
+
     if repo.config.repo.is_empty() {
       return Err(anyhow!(
         "No repo configured, can't create webhook"
@@ -298,12 +345,6 @@ impl Resolve<DeleteRepoWebhook, User> for State {
     DeleteRepoWebhook { repo, action }: DeleteRepoWebhook,
     user: User,
   ) -> anyhow::Result<DeleteRepoWebhookResponse> {
-    let Some(github) = github_client() else {
-      return Err(anyhow!(
-        "github_webhook_app is not configured in core config toml"
-      ));
-    };
-
     let repo = resource::get_check_permissions::<Repo>(
       &repo,
       &user,
@@ -311,6 +352,38 @@ impl Resolve<DeleteRepoWebhook, User> for State {
     )
     .await?;
 
+    if let Some(client) = GitWebhookClient::new(
+      &repo.config.git_provider,
+      &repo.config.git_account,
+    )
+    .await?
+    {
+      let path = match action {
+        RepoWebhookAction::Clone => {
+          format!("repo/{}/clone", repo.id)
+        }
+        RepoWebhookAction::Pull => {
+          format!("repo/{}/pull", repo.id)
+        }
+        RepoWebhookAction::Build => {
+          format!("repo/{}/build", repo.id)
+        }
+      };
+      client
+        .delete_webhook(
+          &repo.config.repo,
+          &client.listener_url(&path),
+        )
+        .await?;
+      return Ok(NoData {});
+    }
+
+    let Some(github) = github_client() else {
+      return Err(anyhow!(
+        "github_webhook_app is not configured in core config toml"
+      ));
+    };
+
     if repo.config.git_provider != "github.com" {
       return Err(anyhow!(
         "Can only manage github.com repo webhooks"
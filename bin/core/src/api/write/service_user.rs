@@ -53,6 +53,7 @@ impl Resolve<CreateServiceUser, User> for State {
       last_update_view: 0,
       recents: Default::default(),
       all: Default::default(),
+      tags: Default::default(),
       updated_at: komodo_timestamp(),
     };
     user.id = db_client()
@@ -118,6 +119,7 @@ impl Resolve<CreateApiKeyForServiceUser, User> for State {
       user_id,
       name,
       expires,
+      scope,
     }: CreateApiKeyForServiceUser,
     user: User,
   ) -> anyhow::Result<CreateApiKeyForServiceUserResponse> {
@@ -133,7 +135,14 @@ impl Resolve<CreateApiKeyForServiceUser, User> for State {
       return Err(anyhow!("user is not service user"));
     };
     self
-      .resolve(CreateApiKey { name, expires }, service_user)
+      .resolve(
+        CreateApiKey {
+          name,
+          expires,
+          scope,
+        },
+        service_user,
+      )
       .await
   }
 }
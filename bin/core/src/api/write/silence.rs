@@ -0,0 +1,195 @@
+use anyhow::{anyhow, Context};
+use futures::future::join_all;
+use komodo_client::{
+  api::write::{
+    CreateSilence, CreateSilenceResponse, DeleteSilence,
+    DeleteSilenceResponse, UpdateSilence, UpdateSilenceResponse,
+  },
+  entities::{
+    komodo_timestamp, silence::Silence, user::User, Operation,
+    ResourceTarget,
+  },
+};
+use mungos::{
+  by_id::{delete_one_by_id, update_one_by_id},
+  mongodb::bson::{doc, to_document},
+};
+use resolver_api::Resolve;
+
+use crate::{
+  helpers::{
+    query::{get_silence, get_tag},
+    update::{add_update, make_update},
+  },
+  state::{db_client, State},
+};
+
+impl Resolve<CreateSilence, User> for State {
+  #[instrument(name = "CreateSilence", skip(self, user))]
+  async fn resolve(
+    &self,
+    CreateSilence {
+      reason,
+      start_ts,
+      end_ts,
+      resources,
+      tags,
+      alert_types,
+    }: CreateSilence,
+    user: User,
+  ) -> anyhow::Result<CreateSilenceResponse> {
+    if !user.admin {
+      return Err(anyhow!("only admins can create silences"));
+    }
+
+    let mut silence = Silence {
+      id: Default::default(),
+      reason,
+      start_ts,
+      end_ts,
+      resources,
+      tags: get_tag_ids(tags).await?,
+      alert_types,
+      created_by: user.id.clone(),
+      created_at: komodo_timestamp(),
+    };
+    validate_silence(&silence)?;
+
+    silence.id = db_client()
+      .await
+      .silences
+      .insert_one(&silence)
+      .await
+      .context("failed to create silence on db")?
+      .inserted_id
+      .as_object_id()
+      .context("inserted_id is not ObjectId")?
+      .to_string();
+
+    let mut update = make_update(
+      ResourceTarget::system(),
+      Operation::CreateSilence,
+      &user,
+    );
+
+    update.push_simple_log("create silence", format!("{silence:#?}"));
+    update.finalize();
+
+    add_update(update).await?;
+
+    Ok(silence)
+  }
+}
+
+impl Resolve<UpdateSilence, User> for State {
+  #[instrument(name = "UpdateSilence", skip(self, user))]
+  async fn resolve(
+    &self,
+    UpdateSilence {
+      id,
+      reason,
+      start_ts,
+      end_ts,
+      resources,
+      tags,
+      alert_types,
+    }: UpdateSilence,
+    user: User,
+  ) -> anyhow::Result<UpdateSilenceResponse> {
+    if !user.admin {
+      return Err(anyhow!("only admins can update silences"));
+    }
+
+    let original = get_silence(&id).await?;
+
+    let silence = Silence {
+      reason,
+      start_ts,
+      end_ts,
+      resources,
+      tags: get_tag_ids(tags).await?,
+      alert_types,
+      ..original.clone()
+    };
+    validate_silence(&silence)?;
+
+    let mut set = to_document(&silence)
+      .context("failed to serialize silence to bson")?;
+    set.remove("_id");
+
+    update_one_by_id(
+      &db_client().await.silences,
+      &id,
+      doc! { "$set": set },
+      None,
+    )
+    .await
+    .context("failed to update silence on db")?;
+
+    let mut update = make_update(
+      ResourceTarget::system(),
+      Operation::UpdateSilence,
+      &user,
+    );
+
+    update.push_simple_log(
+      "update silence",
+      format!("from: {original:#?}\n\nto: {silence:#?}"),
+    );
+    update.finalize();
+
+    add_update(update).await?;
+
+    Ok(silence)
+  }
+}
+
+impl Resolve<DeleteSilence, User> for State {
+  #[instrument(name = "DeleteSilence", skip(self, user))]
+  async fn resolve(
+    &self,
+    DeleteSilence { id }: DeleteSilence,
+    user: User,
+  ) -> anyhow::Result<DeleteSilenceResponse> {
+    if !user.admin {
+      return Err(anyhow!("only admins can delete silences"));
+    }
+
+    let silence = get_silence(&id).await?;
+
+    delete_one_by_id(&db_client().await.silences, &id, None)
+      .await
+      .context("failed to delete silence on db")?;
+
+    let mut update = make_update(
+      ResourceTarget::system(),
+      Operation::DeleteSilence,
+      &user,
+    );
+
+    update.push_simple_log("delete silence", format!("{silence:#?}"));
+    update.finalize();
+
+    add_update(update).await?;
+
+    Ok(silence)
+  }
+}
+
+fn validate_silence(silence: &Silence) -> anyhow::Result<()> {
+  if silence.end_ts <= silence.start_ts {
+    return Err(anyhow!("silence end_ts must be after start_ts"));
+  }
+  Ok(())
+}
+
+/// Tags can be passed by id or name, but are stored by id.
+async fn get_tag_ids(
+  tags: Vec<String>,
+) -> anyhow::Result<Vec<String>> {
+  join_all(tags.iter().map(|tag| get_tag(tag)))
+    .await
+    .into_iter()
+    .map(|tag| tag.map(|tag| tag.id))
+    .collect()
+}
@@ -35,6 +35,7 @@ use crate::{
       services::extract_services_into_res,
     },
     update::{add_update, make_update},
+    webhook::GitWebhookClient,
   },
   resource,
   state::{db_client, github_client, State},
@@ -318,12 +319,6 @@ impl Resolve<CreateStackWebhook, User> for State {
     CreateStackWebhook { stack, action }: CreateStackWebhook,
     user: User,
   ) -> anyhow::Result<CreateStackWebhookResponse> {
-    let Some(github) = github_client() else {
-      return Err(anyhow!(
-        "github_webhook_app is not configured in core config toml"
-      ));
-    };
-
     let stack = resource::get_check_permissions::<Stack>(
       &stack,
       &user,
@@ -331,6 +326,56 @@ impl Resolve<CreateStackWebhook, User> for State {
     )
     .await?;
 
+    if let Some(client) = GitWebhookClient::new(
+      &stack.config.git_provider,
+      &stack.config.git_account,
+    )
+    .await?
+    {
+      let path = match action {
+        StackWebhookAction::Refresh => {
+          format!("stack/{}/refresh", stack.id)
+        }
+        StackWebhookAction::Deploy => {
+          format!("stack/{}/deploy", stack.id)
+        }
+      };
+      let webhook_secret = if stack.config.webhook_secret.is_empty() {
+        &core_config().webhook_secret
+      } else {
+        &stack.config.webhook_secret
+      };
+      client
+        .create_webhook(
+          &stack.config.repo,
+          &client.listener_url(&path),
+          webhook_secret,
+        )
+        .await?;
+      if !stack.config.webhook_enabled {
+        self
+          .resolve(
+            UpdateStack {
+              id: stack.id,
+              config: PartialStackConfig {
+                webhook_enabled: Some(true),
+                ..Default::default()
+              },
+            },
+            user,
+          )
+          .await
+          .context("failed to update stack to enable webhook")?;
+      }
+      return Ok(NoData {});
+    }
+
+    let Some(github) = github_client() else {
+      return Err(anyhow!(
+        "github_webhook_app is not configured in core config toml"
+      ));
+    };
+
     if stack.config.repo.is_empty() {
       return Err(anyhow!(
         "No repo configured, can't create webhook"
@@ -433,12 +478,6 @@ impl Resolve<DeleteStackWebhook, User> for State {
     DeleteStackWebhook { stack, action }: DeleteStackWebhook,
     user: User,
   ) -> anyhow::Result<DeleteStackWebhookResponse> {
-    let Some(github) = github_client() else {
-      return Err(anyhow!(
-        "github_webhook_app is not configured in core config toml"
-      ));
-    };
-
     let stack = resource::get_check_permissions::<Stack>(
       &stack,
       &user,
@@ -446,6 +485,35 @@ impl Resolve<DeleteStackWebhook, User> for State {
     )
     .await?;
 
+    if let Some(client) = GitWebhookClient::new(
+      &stack.config.git_provider,
+      &stack.config.git_account,
+    )
+    .await?
+    {
+      let path = match action {
+        StackWebhookAction::Refresh => {
+          format!("stack/{}/refresh", stack.id)
+        }
+        StackWebhookAction::Deploy => {
+          format!("stack/{}/deploy", stack.id)
+        }
+      };
+      client
+        .delete_webhook(
+          &stack.config.repo,
+          &client.listener_url(&path),
+        )
+        .await?;
+      return Ok(NoData {});
+    }
+
+    let Some(github) = github_client() else {
+      return Err(anyhow!(
+        "github_webhook_app is not configured in core config toml"
+      ));
+    };
+
     if stack.config.git_provider != "github.com" {
       return Err(anyhow!(
         "Can only manage github.com repo webhooks"
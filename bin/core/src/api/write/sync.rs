@@ -41,12 +41,13 @@ use resolver_api::Resolve;
 use crate::{
   config::core_config,
   helpers::{
-    alert::send_alerts,
+    alert::{send_alerts, silence_alerts},
     query::get_id_to_tags,
     sync::{
       deploy::SyncDeployParams,
       resource::{get_updates_for_view, AllResourcesById},
     },
+    webhook::GitWebhookClient,
   },
   resource,
   state::{db_client, github_client, State},
@@ -326,7 +327,7 @@ impl Resolve<RefreshResourceSyncPending, User> for State {
       match (existing, has_updates) {
         // OPEN A NEW ALERT
         (None, true) => {
-          let alert = Alert {
+          let mut alert = Alert {
             id: Default::default(),
             ts: komodo_timestamp(),
             resolved: false,
@@ -334,7 +335,9 @@ impl Resolve<RefreshResourceSyncPending, User> for State {
             target: ResourceTarget::ResourceSync(id.clone()),
             data: AlertData::ResourceSyncPendingUpdates { id, name },
             resolved_ts: None,
+            silenced: false,
           };
+          silence_alerts(vec![&mut alert]).await;
           db.alerts
             .insert_one(&alert)
             .await
@@ -377,12 +380,6 @@ impl Resolve<CreateSyncWebhook, User> for State {
     CreateSyncWebhook { sync, action }: CreateSyncWebhook,
     user: User,
   ) -> anyhow::Result<CreateSyncWebhookResponse> {
-    let Some(github) = github_client() else {
-      return Err(anyhow!(
-        "github_webhook_app is not configured in core config toml"
-      ));
-    };
-
     let sync = resource::get_check_permissions::<ResourceSync>(
       &sync,
       &user,
@@ -390,6 +387,56 @@ impl Resolve<CreateSyncWebhook, User> for State {
     )
     .await?;
 
+    if let Some(client) = GitWebhookClient::new(
+      &sync.config.git_provider,
+      &sync.config.git_account,
+    )
+    .await?
+    {
+      let path = match action {
+        SyncWebhookAction::Refresh => {
+          format!("sync/{}/refresh", sync.id)
+        }
+        SyncWebhookAction::Sync => {
+          format!("sync/{}/sync", sync.id)
+        }
+      };
+      let webhook_secret = if sync.config.webhook_secret.is_empty() {
+        &core_config().webhook_secret
+      } else {
+        &sync.config.webhook_secret
+      };
+      client
+        .create_webhook(
+          &sync.config.repo,
+          &client.listener_url(&path),
+          webhook_secret,
+        )
+        .await?;
+      if !sync.config.webhook_enabled {
+        self
+          .resolve(
+            UpdateResourceSync {
+              id: sync.id,
+              config: PartialResourceSyncConfig {
+                webhook_enabled: Some(true),
+                ..Default::default()
+              },
+            },
+            user,
+          )
+          .await
+          .context("failed to update sync to enable webhook")?;
+      }
+      return Ok(NoData {});
+    }
+
+    let Some(github) = github_client() else {
+      return Err(anyhow!(
+        "github_webhook_app is not configured in core config toml"
+      ));
+    };
+
     if sync.config.repo.is_empty() {
       return Err(anyhow!(
         "No repo configured, can't create webhook"
@@ -492,12 +539,6 @@ impl Resolve<DeleteSyncWebhook, User> for State {
     DeleteSyncWebhook { sync, action }: DeleteSyncWebhook,
     user: User,
   ) -> anyhow::Result<DeleteSyncWebhookResponse> {
-    let Some(github) = github_client() else {
-      return Err(anyhow!(
-        "github_webhook_app is not configured in core config toml"
-      ));
-    };
-
     let sync = resource::get_check_permissions::<ResourceSync>(
       &sync,
       &user,
@@ -505,6 +546,35 @@ impl Resolve<DeleteSyncWebhook, User> for State {
     )
     .await?;
 
+    if let Some(client) = GitWebhookClient::new(
+      &sync.config.git_provider,
+      &sync.config.git_account,
+    )
+    .await?
+    {
+      let path = match action {
+        SyncWebhookAction::Refresh => {
+          format!("sync/{}/refresh", sync.id)
+        }
+        SyncWebhookAction::Sync => {
+          format!("sync/{}/sync", sync.id)
+        }
+      };
+      client
+        .delete_webhook(
+          &sync.config.repo,
+          &client.listener_url(&path),
+        )
+        .await?;
+      return Ok(NoData {});
+    }
+
+    let Some(github) = github_client() else {
+      return Err(anyhow!(
+        "github_webhook_app is not configured in core config toml"
+      ));
+    };
+
     if sync.config.git_provider != "github.com" {
       return Err(anyhow!(
         "Can only manage github.com repo webhooks"
@@ -105,6 +105,17 @@ impl Resolve<DeleteTag, User> for State {
       resource::remove_tag_from_all::<ServerTemplate>(&id),
     )?;
 
+    // Remove any permissions given on the tag
+    let db = db_client().await;
+    let field = format!("tags.{}", tag.id);
+    let filter = doc! { &field: { "$exists": true } };
+    let update = doc! { "$unset": { &field: "" } };
+    tokio::try_join!(
+      db.users.update_many(filter.clone(), update.clone()),
+      db.user_groups.update_many(filter, update),
+    )
+    .context("failed to remove tag permissions from users")?;
+
     delete_one_by_id(&db_client().await.tags, &id, None).await?;
 
     Ok(tag)
@@ -30,6 +30,7 @@ impl Resolve<CreateUserGroup, User> for State {
       id: Default::default(),
       users: Default::default(),
       all: Default::default(),
+      tags: Default::default(),
       updated_at: komodo_timestamp(),
       name,
     };
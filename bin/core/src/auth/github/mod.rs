@@ -93,6 +93,7 @@ async fn callback(
         last_update_view: 0,
         recents: Default::default(),
         all: Default::default(),
+        tags: Default::default(),
         config: UserConfig::Github {
           github_id,
           avatar: github_user.avatar_url,
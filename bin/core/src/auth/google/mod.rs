@@ -108,6 +108,7 @@ async fn callback(
         last_update_view: 0,
         recents: Default::default(),
         all: Default::default(),
+        tags: Default::default(),
         config: UserConfig::Google {
           google_id,
           avatar: google_user.picture,
@@ -15,6 +15,10 @@ use tokio::sync::Mutex;
 use crate::helpers::random_string;
 
 type ExchangeTokenMap = Mutex<HashMap<String, (String, u128)>>;
+type TotpTokenMap = Mutex<HashMap<String, TotpLogin>>;
+
+/// Failed code attempts allowed for one TOTP login token.
+const TOTP_TOKEN_MAX_ATTEMPTS: u8 = 5;
 
 #[derive(Serialize, Deserialize)]
 pub struct JwtClaims {
@@ -27,6 +31,15 @@ pub struct JwtClient {
   pub key: Hmac<Sha256>,
   ttl_ms: u128,
   exchange_tokens: ExchangeTokenMap,
+  totp_tokens: TotpTokenMap,
+}
+
+/// A login which passed the password check,
+/// and is waiting for a TOTP code.
+pub struct TotpLogin {
+  pub user_id: String,
+  valid_until: u128,
+  attempts: u8,
 }
 
 impl JwtClient {
@@ -44,6 +57,7 @@ impl JwtClient {
         config.jwt_ttl.to_string().parse()?,
       ),
       exchange_tokens: Default::default(),
+      totp_tokens: Default::default(),
     })
   }
 
@@ -91,4 +105,56 @@ impl JwtClient {
       Err(anyhow!("invalid exchange token: expired"))
     }
   }
+
+  #[instrument(level = "debug", skip_all)]
+  pub async fn create_totp_token(&self, user_id: String) -> String {
+    let totp_token = random_string(40);
+    let ts = unix_timestamp_ms();
+    let mut totp_tokens = self.totp_tokens.lock().await;
+    // Logins which never submit a code are otherwise never removed.
+    totp_tokens.retain(|_, login| ts < login.valid_until);
+    totp_tokens.insert(
+      totp_token.clone(),
+      TotpLogin {
+        user_id,
+        valid_until: ts
+          + get_timelength_in_ms(Timelength::FiveMinutes),
+        attempts: 0,
+      },
+    );
+    totp_token
+  }
+
+  /// Removes the TOTP token. If the code turns out to be invalid,
+  /// give it back with [JwtClient::return_failed_totp_token].
+  #[instrument(level = "debug", skip(self))]
+  pub async fn take_totp_token(
+    &self,
+    totp_token: &str,
+  ) -> anyhow::Result<TotpLogin> {
+    let login = self
+      .totp_tokens
+      .lock()
+      .await
+      .remove(totp_token)
+      .context("invalid totp token: unrecognized")?;
+    if unix_timestamp_ms() < login.valid_until {
+      Ok(login)
+    } else {
+      Err(anyhow!("invalid totp token: expired"))
+    }
+  }
+
+  /// Allows retrying the code, until too many attempts have failed.
+  #[instrument(level = "debug", skip(self, login))]
+  pub async fn return_failed_totp_token(
+    &self,
+    totp_token: String,
+    mut login: TotpLogin,
+  ) {
+    login.attempts += 1;
+    if login.attempts < TOTP_TOKEN_MAX_ATTEMPTS {
+      self.totp_tokens.lock().await.insert(totp_token, login);
+    }
+  }
 }
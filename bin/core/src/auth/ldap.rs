@@ -0,0 +1,359 @@
+use std::{collections::HashMap, sync::OnceLock, time::Duration};
+
+use anyhow::{anyhow, Context};
+use async_timing_util::{unix_timestamp_ms, ONE_MIN_MS};
+use axum::http::HeaderMap;
+use komodo_client::{
+  api::auth::{LoginLdapUser, LoginLdapUserResponse},
+  entities::{
+    config::core::LdapConfig,
+    user::{User, UserConfig},
+  },
+};
+use ldap3::{
+  dn_escape, ldap_escape, Ldap, LdapConnAsync, LdapConnSettings,
+  Scope, SearchEntry,
+};
+use mongo_indexed::Document;
+use mungos::{by_id::update_one_by_id, mongodb::bson::doc};
+use resolver_api::Resolve;
+use tokio::sync::Mutex;
+
+use crate::{
+  config::core_config,
+  state::{db_client, jwt_client, State},
+};
+
+use super::sync_user_groups;
+
+/// Active Directory `userAccountControl` flag for disabled accounts.
+const AD_ACCOUNT_DISABLED: u32 = 0x2;
+/// LDAP result code for searches on a DN which doesn't exist.
+const LDAP_NO_SUCH_OBJECT: u32 = 32;
+/// How often a logged in user's directory account is re-checked.
+const ACCOUNT_CHECK_INTERVAL_MS: u128 = ONE_MIN_MS;
+
+impl Resolve<LoginLdapUser, HeaderMap> for State {
+  #[instrument(
+    name = "LoginLdapUser",
+    level = "debug",
+    skip(self, password)
+  )]
+  async fn resolve(
+    &self,
+    LoginLdapUser { username, password }: LoginLdapUser,
+    _: HeaderMap,
+  ) -> anyhow::Result<LoginLdapUserResponse> {
+    let core_config = core_config();
+    let config = &core_config.ldap;
+
+    if !config.enabled
+      || config.url.is_empty()
+      || config.bind_dn.is_empty()
+    {
+      return Err(anyhow!("ldap auth is not enabled"));
+    }
+
+    // Without these, disabled directory accounts
+    // can't be checked after login.
+    if config.search_base.is_empty()
+      || config.service_bind_dn.is_empty()
+    {
+      return Err(anyhow!(
+        "ldap auth requires ldap.search_base and ldap.service_bind_dn to be configured"
+      ));
+    }
+
+    if username.is_empty() {
+      return Err(anyhow!("Username cannot be empty string"));
+    }
+
+    // An empty password would be an unauthenticated bind,
+    // which many directories accept.
+    if password.is_empty() {
+      return Err(anyhow!("Password cannot be empty string"));
+    }
+
+    let mut ldap = connect(config).await?;
+    let bind_dn =
+      config.bind_dn.replace("{username}", &dn_escape(&username));
+    ldap
+      .simple_bind(&bind_dn, &password)
+      .await
+      .context("failed to bind to ldap")?
+      .success()
+      .context("invalid credentials")?;
+
+    let entry = search_user(&mut ldap, config, &username).await;
+    let _ = ldap.unbind().await;
+    let entry = entry?
+      .context("did not find user entry under ldap search base")?;
+
+    if account_disabled(&entry) {
+      return Err(anyhow!("ldap account is disabled"));
+    }
+
+    // Users are matched by their entry DN, so logging in with
+    // a username differing only in case gives the same user.
+    let db_client = db_client().await;
+    let user = match db_client
+      .users
+      .find_one(doc! {
+        "config.type": "Ldap",
+        "config.data.dn": &entry.dn,
+      })
+      .await
+      .context("failed at db query for users")?
+    {
+      Some(user) => Some(user),
+      None => db_client
+        .users
+        .find_one(doc! { "username": &username })
+        .await
+        .context("failed at db query for users")?,
+    };
+
+    let user_id = match user {
+      Some(user) => {
+        let UserConfig::Ldap { dn } = user.config else {
+          return Err(anyhow!(
+            "username is already taken by a non-ldap user"
+          ));
+        };
+        // A different directory entry with the same username
+        // must not take over the existing user.
+        if dn != entry.dn {
+          return Err(anyhow!(
+            "username is already taken by a different ldap account"
+          ));
+        }
+        user.id
+      }
+      None => {
+        let no_users_exist =
+          db_client.users.find_one(Document::new()).await?.is_none();
+        if !no_users_exist && core_config.disable_user_registration {
+          return Err(anyhow!("User registration is disabled"));
+        }
+        let user = User {
+          id: Default::default(),
+          username,
+          enabled: no_users_exist || core_config.enable_new_users,
+          admin: no_users_exist,
+          create_server_permissions: no_users_exist,
+          create_build_permissions: no_users_exist,
+          updated_at: unix_timestamp_ms() as i64,
+          last_update_view: 0,
+          recents: Default::default(),
+          all: Default::default(),
+          tags: Default::default(),
+          config: UserConfig::Ldap {
+            dn: entry.dn.clone(),
+          },
+        };
+        db_client
+          .users
+          .insert_one(user)
+          .await
+          .context("failed to create user")?
+          .inserted_id
+          .as_object_id()
+          .context("inserted_id is not ObjectId")?
+          .to_string()
+      }
+    };
+
+    if !config.group_attribute.is_empty() {
+      let groups: Vec<_> = attribute(&entry, &config.group_attribute)
+        .map(|values| {
+          values.iter().map(|group| group_name(group)).collect()
+        })
+        .unwrap_or_default();
+      sync_user_groups(&user_id, &groups, &config.managed_groups)
+        .await
+        .context("failed to sync user groups from ldap groups")?;
+    }
+
+    let jwt = jwt_client()
+      .generate(user_id)
+      .context("failed at generating jwt for user")?;
+
+    Ok(LoginLdapUserResponse { jwt })
+  }
+}
+
+/// Checks the user's directory entry still exists and is enabled,
+/// using the configured service account. Disables the Komodo user
+/// only when the entry is confirmed missing or disabled.
+/// Checks are done at most once per [ACCOUNT_CHECK_INTERVAL_MS]
+/// per user. Access is denied, without disabling the user,
+/// whenever the entry can't be checked.
+pub async fn check_directory_account(
+  user: &User,
+) -> anyhow::Result<()> {
+  let config = &core_config().ldap;
+  if !config.enabled
+    || config.search_base.is_empty()
+    || config.service_bind_dn.is_empty()
+  {
+    return Err(anyhow!(
+      "ldap auth is not configured, can't check ldap account"
+    ));
+  }
+
+  let ts = unix_timestamp_ms();
+  if last_checked()
+    .lock()
+    .await
+    .get(&user.id)
+    .is_some_and(|last| ts - last < ACCOUNT_CHECK_INTERVAL_MS)
+  {
+    return Ok(());
+  }
+
+  let account_enabled = directory_account_enabled(config, user)
+    .await
+    .context("failed to check ldap account")?;
+
+  if account_enabled {
+    last_checked().lock().await.insert(user.id.clone(), ts);
+    return Ok(());
+  }
+
+  update_one_by_id(
+    &db_client().await.users,
+    &user.id,
+    mungos::update::Update::Set(doc! {
+      "enabled": false,
+      "updated_at": unix_timestamp_ms() as i64,
+    }),
+    None,
+  )
+  .await
+  .context("failed to disable user")?;
+
+  info!(
+    "disabled user {} | ldap account is missing or disabled",
+    user.username
+  );
+
+  Err(anyhow!("user not enabled"))
+}
+
+fn last_checked() -> &'static Mutex<HashMap<String, u128>> {
+  static LAST_CHECKED: OnceLock<Mutex<HashMap<String, u128>>> =
+    OnceLock::new();
+  LAST_CHECKED.get_or_init(Default::default)
+}
+
+/// Looks up the user's stored entry DN. Returns false only if the
+/// entry no longer exists or is flagged as disabled.
+async fn directory_account_enabled(
+  config: &LdapConfig,
+  user: &User,
+) -> anyhow::Result<bool> {
+  let UserConfig::Ldap { dn } = &user.config else {
+    return Err(anyhow!("user is not an ldap user"));
+  };
+  if dn.is_empty() {
+    return Err(anyhow!("user has no ldap entry dn"));
+  }
+  let mut ldap = connect(config).await?;
+  ldap
+    .simple_bind(
+      &config.service_bind_dn,
+      &config.service_bind_password,
+    )
+    .await
+    .context("failed to bind to ldap")?
+    .success()
+    .context("failed to bind with ldap service account")?;
+  let res = ldap
+    .search(
+      dn,
+      Scope::Base,
+      "(objectClass=*)",
+      vec!["userAccountControl"],
+    )
+    .await
+    .context("failed to search ldap for user entry");
+  let _ = ldap.unbind().await;
+  let res = res?;
+  if res.1.rc == LDAP_NO_SUCH_OBJECT {
+    return Ok(false);
+  }
+  let (entries, _) =
+    res.success().context("ldap user entry search failed")?;
+  let entry = entries
+    .into_iter()
+    .next()
+    .map(SearchEntry::construct)
+    .context("ldap user entry search returned no entry")?;
+  Ok(!account_disabled(&entry))
+}
+
+fn account_disabled(entry: &SearchEntry) -> bool {
+  attribute(entry, "userAccountControl")
+    .and_then(|values| values.first())
+    .and_then(|value| value.parse::<u32>().ok())
+    .is_some_and(|flags| flags & AD_ACCOUNT_DISABLED != 0)
+}
+
+async fn connect(config: &LdapConfig) -> anyhow::Result<Ldap> {
+  let settings = LdapConnSettings::new()
+    .set_conn_timeout(Duration::from_secs(10))
+    .set_starttls(config.starttls)
+    .set_no_tls_verify(config.tls_skip_verify);
+  let (conn, ldap) =
+    LdapConnAsync::with_settings(settings, &config.url)
+      .await
+      .context("failed to connect to ldap")?;
+  ldap3::drive!(conn);
+  Ok(ldap)
+}
+
+async fn search_user(
+  ldap: &mut Ldap,
+  config: &LdapConfig,
+  username: &str,
+) -> anyhow::Result<Option<SearchEntry>> {
+  let filter = config
+    .user_filter
+    .replace("{username}", &ldap_escape(username));
+  let mut attrs = vec!["userAccountControl"];
+  if !config.group_attribute.is_empty() {
+    attrs.push(&config.group_attribute);
+  }
+  let (entries, _) = ldap
+    .search(&config.search_base, Scope::Subtree, &filter, attrs)
+    .await
+    .context("failed to search ldap for user")?
+    .success()
+    .context("ldap user search failed")?;
+  Ok(entries.into_iter().next().map(SearchEntry::construct))
+}
+
+/// Attribute names are case insensitive.
+fn attribute<'a>(
+  entry: &'a SearchEntry,
+  name: &str,
+) -> Option<&'a Vec<String>> {
+  entry
+    .attrs
+    .iter()
+    .find(|(attr, _)| attr.eq_ignore_ascii_case(name))
+    .map(|(_, values)| values)
+}
+
+/// Extracts the group name from a group DN, eg.
+/// `CN=Admins,OU=Groups,DC=corp,DC=com` -> `Admins`.
+/// Values which aren't DNs are used as is.
+fn group_name(group: &str) -> String {
+  group
+    .split(',')
+    .next()
+    .and_then(|rdn| rdn.split_once('='))
+    .map(|(_, name)| name.trim())
+    .unwrap_or(group)
+    .to_string()
+}
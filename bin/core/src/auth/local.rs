@@ -7,7 +7,7 @@ use mongo_indexed::Document;
 use komodo_client::{
   api::auth::{
     CreateLocalUser, CreateLocalUserResponse, LoginLocalUser,
-    LoginLocalUserResponse,
+    LoginLocalUserResponse, LoginTotp, LoginTotpResponse,
   },
   entities::user::{User, UserConfig},
 };
@@ -16,10 +16,13 @@ use resolver_api::Resolve;
 
 use crate::{
   config::core_config,
+  helpers::query::get_user,
   state::State,
   state::{db_client, jwt_client},
 };
 
+use super::totp::verify_user_code;
+
 const BCRYPT_COST: u32 = 10;
 
 impl Resolve<CreateLocalUser, HeaderMap> for State {
@@ -74,7 +77,14 @@ impl Resolve<CreateLocalUser, HeaderMap> for State {
       last_update_view: 0,
       recents: Default::default(),
       all: Default::default(),
-      config: UserConfig::Local { password },
+      tags: Default::default(),
+      config: UserConfig::Local {
+        password,
+        totp_enabled: false,
+        totp_secret: String::new(),
+        totp_recovery_codes: Vec::new(),
+        totp_last_step: 0,
+      },
     };
 
     let user_id = db_client()
@@ -119,6 +129,8 @@ impl Resolve<LoginLocalUser, HeaderMap> for State {
 
     let UserConfig::Local {
       password: user_pw_hash,
+      totp_enabled,
+      ..
     } = user.config
     else {
       return Err(anyhow!(
@@ -133,10 +145,48 @@ impl Resolve<LoginLocalUser, HeaderMap> for State {
       return Err(anyhow!("invalid credentials"));
     }
 
+    if totp_enabled {
+      let totp_token = jwt_client().create_totp_token(user.id).await;
+      return Ok(LoginLocalUserResponse {
+        jwt: String::new(),
+        totp_token: Some(totp_token),
+      });
+    }
+
+    let jwt = jwt_client()
+      .generate(user.id)
+      .context("failed at generating jwt for user")?;
+
+    Ok(LoginLocalUserResponse {
+      jwt,
+      totp_token: None,
+    })
+  }
+}
+
+impl Resolve<LoginTotp, HeaderMap> for State {
+  #[instrument(name = "LoginTotp", level = "debug", skip_all)]
+  async fn resolve(
+    &self,
+    LoginTotp { token, code }: LoginTotp,
+    _: HeaderMap,
+  ) -> anyhow::Result<LoginTotpResponse> {
+    if !core_config().local_auth {
+      return Err(anyhow!("local auth is not enabled"));
+    }
+
+    let login = jwt_client().take_totp_token(&token).await?;
+    let user = get_user(&login.user_id).await?;
+
+    if let Err(e) = verify_user_code(&user, &code).await {
+      jwt_client().return_failed_totp_token(token, login).await;
+      return Err(e);
+    }
+
     let jwt = jwt_client()
       .generate(user.id)
       .context("failed at generating jwt for user")?;
 
-    Ok(LoginLocalUserResponse { jwt })
+    Ok(LoginTotpResponse { jwt })
   }
 }
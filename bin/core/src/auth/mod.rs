@@ -1,11 +1,21 @@
+use std::future::Future;
+
 use ::jwt::VerifyWithKey;
 use anyhow::{anyhow, Context};
 use async_timing_util::unix_timestamp_ms;
 use axum::{
-  extract::Request, http::HeaderMap, middleware::Next,
+  extract::{Request, State},
+  http::HeaderMap,
+  middleware::Next,
   response::Response,
 };
-use komodo_client::entities::{komodo_timestamp, user::User};
+use komodo_client::entities::{
+  api_key::{ApiKeyRequestType, ApiKeyScope},
+  komodo_timestamp,
+  permission::PermissionLevel,
+  user::{User, UserConfig},
+  ResourceTargetVariant,
+};
 use mungos::mongodb::bson::doc;
 use reqwest::StatusCode;
 use serde::Deserialize;
@@ -21,33 +31,110 @@ use self::jwt::JwtClaims;
 pub mod github;
 pub mod google;
 pub mod jwt;
+pub mod oidc;
+pub mod totp;
 
+mod ldap;
 mod local;
 
 const STATE_PREFIX_LENGTH: usize = 20;
 
+/// Admins using api keys scoped to specific resources
+/// get Write on all of these, clamped by the scope.
+const SCOPED_ADMIN_RESOURCE_TYPES: [ResourceTargetVariant; 10] = [
+  ResourceTargetVariant::Server,
+  ResourceTargetVariant::Stack,
+  ResourceTargetVariant::Deployment,
+  ResourceTargetVariant::Build,
+  ResourceTargetVariant::Repo,
+  ResourceTargetVariant::Procedure,
+  ResourceTargetVariant::Builder,
+  ResourceTargetVariant::Alerter,
+  ResourceTargetVariant::ServerTemplate,
+  ResourceTargetVariant::ResourceSync,
+];
+
+tokio::task_local! {
+  static API_KEY_SCOPE: Option<ApiKeyScope>;
+}
+
 #[derive(Deserialize)]
 pub struct RedirectQuery {
   pub redirect: Option<String>,
 }
 
+/// The scope of the api key a request was authenticated with,
+/// attached to the request next to the [User].
+/// None if the key is unrestricted, or a jwt was used.
+#[derive(Debug, Clone, Default)]
+pub struct RequestApiKeyScope(pub Option<ApiKeyScope>);
+
+/// Runs the future with the api key scope of the request, which
+/// the resource permission checks within are clamped by.
+/// Tasks spawned while handling the request must be wrapped again.
+pub async fn with_api_key_scope<F: Future>(
+  RequestApiKeyScope(scope): RequestApiKeyScope,
+  f: F,
+) -> F::Output {
+  API_KEY_SCOPE.scope(scope, f).await
+}
+
+/// The api key scope of the request being handled,
+/// see [with_api_key_scope].
+pub fn api_key_scope() -> RequestApiKeyScope {
+  RequestApiKeyScope(
+    API_KEY_SCOPE.try_with(Clone::clone).ok().flatten(),
+  )
+}
+
+/// Authenticates requests to the api of the given request type,
+/// used with `middleware::from_fn_with_state`.
 #[instrument(level = "debug")]
 pub async fn auth_request(
+  State(request_type): State<ApiKeyRequestType>,
   headers: HeaderMap,
   mut req: Request,
   next: Next,
 ) -> serror::Result<Response> {
-  let user = authenticate_check_enabled(&headers)
-    .await
-    .status_code(StatusCode::UNAUTHORIZED)?;
+  let (user, scope) =
+    authenticate_check_enabled(&headers, request_type)
+      .await
+      .status_code(StatusCode::UNAUTHORIZED)?;
+  req.extensions_mut().insert(user);
+  req.extensions_mut().insert(scope);
+  Ok(next.run(req).await)
+}
+
+/// Like [auth_request], but lets admins which are required
+/// to enable TOTP through, so they can enroll.
+/// Scoped api keys are not allowed.
+#[instrument(level = "debug")]
+pub async fn user_auth_request(
+  headers: HeaderMap,
+  mut req: Request,
+  next: Next,
+) -> serror::Result<Response> {
+  let user = async {
+    let (user_id, scope) = get_user_id_from_headers(&headers).await?;
+    if scope.is_some() {
+      return Err(anyhow!(
+        "scoped api keys cannot use the /user api"
+      ));
+    }
+    check_enabled_allow_totp_enrollment(user_id).await
+  }
+  .await
+  .status_code(StatusCode::UNAUTHORIZED)?;
   req.extensions_mut().insert(user);
   Ok(next.run(req).await)
 }
 
 #[instrument(level = "debug")]
+/// Returns the user id, along with the api key scope
+/// if a scoped api key was used.
 pub async fn get_user_id_from_headers(
   headers: &HeaderMap,
-) -> anyhow::Result<String> {
+) -> anyhow::Result<(String, Option<ApiKeyScope>)> {
   match (
     headers.get("authorization"),
     headers.get("x-api-key"),
@@ -56,9 +143,10 @@ pub async fn get_user_id_from_headers(
     (Some(jwt), _, _) => {
       // USE JWT
       let jwt = jwt.to_str().context("jwt is not str")?;
-      auth_jwt_get_user_id(jwt)
+      let user_id = auth_jwt_get_user_id(jwt)
         .await
-        .context("failed to authenticate jwt")
+        .context("failed to authenticate jwt")?;
+      Ok((user_id, None))
     }
     (None, Some(key), Some(secret)) => {
       // USE API KEY / SECRET
@@ -78,14 +166,12 @@ pub async fn get_user_id_from_headers(
 #[instrument(level = "debug")]
 pub async fn authenticate_check_enabled(
   headers: &HeaderMap,
-) -> anyhow::Result<User> {
-  let user_id = get_user_id_from_headers(headers).await?;
-  let user = get_user(&user_id).await?;
-  if user.enabled {
-    Ok(user)
-  } else {
-    Err(anyhow!("user not enabled"))
-  }
+  request_type: ApiKeyRequestType,
+) -> anyhow::Result<(User, RequestApiKeyScope)> {
+  let (user_id, scope) = get_user_id_from_headers(headers).await?;
+  let mut user = check_enabled(user_id).await?;
+  apply_api_key_scope(&mut user, &scope, request_type)?;
+  Ok((user, RequestApiKeyScope(scope)))
 }
 
 #[instrument(level = "debug")]
@@ -110,11 +196,12 @@ pub async fn auth_jwt_check_enabled(
   check_enabled(user_id).await
 }
 
+/// Returns the user id, along with the key scope if it is restricted.
 #[instrument(level = "debug")]
 pub async fn auth_api_key_get_user_id(
   key: &str,
   secret: &str,
-) -> anyhow::Result<String> {
+) -> anyhow::Result<(String, Option<ApiKeyScope>)> {
   let key = db_client()
     .await
     .api_keys
@@ -129,7 +216,12 @@ pub async fn auth_api_key_get_user_id(
     .context("failed to verify secret hash")?
   {
     // secret matches
-    Ok(key.user_id)
+    let scope = if key.scope.is_unrestricted() {
+      None
+    } else {
+      Some(key.scope)
+    };
+    Ok((key.user_id, scope))
   } else {
     // secret mismatch
     Err(anyhow!("invalid api secret"))
@@ -140,17 +232,112 @@ pub async fn auth_api_key_get_user_id(
 pub async fn auth_api_key_check_enabled(
   key: &str,
   secret: &str,
-) -> anyhow::Result<User> {
-  let user_id = auth_api_key_get_user_id(key, secret).await?;
-  check_enabled(user_id).await
+  request_type: ApiKeyRequestType,
+) -> anyhow::Result<(User, RequestApiKeyScope)> {
+  let (user_id, scope) =
+    auth_api_key_get_user_id(key, secret).await?;
+  let mut user = check_enabled(user_id).await?;
+  apply_api_key_scope(&mut user, &scope, request_type)?;
+  Ok((user, RequestApiKeyScope(scope)))
+}
+
+/// Checks the api key scope allows the request type,
+/// and limits the user's global permissions to match it.
+fn apply_api_key_scope(
+  user: &mut User,
+  scope: &Option<ApiKeyScope>,
+  request_type: ApiKeyRequestType,
+) -> anyhow::Result<()> {
+  let Some(scope) = scope else {
+    return Ok(());
+  };
+  if !scope.allows_request(request_type) {
+    return Err(anyhow!(
+      "api key scope does not allow {request_type} requests"
+    ));
+  }
+  if scope.restricts_resources() {
+    // Keys restricted to some resources or permission level
+    // can't use admin only apis or create resources.
+    // Admins keep Write on resources, which the scope then clamps.
+    if user.admin {
+      user.admin = false;
+      user.all = SCOPED_ADMIN_RESOURCE_TYPES
+        .into_iter()
+        .map(|resource_type| (resource_type, PermissionLevel::Write))
+        .collect();
+    }
+    user.create_server_permissions = false;
+    user.create_build_permissions = false;
+  }
+  Ok(())
 }
 
 #[instrument(level = "debug")]
 async fn check_enabled(user_id: String) -> anyhow::Result<User> {
+  let user = check_enabled_allow_totp_enrollment(user_id).await?;
+  if totp::enrollment_required(&user) {
+    return Err(anyhow!(
+      "admins must enable two-factor authentication (TOTP) before using Komodo"
+    ));
+  }
+  Ok(user)
+}
+
+#[instrument(level = "debug")]
+async fn check_enabled_allow_totp_enrollment(
+  user_id: String,
+) -> anyhow::Result<User> {
   let user = get_user(&user_id).await?;
-  if user.enabled {
-    Ok(user)
-  } else {
-    Err(anyhow!("user not enabled"))
+  if !user.enabled {
+    return Err(anyhow!("user not enabled"));
+  }
+  if let UserConfig::Ldap { .. } = &user.config {
+    ldap::check_directory_account(&user).await?;
   }
+  Ok(user)
+}
+
+/// Makes the user a member of exactly the `managed` User Groups
+/// whose names are given in `groups`. Membership in User Groups
+/// which aren't managed is left untouched, as it may be assigned
+/// manually or by a Resource Sync.
+async fn sync_user_groups(
+  user_id: &str,
+  groups: &[String],
+  managed: &[String],
+) -> anyhow::Result<()> {
+  if managed.is_empty() {
+    return Ok(());
+  }
+  let add = groups
+    .iter()
+    .filter(|group| managed.contains(group))
+    .collect::<Vec<_>>();
+  let user_groups = &db_client().await.user_groups;
+  let updated_at = unix_timestamp_ms() as i64;
+  user_groups
+    .update_many(
+      doc! { "name": { "$in": add }, "users": { "$ne": user_id } },
+      doc! {
+        "$push": { "users": user_id },
+        "$set": { "updated_at": updated_at },
+      },
+    )
+    .await
+    .context("failed to add user to user groups")?;
+  user_groups
+    .update_many(
+      doc! {
+        "name": { "$in": managed, "$nin": groups },
+        "users": user_id,
+      },
+      doc! {
+        "$pull": { "users": user_id },
+        "$set": { "updated_at": updated_at },
+      },
+    )
+    .await
+    .context("failed to remove user from user groups")?;
+  Ok(())
 }
@@ -0,0 +1,303 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use anyhow::{anyhow, Context};
+use async_timing_util::{unix_timestamp_ms, ONE_MIN_MS};
+use base64::Engine;
+use jwt::Token;
+use komodo_client::entities::config::core::{CoreConfig, OidcConfig};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use tokio::sync::{Mutex, OnceCell};
+
+use crate::{
+  auth::STATE_PREFIX_LENGTH, config::core_config,
+  helpers::random_string,
+};
+
+/// Pending logins which are not completed within this time are dropped.
+const PENDING_LOGIN_TIMEOUT_MS: u128 = 10 * ONE_MIN_MS;
+
+pub fn oidc_client() -> &'static Option<OidcClient> {
+  static OIDC_CLIENT: OnceLock<Option<OidcClient>> = OnceLock::new();
+  OIDC_CLIENT.get_or_init(|| OidcClient::new(core_config()))
+}
+
+pub struct OidcClient {
+  http: reqwest::Client,
+  discovery_url: String,
+  client_id: String,
+  client_secret: String,
+  redirect_uri: String,
+  scopes: String,
+  pub username_claim: String,
+  pub groups_claim: String,
+  pub managed_groups: Vec<String>,
+  /// Lazily fetched on first login, so Core can start
+  /// while the provider is unreachable.
+  provider: OnceCell<OidcProvider>,
+  /// Maps state -> pending login
+  pending: Mutex<HashMap<String, PendingLogin>>,
+}
+
+/// The relevant fields of the provider discovery document.
+#[derive(Deserialize)]
+pub struct OidcProvider {
+  pub issuer: String,
+  pub authorization_endpoint: String,
+  pub token_endpoint: String,
+}
+
+pub struct PendingLogin {
+  /// The PKCE code verifier
+  code_verifier: String,
+  nonce: String,
+  ts: u128,
+}
+
+impl OidcClient {
+  pub fn new(
+    CoreConfig {
+      oidc:
+        OidcConfig {
+          enabled,
+          discovery_url,
+          client_id,
+          client_secret,
+          scopes,
+          username_claim,
+          groups_claim,
+          managed_groups,
+        },
+      host,
+      ..
+    }: &CoreConfig,
+  ) -> Option<OidcClient> {
+    if !enabled {
+      return None;
+    }
+    if host.is_empty() {
+      warn!("oidc is enabled, but 'config.host' is not configured");
+      return None;
+    }
+    if discovery_url.is_empty() {
+      warn!("oidc is enabled, but 'config.oidc.discovery_url' is not configured");
+      return None;
+    }
+    if client_id.is_empty() {
+      warn!("oidc is enabled, but 'config.oidc.client_id' is not configured");
+      return None;
+    }
+    if username_claim.is_empty() {
+      warn!(
+        "oidc is enabled, but 'config.oidc.username_claim' is empty"
+      );
+      return None;
+    }
+    let mut scopes = scopes
+      .iter()
+      .map(|scope| scope.trim())
+      .filter(|scope| !scope.is_empty())
+      .collect::<Vec<_>>();
+    if !scopes.contains(&"openid") {
+      scopes.insert(0, "openid");
+    }
+    let discovery_url = if discovery_url
+      .ends_with("/.well-known/openid-configuration")
+    {
+      discovery_url.clone()
+    } else {
+      format!(
+        "{}/.well-known/openid-configuration",
+        discovery_url.trim_end_matches('/')
+      )
+    };
+    OidcClient {
+      http: Default::default(),
+      discovery_url,
+      client_id: client_id.clone(),
+      client_secret: client_secret.clone(),
+      redirect_uri: format!("{host}/auth/oidc/callback"),
+      scopes: urlencoding::encode(&scopes.join(" ")).to_string(),
+      username_claim: username_claim.clone(),
+      groups_claim: groups_claim.clone(),
+      managed_groups: managed_groups.clone(),
+      provider: Default::default(),
+      pending: Default::default(),
+    }
+    .into()
+  }
+
+  async fn provider(&self) -> anyhow::Result<&OidcProvider> {
+    self
+      .provider
+      .get_or_try_init(|| async {
+        let res = self
+          .http
+          .get(&self.discovery_url)
+          .send()
+          .await
+          .context("failed to reach oidc provider")?;
+        let status = res.status();
+        if status != StatusCode::OK {
+          let text = res.text().await.unwrap_or_default();
+          return Err(anyhow!(
+            "method: GET | status: {status} | text: {text}"
+          ))
+          .context("failed to get oidc discovery document");
+        }
+        res
+          .json::<OidcProvider>()
+          .await
+          .context("failed to parse oidc discovery document")
+      })
+      .await
+  }
+
+  #[instrument(level = "debug", skip(self))]
+  pub async fn get_login_redirect_url(
+    &self,
+    redirect: Option<String>,
+  ) -> anyhow::Result<String> {
+    let provider = self.provider().await?;
+    let state_prefix = random_string(STATE_PREFIX_LENGTH);
+    let state = match redirect {
+      Some(redirect) => format!("{state_prefix}{redirect}"),
+      None => state_prefix,
+    };
+    let code_verifier = random_string(64);
+    let code_challenge =
+      base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .encode(Sha256::digest(code_verifier.as_bytes()));
+    let nonce = random_string(32);
+    let splitter = if provider.authorization_endpoint.contains('?') {
+      '&'
+    } else {
+      '?'
+    };
+    let redirect_url = format!(
+      "{}{splitter}response_type=code&state={}&client_id={}&redirect_uri={}&scope={}&nonce={nonce}&code_challenge={code_challenge}&code_challenge_method=S256",
+      provider.authorization_endpoint,
+      urlencoding::encode(&state),
+      urlencoding::encode(&self.client_id),
+      urlencoding::encode(&self.redirect_uri),
+      self.scopes,
+    );
+    let ts = unix_timestamp_ms();
+    let mut pending = self.pending.lock().await;
+    pending
+      .retain(|_, login| ts - login.ts < PENDING_LOGIN_TIMEOUT_MS);
+    pending.insert(
+      state,
+      PendingLogin {
+        code_verifier,
+        nonce,
+        ts,
+      },
+    );
+    Ok(redirect_url)
+  }
+
+  /// Removes and returns the pending login for the state, if it exists.
+  #[instrument(level = "debug", skip(self))]
+  pub async fn take_pending_login(
+    &self,
+    state: &str,
+  ) -> Option<PendingLogin> {
+    self.pending.lock().await.remove(state)
+  }
+
+  /// Exchanges the code for tokens and returns the
+  /// validated id token claims, along with the provider issuer.
+  #[instrument(level = "debug", skip(self, login))]
+  pub async fn get_id_token_claims(
+    &self,
+    code: &str,
+    login: PendingLogin,
+  ) -> anyhow::Result<(String, Map<String, Value>)> {
+    let provider = self.provider().await?;
+    let mut body = vec![
+      ("grant_type", "authorization_code"),
+      ("code", code),
+      ("redirect_uri", self.redirect_uri.as_str()),
+      ("client_id", self.client_id.as_str()),
+      ("code_verifier", login.code_verifier.as_str()),
+    ];
+    if !self.client_secret.is_empty() {
+      body.push(("client_secret", self.client_secret.as_str()));
+    }
+    let res = self
+      .http
+      .post(&provider.token_endpoint)
+      .form(&body)
+      .header("Accept", "application/json")
+      .send()
+      .await
+      .context("failed to reach oidc provider")?;
+    let status = res.status();
+    if status != StatusCode::OK {
+      let text = res.text().await.context(format!(
+        "method: POST | status: {status} | failed to get response text"
+      ))?;
+      return Err(anyhow!(
+        "method: POST | status: {status} | text: {text}"
+      ))
+      .context("failed to exchange code for oidc tokens");
+    }
+    let TokenResponse { id_token } = res
+      .json()
+      .await
+      .context("failed to parse oidc token response")?;
+
+    // The id token is received directly from the token endpoint
+    // over TLS, so the claims are validated without verifying
+    // the signature (OpenID Connect Core 1.0, section 3.1.3.7).
+    let token: Token<Value, Map<String, Value>, jwt::Unverified> =
+      Token::parse_unverified(&id_token)
+        .context("failed to parse id_token")?;
+    let claims = token.claims().to_owned();
+
+    let issuer = claims
+      .get("iss")
+      .and_then(Value::as_str)
+      .context("id_token missing 'iss' claim")?;
+    if issuer != provider.issuer {
+      return Err(anyhow!(
+        "id_token issuer {issuer} does not match provider issuer {}",
+        provider.issuer
+      ));
+    }
+    let audience_valid = match claims.get("aud") {
+      Some(Value::String(aud)) => aud == &self.client_id,
+      Some(Value::Array(auds)) => auds
+        .iter()
+        .any(|aud| aud.as_str() == Some(self.client_id.as_str())),
+      _ => false,
+    };
+    if !audience_valid {
+      return Err(anyhow!(
+        "id_token audience does not include client id"
+      ));
+    }
+    let expiry = claims
+      .get("exp")
+      .and_then(Value::as_u64)
+      .context("id_token missing 'exp' claim")?;
+    if (expiry as u128) * 1000 < unix_timestamp_ms() {
+      return Err(anyhow!("id_token has expired"));
+    }
+    if claims.get("nonce").and_then(Value::as_str)
+      != Some(login.nonce.as_str())
+    {
+      return Err(anyhow!("id_token nonce mismatch"));
+    }
+
+    Ok((provider.issuer.clone(), claims))
+  }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+  id_token: String,
+}
@@ -0,0 +1,176 @@
+use anyhow::{anyhow, Context};
+use async_timing_util::unix_timestamp_ms;
+use axum::{
+  extract::Query, response::Redirect, routing::get, Router,
+};
+use komodo_client::entities::user::{User, UserConfig};
+use mongo_indexed::Document;
+use mungos::mongodb::bson::doc;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::Value;
+use serror::AddStatusCode;
+
+use crate::{
+  config::core_config,
+  state::{db_client, jwt_client},
+};
+
+use self::client::oidc_client;
+
+use super::{sync_user_groups, RedirectQuery, STATE_PREFIX_LENGTH};
+
+pub mod client;
+
+pub fn router() -> Router {
+  Router::new()
+    .route(
+      "/login",
+      get(|Query(query): Query<RedirectQuery>| async move {
+        oidc_client()
+          .as_ref()
+          // OK: its not mounted unless the client is populated
+          .unwrap()
+          .get_login_redirect_url(query.redirect)
+          .await
+          .map(|url| Redirect::to(&url))
+          .status_code(StatusCode::INTERNAL_SERVER_ERROR)
+      }),
+    )
+    .route(
+      "/callback",
+      get(|query| async {
+        callback(query).await.status_code(StatusCode::UNAUTHORIZED)
+      }),
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct CallbackQuery {
+  state: Option<String>,
+  code: Option<String>,
+  error: Option<String>,
+}
+
+#[instrument(name = "OidcCallback", level = "debug")]
+async fn callback(
+  Query(query): Query<CallbackQuery>,
+) -> anyhow::Result<Redirect> {
+  // Safe: the method is only called after the client is_some
+  let client = oidc_client().as_ref().unwrap();
+  if let Some(error) = query.error {
+    return Err(anyhow!("auth error from oidc provider: {error}"));
+  }
+  let state = query
+    .state
+    .context("callback query does not contain state")?;
+  let login = client
+    .take_pending_login(&state)
+    .await
+    .context("state mismatch")?;
+  let (provider, claims) = client
+    .get_id_token_claims(
+      &query.code.context("callback query does not contain code")?,
+      login,
+    )
+    .await?;
+  let oidc_id = claims
+    .get("sub")
+    .and_then(Value::as_str)
+    .context("id_token missing 'sub' claim")?
+    .to_string();
+  let db_client = db_client().await;
+  let user = db_client
+    .users
+    .find_one(doc! {
+      "config.type": "Oidc",
+      "config.data.provider": &provider,
+      "config.data.oidc_id": &oidc_id,
+    })
+    .await
+    .context("failed at find user query from mongo")?;
+  let user_id = match user {
+    Some(user) => user.id,
+    None => {
+      let ts = unix_timestamp_ms() as i64;
+      let no_users_exist =
+        db_client.users.find_one(Document::new()).await?.is_none();
+      let core_config = core_config();
+      if !no_users_exist && core_config.disable_user_registration {
+        return Err(anyhow!("User registration is disabled"));
+      }
+      let username = claims
+        .get(&client.username_claim)
+        .and_then(Value::as_str)
+        .map(|username| {
+          // Use the local part when mapped to an email claim
+          username.split('@').next().unwrap_or_default()
+        })
+        .filter(|username| !username.is_empty())
+        .with_context(|| {
+          format!(
+            "id_token missing username claim '{}'",
+            client.username_claim
+          )
+        })?
+        .to_string();
+      let user = User {
+        id: Default::default(),
+        username,
+        enabled: no_users_exist || core_config.enable_new_users,
+        admin: no_users_exist,
+        create_server_permissions: no_users_exist,
+        create_build_permissions: no_users_exist,
+        updated_at: ts,
+        last_update_view: 0,
+        recents: Default::default(),
+        all: Default::default(),
+        tags: Default::default(),
+        config: UserConfig::Oidc {
+          provider,
+          oidc_id,
+          avatar: claims
+            .get("picture")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        },
+      };
+      db_client
+        .users
+        .insert_one(user)
+        .await
+        .context("failed to create user on mongo")?
+        .inserted_id
+        .as_object_id()
+        .context("inserted_id is not ObjectId")?
+        .to_string()
+    }
+  };
+  if !client.groups_claim.is_empty() {
+    let groups = match claims.get(&client.groups_claim) {
+      Some(Value::Array(groups)) => groups
+        .iter()
+        .filter_map(Value::as_str)
+        .map(String::from)
+        .collect::<Vec<_>>(),
+      Some(Value::String(group)) => vec![group.clone()],
+      _ => Vec::new(),
+    };
+    sync_user_groups(&user_id, &groups, &client.managed_groups)
+      .await
+      .context("failed to sync user groups from oidc groups claim")?;
+  }
+  let jwt = jwt_client()
+    .generate(user_id)
+    .context("failed to generate jwt")?;
+  let exchange_token = jwt_client().create_exchange_token(jwt).await;
+  let redirect = &state[STATE_PREFIX_LENGTH..];
+  let redirect_url = if redirect.is_empty() {
+    format!("{}?token={exchange_token}", core_config().host)
+  } else {
+    let splitter = if redirect.contains('?') { '&' } else { '?' };
+    format!("{}{splitter}token={exchange_token}", redirect)
+  };
+  Ok(Redirect::to(&redirect_url))
+}
@@ -0,0 +1,226 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use anyhow::{anyhow, Context};
+use async_timing_util::unix_timestamp_ms;
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use komodo_client::entities::user::{User, UserConfig};
+use mungos::mongodb::bson::{doc, oid::ObjectId};
+use rand::Rng;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use tokio::sync::Mutex;
+
+use crate::{
+  config::core_config, helpers::random_string, state::db_client,
+};
+
+const ISSUER: &str = "Komodo";
+const PERIOD_SECS: u64 = 30;
+const DIGITS: u32 = 6;
+/// Codes from this many periods before / after
+/// the current one are also accepted, for clock drift.
+const ALLOWED_DRIFT: i64 = 1;
+const RECOVERY_CODE_COUNT: usize = 10;
+/// Code attempts allowed for one user within
+/// [FAILED_ATTEMPTS_WINDOW_MS], across all of their login tokens.
+/// A successful attempt resets the count.
+const USER_MAX_FAILED_ATTEMPTS: u8 = 10;
+const FAILED_ATTEMPTS_WINDOW_MS: u128 = 15 * 60 * 1000;
+
+/// Failed attempt count and the start of the window,
+/// keyed by user id.
+type FailedAttempts = Mutex<HashMap<String, (u8, u128)>>;
+
+fn failed_attempts() -> &'static FailedAttempts {
+  static FAILED_ATTEMPTS: OnceLock<FailedAttempts> = OnceLock::new();
+  FAILED_ATTEMPTS.get_or_init(Default::default)
+}
+
+/// Generates a new random base32 encoded secret.
+pub fn generate_secret() -> String {
+  let bytes: [u8; 20] = rand::thread_rng().gen();
+  BASE32_NOPAD.encode(&bytes)
+}
+
+/// The `otpauth://` uri used to add the secret to authenticator apps.
+pub fn provisioning_uri(secret: &str, username: &str) -> String {
+  let label =
+    urlencoding::encode(&format!("{ISSUER}:{username}")).to_string();
+  format!(
+    "otpauth://totp/{label}?secret={secret}&issuer={ISSUER}&algorithm=SHA1&digits={DIGITS}&period={PERIOD_SECS}"
+  )
+}
+
+/// Checks the code against the secret at the current time.
+/// Returns the time step the code is valid for, if any.
+pub fn verify_code(
+  secret: &str,
+  code: &str,
+) -> anyhow::Result<Option<i64>> {
+  let code = code.trim();
+  if code.len() != DIGITS as usize
+    || !code.chars().all(|c| c.is_ascii_digit())
+  {
+    return Ok(None);
+  }
+  let key = BASE32_NOPAD
+    .decode(secret.as_bytes())
+    .context("invalid totp secret")?;
+  let counter =
+    (unix_timestamp_ms() / 1000) as i64 / PERIOD_SECS as i64;
+  for offset in -ALLOWED_DRIFT..=ALLOWED_DRIFT {
+    let step = counter + offset;
+    let expected = format!(
+      "{:0width$}",
+      hotp(&key, step as u64)?,
+      width = DIGITS as usize
+    );
+    if bool::from(expected.as_bytes().ct_eq(code.as_bytes())) {
+      return Ok(Some(step));
+    }
+  }
+  Ok(None)
+}
+
+/// HOTP (RFC 4226) with HMAC-SHA1.
+fn hotp(key: &[u8], counter: u64) -> anyhow::Result<u32> {
+  let mut mac = Hmac::<Sha1>::new_from_slice(key)
+    .context("failed to initialize hmac")?;
+  mac.update(&counter.to_be_bytes());
+  let hash = mac.finalize().into_bytes();
+  let offset = (hash[hash.len() - 1] & 0xf) as usize;
+  let binary = u32::from_be_bytes([
+    hash[offset] & 0x7f,
+    hash[offset + 1],
+    hash[offset + 2],
+    hash[offset + 3],
+  ]);
+  Ok(binary % 10u32.pow(DIGITS))
+}
+
+/// Returns the plain recovery codes, along with the hashes to store.
+pub fn generate_recovery_codes() -> (Vec<String>, Vec<String>) {
+  (0..RECOVERY_CODE_COUNT)
+    .map(|_| {
+      let code = random_string(10).to_lowercase();
+      let code = format!("{}-{}", &code[..5], &code[5..]);
+      let hash = hash_recovery_code(&code);
+      (code, hash)
+    })
+    .unzip()
+}
+
+/// Recovery codes are random with high entropy,
+/// so a fast hash is sufficient.
+fn hash_recovery_code(code: &str) -> String {
+  hex::encode(Sha256::digest(code.trim().to_lowercase().as_bytes()))
+}
+
+/// Verifies the code is a valid TOTP code for the user,
+/// or an unused recovery code. Each code is only accepted once,
+/// and failed attempts are rate limited per user.
+pub async fn verify_user_code(
+  user: &User,
+  code: &str,
+) -> anyhow::Result<()> {
+  let UserConfig::Local { totp_secret, .. } = &user.config else {
+    return Err(anyhow!("user does not use local auth"));
+  };
+
+  // The attempt is counted before verifying, so concurrent
+  // attempts can't all pass the limit check.
+  {
+    let mut attempts = failed_attempts().lock().await;
+    let ts = unix_timestamp_ms();
+    let (count, since) =
+      attempts.entry(user.id.clone()).or_insert((0, ts));
+    if ts - *since > FAILED_ATTEMPTS_WINDOW_MS {
+      (*count, *since) = (0, ts);
+    }
+    if *count >= USER_MAX_FAILED_ATTEMPTS {
+      return Err(anyhow!(
+        "too many failed attempts, try again later"
+      ));
+    }
+    *count += 1;
+  }
+
+  let res = match verify_code(totp_secret, code)? {
+    Some(step) => use_totp_step(user, step).await,
+    None => use_recovery_code(user, code).await,
+  };
+
+  if res.is_ok() {
+    failed_attempts().lock().await.remove(&user.id);
+  }
+
+  res
+}
+
+/// Marks the time step as used, failing if it
+/// (or a later step) was already used.
+async fn use_totp_step(user: &User, step: i64) -> anyhow::Result<()> {
+  let id = ObjectId::parse_str(&user.id)
+    .context("user id is not valid ObjectId")?;
+  let res = db_client()
+    .await
+    .users
+    .update_one(
+      doc! {
+        "_id": id,
+        "config.data.totp_last_step": { "$not": { "$gte": step } },
+      },
+      doc! { "$set": { "config.data.totp_last_step": step } },
+    )
+    .await
+    .context("failed to store used totp step")?;
+  if res.modified_count == 1 {
+    Ok(())
+  } else {
+    Err(anyhow!("code has already been used"))
+  }
+}
+
+/// Removes the recovery code, failing if it isn't
+/// one of the user's unused recovery codes.
+async fn use_recovery_code(
+  user: &User,
+  code: &str,
+) -> anyhow::Result<()> {
+  let id = ObjectId::parse_str(&user.id)
+    .context("user id is not valid ObjectId")?;
+  let hash = hash_recovery_code(code);
+  let res = db_client()
+    .await
+    .users
+    .update_one(
+      doc! {
+        "_id": id,
+        "config.data.totp_recovery_codes": &hash,
+      },
+      doc! { "$pull": { "config.data.totp_recovery_codes": &hash } },
+    )
+    .await
+    .context("failed to remove used recovery code")?;
+  if res.modified_count == 1 {
+    Ok(())
+  } else {
+    Err(anyhow!("invalid code"))
+  }
+}
+
+/// Whether the user is an admin who must enable TOTP
+/// before using the api, see `require_admin_totp`.
+pub fn enrollment_required(user: &User) -> bool {
+  user.admin
+    && core_config().require_admin_totp
+    && matches!(
+      user.config,
+      UserConfig::Local {
+        totp_enabled: false,
+        ..
+      }
+    )
+}
@@ -19,7 +19,10 @@ use komodo_client::entities::{
   ResourceTarget,
 };
 
-use crate::{config::core_config, helpers::alert::send_alerts};
+use crate::{
+  config::core_config,
+  helpers::alert::{send_alerts, silence_alerts},
+};
 
 const POLL_RATE_SECS: u64 = 2;
 const MAX_POLL_TRIES: usize = 30;
@@ -168,7 +171,7 @@ pub async fn terminate_ec2_instance_with_retry(
       Err(e) => {
         if i == MAX_TERMINATION_TRIES - 1 {
           error!("failed to terminate aws instance {instance_id}.");
-          let alert = Alert {
+          let mut alert = Alert {
             id: Default::default(),
             ts: komodo_timestamp(),
             resolved: false,
@@ -179,7 +182,9 @@ pub async fn terminate_ec2_instance_with_retry(
               message: format!("{e:#}"),
             },
             resolved_ts: None,
+            silenced: false,
           };
+          silence_alerts(vec![&mut alert]).await;
           send_alerts(&[alert]).await;
           return Err(e);
         }
@@ -6,7 +6,7 @@ use komodo_client::entities::{
   config::core::{
     AwsCredentials, CoreConfig, Env, GithubWebhookAppConfig,
     GithubWebhookAppInstallationConfig, HetznerCredentials,
-    MongoConfig, OauthCredentials,
+    LdapConfig, MongoConfig, OauthCredentials, OidcConfig,
   },
   logger::LogConfig,
 };
@@ -115,6 +115,12 @@ pub fn core_config() -> &'static CoreConfig {
       webhook_base_url: env
         .komodo_webhook_base_url
         .or(config.webhook_base_url),
+      enable_metrics: env
+        .komodo_enable_metrics
+        .unwrap_or(config.enable_metrics),
+      metrics_token: env
+        .komodo_metrics_token
+        .unwrap_or(config.metrics_token),
       transparent_mode: env
         .komodo_transparent_mode
         .unwrap_or(config.transparent_mode),
@@ -126,6 +132,9 @@ pub fn core_config() -> &'static CoreConfig {
       disable_user_registration: env.komodo_disable_user_registration
         .unwrap_or(config.disable_user_registration),
       local_auth: env.komodo_local_auth.unwrap_or(config.local_auth),
+      require_admin_totp: env
+        .komodo_require_admin_totp
+        .unwrap_or(config.require_admin_totp),
       google_oauth: OauthCredentials {
         enabled: env
           .komodo_google_oauth_enabled
@@ -148,6 +157,65 @@ pub fn core_config() -> &'static CoreConfig {
           .komodo_github_oauth_secret
           .unwrap_or(config.github_oauth.secret),
       },
+      oidc: OidcConfig {
+        enabled: env
+          .komodo_oidc_enabled
+          .unwrap_or(config.oidc.enabled),
+        discovery_url: env
+          .komodo_oidc_discovery_url
+          .unwrap_or(config.oidc.discovery_url),
+        client_id: env
+          .komodo_oidc_client_id
+          .unwrap_or(config.oidc.client_id),
+        client_secret: env
+          .komodo_oidc_client_secret
+          .unwrap_or(config.oidc.client_secret),
+        scopes: env
+          .komodo_oidc_scopes
+          .unwrap_or(config.oidc.scopes),
+        username_claim: env
+          .komodo_oidc_username_claim
+          .unwrap_or(config.oidc.username_claim),
+        groups_claim: env
+          .komodo_oidc_groups_claim
+          .unwrap_or(config.oidc.groups_claim),
+        managed_groups: env
+          .komodo_oidc_managed_groups
+          .unwrap_or(config.oidc.managed_groups),
+      },
+      ldap: LdapConfig {
+        enabled: env
+          .komodo_ldap_enabled
+          .unwrap_or(config.ldap.enabled),
+        url: env.komodo_ldap_url.unwrap_or(config.ldap.url),
+        bind_dn: env
+          .komodo_ldap_bind_dn
+          .unwrap_or(config.ldap.bind_dn),
+        search_base: env
+          .komodo_ldap_search_base
+          .unwrap_or(config.ldap.search_base),
+        user_filter: env
+          .komodo_ldap_user_filter
+          .unwrap_or(config.ldap.user_filter),
+        starttls: env
+          .komodo_ldap_starttls
+          .unwrap_or(config.ldap.starttls),
+        tls_skip_verify: env
+          .komodo_ldap_tls_skip_verify
+          .unwrap_or(config.ldap.tls_skip_verify),
+        group_attribute: env
+          .komodo_ldap_group_attribute
+          .unwrap_or(config.ldap.group_attribute),
+        managed_groups: env
+          .komodo_ldap_managed_groups
+          .unwrap_or(config.ldap.managed_groups),
+        service_bind_dn: env
+          .komodo_ldap_service_bind_dn
+          .unwrap_or(config.ldap.service_bind_dn),
+        service_bind_password: env
+          .komodo_ldap_service_bind_password
+          .unwrap_or(config.ldap.service_bind_password),
+      },
       github_webhook_app: GithubWebhookAppConfig {
         app_id: env
           .komodo_github_webhook_app_app_id
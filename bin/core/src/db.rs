@@ -6,13 +6,14 @@ use komodo_client::entities::{
   build::Build,
   builder::Builder,
   config::core::MongoConfig,
-  deployment::Deployment,
+  deployment::{Deployment, DeploymentRecord},
   permission::Permission,
   procedure::Procedure,
   provider::{DockerRegistryAccount, GitProviderAccount},
   repo::Repo,
   server::Server,
   server_template::ServerTemplate,
+  silence::Silence,
   stack::Stack,
   stats::SystemStatsRecord,
   sync::ResourceSync,
@@ -38,6 +39,8 @@ pub struct DbClient {
   pub registry_accounts: Collection<DockerRegistryAccount>,
   pub updates: Collection<Update>,
   pub alerts: Collection<Alert>,
+  pub silences: Collection<Silence>,
+  pub deployment_history: Collection<DeploymentRecord>,
   pub stats: Collection<SystemStatsRecord>,
   // RESOURCES
   pub servers: Collection<Server>,
@@ -100,6 +103,9 @@ impl DbClient {
       registry_accounts: mongo_indexed::collection(&db, true).await?,
       updates: mongo_indexed::collection(&db, true).await?,
       alerts: mongo_indexed::collection(&db, true).await?,
+      silences: mongo_indexed::collection(&db, true).await?,
+      deployment_history: mongo_indexed::collection(&db, true)
+        .await?,
       stats: mongo_indexed::collection(&db, true).await?,
       // RESOURCES
       servers: resource_collection(&db, "Server").await?,
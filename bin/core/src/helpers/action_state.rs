@@ -72,6 +72,21 @@ impl<States: Default + Busy + Copy + Send + 'static>
     handler(&mut *lock);
     Ok(UpdateGuard(&self.0))
   }
+
+  /// Will acquire lock and run the provided update function
+  /// on the states, without checking busy.
+  /// Used to report the progress of an action already holding the guard.
+  pub fn set(
+    &self,
+    handler: impl Fn(&mut States),
+  ) -> anyhow::Result<()> {
+    let mut lock = self
+      .0
+      .lock()
+      .map_err(|e| anyhow!("action state lock poisoned | {e:?}"))?;
+    handler(&mut *lock);
+    Ok(())
+  }
 }
 
 /// When dropped will return the inner state to default.
@@ -0,0 +1,30 @@
+use komodo_client::entities::alert::Alert;
+use serde_json::json;
+
+use super::*;
+
+#[instrument(level = "debug")]
+pub async fn send_alert(
+  url: &str,
+  alert: &Alert,
+) -> anyhow::Result<()> {
+  let Some(AlertContent {
+    title,
+    details,
+    link,
+  }) = alert_content(alert)
+  else {
+    return Ok(());
+  };
+  let level = fmt_level(alert.level);
+  let mut embed = json!({
+    "title": format!("{level} | {title}"),
+    "description": details.join("\n"),
+    "color": level_color(alert.level),
+  });
+  if let Some(link) = link {
+    embed["url"] = link.into();
+  }
+  let body = json!({ "embeds": [embed] });
+  send_request(reqwest::Client::new().post(url).json(&body)).await
+}
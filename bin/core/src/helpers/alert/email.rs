@@ -0,0 +1,302 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use anyhow::Context;
+use komodo_client::entities::{
+  alert::Alert,
+  alerter::{EmailAlerterEndpoint, SmtpSecurity},
+};
+use lettre::{
+  message::{Mailbox, MultiPart},
+  transport::smtp::authentication::Credentials,
+  AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use tokio::sync::Mutex;
+
+use crate::helpers::query::{
+  get_variables_and_secrets, VariablesAndSecrets,
+};
+
+use super::*;
+
+/// Alerts waiting to be sent in the next digest,
+/// keyed by alerter id.
+type DigestQueue =
+  Mutex<HashMap<String, (String, EmailAlerterEndpoint, Vec<Alert>)>>;
+
+fn digest_queue() -> &'static DigestQueue {
+  static DIGEST_QUEUE: OnceLock<DigestQueue> = OnceLock::new();
+  DIGEST_QUEUE.get_or_init(Default::default)
+}
+
+#[instrument(level = "debug", skip(endpoint))]
+pub async fn send_alert(
+  endpoint: &EmailAlerterEndpoint,
+  alert: &Alert,
+) -> anyhow::Result<()> {
+  let Some(content) = alert_content(alert) else {
+    return Ok(());
+  };
+  let subject =
+    format!("{} | {}", fmt_level(alert.level), content.title);
+  let plain = fmt_plain(alert, &content);
+  let html = fmt_html_document(&fmt_html(alert, &content));
+  send_email(endpoint, subject, plain, html).await
+}
+
+/// Queues the alert to be sent with the next digest,
+/// see [send_digests].
+pub async fn queue_digest(alerter: &Alerter, alert: &Alert) {
+  let AlerterEndpoint::Email(endpoint) = &alerter.config.endpoint
+  else {
+    return;
+  };
+  let mut queue = digest_queue().lock().await;
+  let entry = queue.entry(alerter.id.clone()).or_insert_with(|| {
+    (alerter.name.clone(), endpoint.clone(), Vec::new())
+  });
+  // Always send the digest using the latest config.
+  entry.0.clone_from(&alerter.name);
+  entry.1 = endpoint.clone();
+  entry.2.push(alert.clone());
+}
+
+/// Sends all the queued alerts, one email per digest alerter.
+/// Called at the end of every monitoring cycle, so alerts sent outside
+/// the monitoring loop (eg. `BuildFailed`) go out with the next cycle's digest.
+#[instrument(level = "debug")]
+pub async fn send_digests() {
+  let queued = std::mem::take(&mut *digest_queue().lock().await);
+  let handles =
+    queued
+      .into_values()
+      .map(|(name, endpoint, alerts)| async move {
+        send_digest(&endpoint, &alerts).await.with_context(|| {
+          format!(
+            "failed to send alert digest to Email alerter {name}"
+          )
+        })
+      });
+  join_all(handles)
+    .await
+    .into_iter()
+    .filter_map(|res| res.err())
+    .for_each(|e| error!("{e:#}"));
+}
+
+async fn send_digest(
+  endpoint: &EmailAlerterEndpoint,
+  alerts: &[Alert],
+) -> anyhow::Result<()> {
+  let alerts = alerts
+    .iter()
+    .filter_map(|alert| Some((alert, alert_content(alert)?)))
+    .collect::<Vec<_>>();
+  if alerts.is_empty() {
+    return Ok(());
+  }
+  let count = |level: SeverityLevel| {
+    alerts
+      .iter()
+      .filter(|(alert, _)| alert.level == level)
+      .count()
+  };
+  let subject = format!(
+    "Komodo | {} alerts ({} critical, {} warning, {} ok)",
+    alerts.len(),
+    count(SeverityLevel::Critical),
+    count(SeverityLevel::Warning),
+    count(SeverityLevel::Ok),
+  );
+  let plain = alerts
+    .iter()
+    .map(|(alert, content)| fmt_plain(alert, content))
+    .collect::<Vec<_>>()
+    .join("\n\n---\n\n");
+  let html = alerts
+    .iter()
+    .map(|(alert, content)| fmt_html(alert, content))
+    .collect::<Vec<_>>()
+    .join("<hr />");
+  send_email(endpoint, subject, plain, fmt_html_document(&html)).await
+}
+
+async fn send_email(
+  EmailAlerterEndpoint {
+    host,
+    port,
+    security,
+    username,
+    password,
+    from,
+    to,
+    ..
+  }: &EmailAlerterEndpoint,
+  subject: String,
+  plain: String,
+  html: String,
+) -> anyhow::Result<()> {
+  if to.is_empty() {
+    return Err(anyhow!("no recipients configured"));
+  }
+  let mut message = Message::builder().subject(subject).from(
+    from
+      .parse::<Mailbox>()
+      .with_context(|| format!("invalid from address: {from}"))?,
+  );
+  for to in to {
+    message = message.to(
+      to.parse::<Mailbox>()
+        .with_context(|| format!("invalid to address: {to}"))?,
+    );
+  }
+  let message = message
+    .multipart(MultiPart::alternative_plain_html(plain, html))
+    .context("failed to build email message")?;
+
+  let mut transport = match security {
+    SmtpSecurity::None => {
+      AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)
+    }
+    SmtpSecurity::StartTls => {
+      AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+        .context("failed to initialize STARTTLS smtp transport")?
+    }
+    SmtpSecurity::Tls => {
+      AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+        .context("failed to initialize TLS smtp transport")?
+    }
+  }
+  .port(*port);
+  if !username.is_empty() {
+    transport = transport.credentials(Credentials::new(
+      username.clone(),
+      interpolate_password(password).await?,
+    ));
+  }
+
+  transport
+    .build()
+    .send(message)
+    .await
+    .context("failed to send email")?;
+
+  Ok(())
+}
+
+/// The password references a Variable or core secret,
+/// so it isn't stored on the alerter.
+async fn interpolate_password(
+  password: &str,
+) -> anyhow::Result<String> {
+  if password.is_empty() {
+    return Ok(String::new());
+  }
+  let VariablesAndSecrets { variables, secrets } =
+    get_variables_and_secrets().await?;
+  let (password, _) = svi::interpolate_variables(
+    password,
+    &variables,
+    svi::Interpolator::DoubleBrackets,
+    false,
+  )
+  .context(
+    "failed to interpolate global variables into smtp password",
+  )?;
+  let (password, _) = svi::interpolate_variables(
+    &password,
+    &secrets,
+    svi::Interpolator::DoubleBrackets,
+    true,
+  )
+  .context("failed to interpolate core secrets into smtp password")?;
+  Ok(password)
+}
+
+fn fmt_plain(
+  alert: &Alert,
+  AlertContent {
+    title,
+    details,
+    link,
+  }: &AlertContent,
+) -> String {
+  let mut lines =
+    vec![format!("{} | {title}", fmt_level(alert.level))];
+  lines
+    .extend(details.iter().map(|line| line.replace(['*', '`'], "")));
+  if let Some(link) = link {
+    lines.push(link.clone());
+  }
+  lines.join("\n")
+}
+
+fn fmt_html(
+  alert: &Alert,
+  AlertContent {
+    title,
+    details,
+    link,
+  }: &AlertContent,
+) -> String {
+  let color = level_color(alert.level);
+  let mut html = format!(
+    "<h3 style=\"color: #{color:06x}\">{} | {}</h3>",
+    fmt_level(alert.level),
+    escape_html(title)
+  );
+  if !details.is_empty() {
+    let details = details
+      .iter()
+      .map(|line| fmt_html_line(line))
+      .collect::<Vec<_>>()
+      .join("<br />");
+    html.push_str(&format!("<p>{details}</p>"));
+  }
+  if let Some(link) = link {
+    let link = escape_html(link);
+    html.push_str(&format!(
+      "<p><a href=\"{link}\">Open in Komodo</a></p>"
+    ));
+  }
+  html
+}
+
+fn fmt_html_document(body: &str) -> String {
+  format!(
+    "<!DOCTYPE html><html><body style=\"font-family: sans-serif\">{body}</body></html>"
+  )
+}
+
+/// Escapes the line, and converts the markdown
+/// `**bold**` and `` `code` `` spans to html.
+fn fmt_html_line(line: &str) -> String {
+  let mut res = String::new();
+  for (i, part) in escape_html(line).split("**").enumerate() {
+    if i % 2 == 1 {
+      res.push_str("<b>");
+      res.push_str(part);
+      res.push_str("</b>");
+    } else {
+      res.push_str(part);
+    }
+  }
+  let mut html = String::new();
+  for (i, part) in res.split('`').enumerate() {
+    if i % 2 == 1 {
+      html.push_str("<code>");
+      html.push_str(part);
+      html.push_str("</code>");
+    } else {
+      html.push_str(part);
+    }
+  }
+  html
+}
+
+fn escape_html(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
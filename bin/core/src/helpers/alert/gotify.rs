@@ -0,0 +1,51 @@
+use komodo_client::entities::alert::Alert;
+use serde_json::json;
+
+use super::*;
+
+#[instrument(level = "debug", skip(token))]
+pub async fn send_alert(
+  url: &str,
+  token: &str,
+  alert: &Alert,
+) -> anyhow::Result<()> {
+  let Some(AlertContent {
+    title,
+    details,
+    link,
+  }) = alert_content(alert)
+  else {
+    return Ok(());
+  };
+  let priority = match alert.level {
+    SeverityLevel::Critical => 8,
+    SeverityLevel::Warning => 5,
+    SeverityLevel::Ok => 2,
+  };
+  let mut message = details.join("\n\n");
+  let mut extras = json!({
+    "client::display": { "contentType": "text/markdown" },
+  });
+  if let Some(link) = link {
+    if !message.is_empty() {
+      message.push_str("\n\n");
+    }
+    message.push_str(&format!("[Open in Komodo]({link})"));
+    extras["client::notification"] =
+      json!({ "click": { "url": link } });
+  }
+  let body = json!({
+    "title": format!("{} | {title}", fmt_level(alert.level)),
+    "message": message,
+    "priority": priority,
+    "extras": extras,
+  });
+  let url = format!("{}/message", url.trim_end_matches('/'));
+  send_request(
+    reqwest::Client::new()
+      .post(url)
+      .header("X-Gotify-Key", token)
+      .json(&body),
+  )
+  .await
+}
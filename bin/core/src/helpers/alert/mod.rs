@@ -0,0 +1,444 @@
+use anyhow::{anyhow, Context};
+use derive_variants::ExtractVariant;
+use futures::future::join_all;
+use komodo_client::entities::{
+  alert::{Alert, AlertData, SeverityLevel},
+  alerter::*,
+  deployment::DeploymentState,
+  stack::StackState,
+  ResourceTargetVariant,
+};
+use mungos::{find::find_collect, mongodb::bson::doc};
+
+use crate::{config::core_config, state::db_client};
+
+mod discord;
+mod email;
+mod gotify;
+mod ntfy;
+mod silence;
+mod slack;
+mod teams;
+
+pub use silence::silence_alerts;
+
+#[instrument]
+pub async fn send_alerts(alerts: &[Alert]) {
+  if alerts.is_empty() {
+    return;
+  }
+
+  let Ok(alerters) = find_collect(
+    &db_client().await.alerters,
+    doc! { "config.enabled": true },
+    None,
+  )
+  .await
+  .inspect_err(|e| {
+    error!(
+      "ERROR sending alerts | failed to get alerters from db | {e:#}"
+    )
+  }) else {
+    return;
+  };
+
+  // Alerts are marked silenced by silence_alerts
+  // before being recorded / sent.
+  let handles = alerts
+    .iter()
+    .filter(|alert| !alert.silenced)
+    .map(|alert| send_alert(&alerters, alert));
+
+  join_all(handles).await;
+}
+
+#[instrument(level = "debug")]
+async fn send_alert(alerters: &[Alerter], alert: &Alert) {
+  if alerters.is_empty() {
+    return;
+  }
+
+  let alert_type = alert.data.extract_variant();
+
+  let handles = alerters.iter().map(|alerter| async {
+    // Don't send if not enabled
+    if !alerter.config.enabled {
+      return Ok(());
+    }
+
+    // Don't send if alert type not configured on the alerter
+    if !alerter.config.alert_types.is_empty()
+      && !alerter.config.alert_types.contains(&alert_type)
+    {
+      return Ok(());
+    }
+
+    // Don't send if resource is in the blacklist
+    if alerter.config.except_resources.contains(&alert.target) {
+      return Ok(());
+    }
+
+    // Don't send if whitelist configured and target is not included
+    if !alerter.config.resources.is_empty()
+      && !alerter.config.resources.contains(&alert.target)
+    {
+      return Ok(());
+    }
+
+    let endpoint_type = alerter.config.endpoint.extract_variant();
+
+    match &alerter.config.endpoint {
+      AlerterEndpoint::Slack(SlackAlerterEndpoint { url }) => {
+        slack::send_alert(url, alert).await
+      }
+      AlerterEndpoint::Custom(CustomAlerterEndpoint { url }) => {
+        send_custom_alert(url, alert).await
+      }
+      AlerterEndpoint::Discord(DiscordAlerterEndpoint { url }) => {
+        discord::send_alert(url, alert).await
+      }
+      AlerterEndpoint::Teams(TeamsAlerterEndpoint { url }) => {
+        teams::send_alert(url, alert).await
+      }
+      AlerterEndpoint::Ntfy(NtfyAlerterEndpoint { url, token }) => {
+        ntfy::send_alert(url, token, alert).await
+      }
+      AlerterEndpoint::Gotify(GotifyAlerterEndpoint {
+        url,
+        token,
+      }) => gotify::send_alert(url, token, alert).await,
+      AlerterEndpoint::Email(endpoint) => {
+        if endpoint.digest {
+          email::queue_digest(alerter, alert).await;
+          Ok(())
+        } else {
+          email::send_alert(endpoint, alert).await
+        }
+      }
+    }
+    .with_context(|| {
+      format!(
+        "failed to send alert to {endpoint_type} alerter {}",
+        alerter.name
+      )
+    })
+  });
+
+  join_all(handles)
+    .await
+    .into_iter()
+    .filter_map(|res| res.err())
+    .for_each(|e| error!("{e:#}"));
+}
+
+/// Sends the alerts queued for Email alerters in digest mode.
+/// Called at the end of every monitoring cycle.
+pub async fn send_alert_digests() {
+  email::send_digests().await
+}
+
+#[instrument(level = "debug")]
+async fn send_custom_alert(
+  url: &str,
+  alert: &Alert,
+) -> anyhow::Result<()> {
+  send_request(reqwest::Client::new().post(url).json(alert)).await
+}
+
+/// Sends the request, and returns an error including
+/// the response text if the alerter does not respond with success.
+async fn send_request(
+  request: reqwest::RequestBuilder,
+) -> anyhow::Result<()> {
+  let res = request
+    .send()
+    .await
+    .context("failed at post request to alerter")?;
+  let status = res.status();
+  if !status.is_success() {
+    let text = res
+      .text()
+      .await
+      .context("failed to get response text on alerter response")?;
+    return Err(anyhow!(
+      "post to alerter failed | {status} | {text}"
+    ));
+  }
+  Ok(())
+}
+
+/// The platform agnostic content of an alert,
+/// rendered by the markdown based endpoints
+/// (Discord, Teams, Ntfy, Gotify, Email).
+struct AlertContent {
+  /// The alert headline, not including the severity level.
+  title: String,
+  /// Additional markdown formatted lines.
+  details: Vec<String>,
+  /// Link to the alert target in the Komodo UI.
+  link: Option<String>,
+}
+
+/// Returns `None` for alerts which should not be sent (`AlertData::None`).
+fn alert_content(alert: &Alert) -> Option<AlertContent> {
+  let content = match &alert.data {
+    AlertData::ServerUnreachable {
+      id,
+      name,
+      region,
+      err,
+    } => {
+      let region = fmt_region(region);
+      let (title, details) = match alert.level {
+        SeverityLevel::Ok => {
+          (format!("{name}{region} is now reachable"), Vec::new())
+        }
+        _ => (
+          format!("{name}{region} is unreachable ❌"),
+          err
+            .as_ref()
+            .map(|e| vec![format!("error: {e:#?}")])
+            .unwrap_or_default(),
+        ),
+      };
+      AlertContent {
+        title,
+        details,
+        link: resource_link(ResourceTargetVariant::Server, id).into(),
+      }
+    }
+    AlertData::ServerCpu {
+      id,
+      name,
+      region,
+      percentage,
+    } => {
+      let region = fmt_region(region);
+      let icon = match alert.level {
+        SeverityLevel::Ok => "",
+        _ => " 📈",
+      };
+      AlertContent {
+        title: format!(
+          "{name}{region} cpu usage at {percentage:.1}%{icon}"
+        ),
+        details: Vec::new(),
+        link: resource_link(ResourceTargetVariant::Server, id).into(),
+      }
+    }
+    AlertData::ServerMem {
+      id,
+      name,
+      region,
+      used_gb,
+      total_gb,
+    } => {
+      let region = fmt_region(region);
+      let percentage = 100.0 * used_gb / total_gb;
+      AlertContent {
+        title: format!(
+          "{name}{region} memory usage at {percentage:.1}% 💾"
+        ),
+        details: vec![format!(
+          "using **{used_gb:.1} GiB** / **{total_gb:.1} GiB**"
+        )],
+        link: resource_link(ResourceTargetVariant::Server, id).into(),
+      }
+    }
+    AlertData::ServerDisk {
+      id,
+      name,
+      region,
+      path,
+      used_gb,
+      total_gb,
+    } => {
+      let region = fmt_region(region);
+      let percentage = 100.0 * used_gb / total_gb;
+      AlertContent {
+        title: format!(
+          "{name}{region} disk usage at {percentage:.1}% 💿"
+        ),
+        details: vec![
+          format!("mount point: `{}`", path.display()),
+          format!(
+            "using **{used_gb:.1} GiB** / **{total_gb:.1} GiB**"
+          ),
+        ],
+        link: resource_link(ResourceTargetVariant::Server, id).into(),
+      }
+    }
+    AlertData::ContainerStateChange {
+      id,
+      name,
+      server_name,
+      from,
+      to,
+      ..
+    } => AlertContent {
+      title: format!(
+        "📦 Container {name} is now {}",
+        fmt_docker_container_state(to)
+      ),
+      details: vec![
+        format!("server: **{server_name}**"),
+        format!("previous: **{from}**"),
+      ],
+      link: resource_link(ResourceTargetVariant::Deployment, id)
+        .into(),
+    },
+    AlertData::StackStateChange {
+      id,
+      name,
+      server_name,
+      from,
+      to,
+      ..
+    } => AlertContent {
+      title: format!(
+        "🥞 Stack {name} is now {}",
+        fmt_stack_state(to)
+      ),
+      details: vec![
+        format!("server: **{server_name}**"),
+        format!("previous: **{from}**"),
+      ],
+      link: resource_link(ResourceTargetVariant::Stack, id).into(),
+    },
+    AlertData::AwsBuilderTerminationFailed {
+      instance_id,
+      message,
+    } => AlertContent {
+      title: String::from("Failed to terminate AWS builder instance"),
+      details: vec![
+        format!("instance id: **{instance_id}**"),
+        message.clone(),
+      ],
+      link: None,
+    },
+    AlertData::ResourceSyncPendingUpdates { id, name } => {
+      AlertContent {
+        title: format!("Pending resource sync updates on {name}"),
+        details: vec![format!("sync id: **{id}**")],
+        link: resource_link(ResourceTargetVariant::ResourceSync, id)
+          .into(),
+      }
+    }
+    AlertData::BuildFailed { id, name, version } => AlertContent {
+      title: format!("Build {name} has failed"),
+      details: vec![
+        format!("build id: **{id}**"),
+        format!("version: **v{version}**"),
+      ],
+      link: resource_link(ResourceTargetVariant::Build, id).into(),
+    },
+    AlertData::BuildVulnerabilities {
+      id,
+      name,
+      version,
+      image,
+      severity,
+      count,
+      threshold,
+    } => AlertContent {
+      title: format!("Build {name} image has vulnerabilities"),
+      details: vec![
+        format!("build id: **{id}**"),
+        format!("version: **v{version}**"),
+        format!("image: **{image}**"),
+        format!(
+          "**{count}** vulnerabilities at or above **{severity}** (threshold: {threshold})"
+        ),
+      ],
+      link: resource_link(ResourceTargetVariant::Build, id).into(),
+    },
+    AlertData::RepoBuildFailed { id, name } => AlertContent {
+      title: format!("Repo build for {name} has failed"),
+      details: vec![format!("repo id: **{id}**")],
+      link: resource_link(ResourceTargetVariant::Repo, id).into(),
+    },
+    AlertData::None {} => return None,
+  };
+  Some(content)
+}
+
+fn fmt_region(region: &Option<String>) -> String {
+  match region {
+    Some(region) => format!(" ({region})"),
+    None => String::new(),
+  }
+}
+
+fn fmt_docker_container_state(state: &DeploymentState) -> String {
+  match state {
+    DeploymentState::Running => String::from("Running ▶️"),
+    DeploymentState::Exited => String::from("Exited 🛑"),
+    DeploymentState::Restarting => String::from("Restarting 🔄"),
+    DeploymentState::NotDeployed => String::from("Not Deployed"),
+    _ => state.to_string(),
+  }
+}
+
+fn fmt_stack_state(state: &StackState) -> String {
+  match state {
+    StackState::Running => String::from("Running ▶️"),
+    StackState::Stopped => String::from("Stopped 🛑"),
+    StackState::Restarting => String::from("Restarting 🔄"),
+    StackState::Down => String::from("Down ⬇️"),
+    _ => state.to_string(),
+  }
+}
+
+fn fmt_level(level: SeverityLevel) -> &'static str {
+  match level {
+    SeverityLevel::Critical => "CRITICAL 🚨",
+    SeverityLevel::Warning => "WARNING ‼️",
+    SeverityLevel::Ok => "OK ✅",
+  }
+}
+
+/// RGB color used to highlight the alert by severity.
+fn level_color(level: SeverityLevel) -> u32 {
+  match level {
+    SeverityLevel::Critical => 0xDC2626,
+    SeverityLevel::Warning => 0xF59E0B,
+    SeverityLevel::Ok => 0x16A34A,
+  }
+}
+
+fn resource_link(
+  resource_type: ResourceTargetVariant,
+  id: &str,
+) -> String {
+  let path = match resource_type {
+    ResourceTargetVariant::System => unreachable!(),
+    ResourceTargetVariant::Build => format!("/builds/{id}"),
+    ResourceTargetVariant::Builder => {
+      format!("/builders/{id}")
+    }
+    ResourceTargetVariant::Deployment => {
+      format!("/deployments/{id}")
+    }
+    ResourceTargetVariant::Stack => {
+      format!("/stacks/{id}")
+    }
+    ResourceTargetVariant::Server => {
+      format!("/servers/{id}")
+    }
+    ResourceTargetVariant::Repo => format!("/repos/{id}"),
+    ResourceTargetVariant::Alerter => {
+      format!("/alerters/{id}")
+    }
+    ResourceTargetVariant::Procedure => {
+      format!("/procedures/{id}")
+    }
+    ResourceTargetVariant::ServerTemplate => {
+      format!("/server-templates/{id}")
+    }
+    ResourceTargetVariant::ResourceSync => {
+      format!("/resource-syncs/{id}")
+    }
+  };
+
+  format!("{}{path}", core_config().host)
+}
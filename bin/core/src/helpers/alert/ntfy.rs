@@ -0,0 +1,55 @@
+use anyhow::Context;
+use komodo_client::entities::alert::Alert;
+use serde_json::json;
+
+use super::*;
+
+/// Publishes the alert using the ntfy JSON api.
+/// The configured url is the full topic url,
+/// eg. `https://ntfy.sh/komodo`.
+#[instrument(level = "debug", skip(token))]
+pub async fn send_alert(
+  url: &str,
+  token: &str,
+  alert: &Alert,
+) -> anyhow::Result<()> {
+  let Some(AlertContent {
+    title,
+    details,
+    link,
+  }) = alert_content(alert)
+  else {
+    return Ok(());
+  };
+  let (server, topic) =
+    url.trim_end_matches('/').rsplit_once('/').context(
+      "ntfy url must include the topic, eg. https://ntfy.sh/komodo",
+    )?;
+  let (priority, tag) = match alert.level {
+    SeverityLevel::Critical => (5, "rotating_light"),
+    SeverityLevel::Warning => (4, "warning"),
+    SeverityLevel::Ok => (3, "white_check_mark"),
+  };
+  let message = if details.is_empty() {
+    // ntfy requires a non-empty message
+    title.clone()
+  } else {
+    details.join("\n")
+  };
+  let mut body = json!({
+    "topic": topic,
+    "title": format!("{} | {title}", alert.level),
+    "message": message,
+    "priority": priority,
+    "tags": [tag],
+    "markdown": true,
+  });
+  if let Some(link) = link {
+    body["click"] = link.into();
+  }
+  let mut request = reqwest::Client::new().post(server).json(&body);
+  if !token.is_empty() {
+    request = request.bearer_auth(token);
+  }
+  send_request(request).await
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+use derive_variants::ExtractVariant;
+use komodo_client::entities::{
+  alert::Alert, komodo_timestamp, silence::Silence, ResourceTarget,
+};
+use mungos::{find::find_collect, mongodb::bson::doc};
+
+use crate::{helpers::query::get_target_tags, state::db_client};
+
+/// Marks `silenced` on the alerts, based on whether they
+/// match any silence active right now. This is the only place
+/// silences are checked, call before recording / sending the alerts.
+/// Silenced alerts are not sent by `send_alerts`.
+pub async fn silence_alerts(alerts: Vec<&mut Alert>) {
+  let silences = active_silences().await;
+  let mut tags = TargetTags::default();
+  for alert in alerts {
+    alert.silenced = is_silenced(&silences, &mut tags, alert).await;
+  }
+}
+
+/// Get the silences active right now.
+/// Failing to query the silences must not stop alerts,
+/// so errors are logged and treated as no silences.
+async fn active_silences() -> Vec<Silence> {
+  let ts = komodo_timestamp();
+  find_collect(
+    &db_client().await.silences,
+    doc! { "start_ts": { "$lte": ts }, "end_ts": { "$gt": ts } },
+    None,
+  )
+  .await
+  .context("failed to query db for active silences")
+  .inspect_err(|e| error!("{e:#}"))
+  .unwrap_or_default()
+}
+
+/// Caches resource tags while checking a batch of alerts.
+type TargetTags = HashMap<ResourceTarget, Vec<String>>;
+
+async fn is_silenced(
+  silences: &[Silence],
+  tags: &mut TargetTags,
+  alert: &Alert,
+) -> bool {
+  let alert_type = alert.data.extract_variant();
+  for silence in silences {
+    if !silence.alert_types.is_empty()
+      && !silence.alert_types.contains(&alert_type)
+    {
+      continue;
+    }
+    if !silence.resources.is_empty()
+      && !silence.resources.contains(&alert.target)
+    {
+      continue;
+    }
+    if silence.tags.is_empty() {
+      return true;
+    }
+    if !tags.contains_key(&alert.target) {
+      let target_tags = get_target_tags(&alert.target)
+        .await
+        .inspect_err(|e| {
+          warn!("failed to get tags for alert target | {e:#}")
+        })
+        .unwrap_or_default();
+      tags.insert(alert.target.clone(), target_tags);
+    }
+    let target_tags = &tags[&alert.target];
+    if silence.tags.iter().any(|tag| target_tags.contains(tag)) {
+      return true;
+    }
+  }
+  false
+}
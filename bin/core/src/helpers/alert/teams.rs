@@ -0,0 +1,71 @@
+use komodo_client::entities::alert::Alert;
+use serde_json::json;
+
+use super::*;
+
+/// Sends the alert as an adaptive card,
+/// supported by both Teams workflows and incoming webhooks.
+#[instrument(level = "debug")]
+pub async fn send_alert(
+  url: &str,
+  alert: &Alert,
+) -> anyhow::Result<()> {
+  let Some(AlertContent {
+    title,
+    details,
+    link,
+  }) = alert_content(alert)
+  else {
+    return Ok(());
+  };
+  let color = match alert.level {
+    SeverityLevel::Critical => "Attention",
+    SeverityLevel::Warning => "Warning",
+    SeverityLevel::Ok => "Good",
+  };
+  let mut body = vec![
+    json!({
+      "type": "TextBlock",
+      "text": fmt_level(alert.level),
+      "weight": "Bolder",
+      "color": color,
+    }),
+    json!({
+      "type": "TextBlock",
+      "text": title,
+      "size": "Medium",
+      "weight": "Bolder",
+      "wrap": true,
+    }),
+  ];
+  body.extend(details.into_iter().map(|detail| {
+    json!({
+      "type": "TextBlock",
+      "text": detail,
+      "wrap": true,
+    })
+  }));
+  let actions = link
+    .map(|url| {
+      vec![json!({
+        "type": "Action.OpenUrl",
+        "title": "Open in Komodo",
+        "url": url,
+      })]
+    })
+    .unwrap_or_default();
+  let card = json!({
+    "type": "message",
+    "attachments": [{
+      "contentType": "application/vnd.microsoft.card.adaptive",
+      "content": {
+        "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+        "type": "AdaptiveCard",
+        "version": "1.4",
+        "body": body,
+        "actions": actions,
+      }
+    }]
+  });
+  send_request(reqwest::Client::new().post(url).json(&card)).await
+}
@@ -40,9 +40,11 @@ pub mod procedure;
 pub mod prune;
 pub mod query;
 pub mod repo;
+pub mod schedule;
 pub mod stack;
 pub mod sync;
 pub mod update;
+pub mod webhook;
 
 // pub mod resource;
 
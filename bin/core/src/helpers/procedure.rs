@@ -1,14 +1,25 @@
-use std::time::{Duration, Instant};
+use std::{
+  collections::{HashMap, HashSet},
+  time::{Duration, Instant},
+};
 
 use anyhow::{anyhow, Context};
 use formatting::{bold, colored, format_serror, muted, Color};
 use futures::future::join_all;
 use komodo_client::{
-  api::execute::Execution,
+  api::execute::{CancelBuild, CancelRepoBuild, Execution},
   entities::{
-    procedure::Procedure,
+    build::Build,
+    deployment::Deployment,
+    procedure::{
+      EnabledExecution, Procedure, ProcedureStage, StageCondition,
+      StageRunOn,
+    },
+    server::Server,
+    stack::Stack,
     update::{Log, Update},
-    user::procedure_user,
+    user::{procedure_user, User},
+    Operation, ResourceTarget,
   },
 };
 use mungos::by_id::find_one_by_id;
@@ -17,119 +28,479 @@ use tokio::sync::Mutex;
 
 use crate::{
   api::execute::ExecuteRequest,
+  auth::{api_key_scope, with_api_key_scope},
+  monitor::update_cache_for_server,
+  resource,
   state::{db_client, State},
 };
 
-use super::update::{init_execution_update, update_update};
+use super::{
+  query::{
+    get_deployment_state, get_server_with_state, get_stack_state,
+  },
+  update::{init_execution_update, update_update},
+};
 
 #[instrument(skip_all)]
 pub async fn execute_procedure(
   procedure: &Procedure,
+  args: &HashMap<String, String>,
+  // The user running the procedure
+  user: &User,
   update: &Mutex<Update>,
 ) -> anyhow::Result<()> {
+  // The ids of builds successfully run by this procedure run,
+  // used by the `BuildNewVersion` stage condition.
+  let built = Mutex::new(HashSet::new());
+  // The error of the first failed stage.
+  // Later stages only run if their `run_on` allows it.
+  let mut failure: Option<anyhow::Error> = None;
   for stage in &procedure.config.stages {
     if !stage.enabled {
       continue;
     }
-    add_line_to_update(
-      update,
-      &format!(
-        "{}: executing stage: '{}'",
-        muted("INFO"),
+    let should_run = match stage.run_on {
+      StageRunOn::Success => failure.is_none(),
+      StageRunOn::Failure => failure.is_some(),
+      StageRunOn::Always => true,
+    };
+    if !should_run {
+      add_line_to_update(
+        update,
+        &format!(
+          "{}: skipping stage '{}' (run on: {})",
+          muted("INFO"),
+          bold(&stage.name),
+          stage.run_on,
+        ),
+      )
+      .await;
+      continue;
+    }
+    let condition_met =
+      stage_condition_met(&stage.condition, &built).await;
+    let res = match condition_met {
+      Ok(true) => {
+        execute_procedure_stage(
+          procedure, stage, args, user, update, &built,
+        )
+        .await
+      }
+      Ok(false) => {
+        add_line_to_update(
+          update,
+          &format!(
+            "{}: skipping stage '{}', condition not met: {:?}",
+            muted("INFO"),
+            bold(&stage.name),
+            stage.condition,
+          ),
+        )
+        .await;
+        continue;
+      }
+      Err(e) => Err(e.context(format!(
+        "failed to evaluate stage '{}' condition",
         bold(&stage.name)
-      ),
-    )
-    .await;
-    let timer = Instant::now();
-    execute_stage(
-      stage
-        .executions
-        .iter()
-        .filter(|item| item.enabled)
-        .map(|item| item.execution.clone())
-        .collect(),
-      &procedure.id,
-      &procedure.name,
-      update,
-    )
-    .await
-    .with_context(|| {
-      format!(
-        "failed stage '{}' execution after {:?}",
-        bold(&stage.name),
-        timer.elapsed(),
+      ))),
+    };
+    if let Err(e) = res {
+      add_line_to_update(
+        update,
+        &format!(
+          "{}: stage '{}' failed | {e:#}",
+          colored("ERROR", Color::Red),
+          bold(&stage.name),
+        ),
       )
-    })?;
-    add_line_to_update(
-      update,
-      &format!(
-        "{}: {} stage '{}' execution in {:?}",
-        muted("INFO"),
-        colored("finished", Color::Green),
-        bold(&stage.name),
-        timer.elapsed()
-      ),
+      .await;
+      if failure.is_none() {
+        failure = Some(e);
+      }
+    }
+  }
+
+  match failure {
+    Some(e) => Err(e),
+    None => Ok(()),
+  }
+}
+
+async fn execute_procedure_stage(
+  procedure: &Procedure,
+  stage: &ProcedureStage,
+  args: &HashMap<String, String>,
+  user: &User,
+  update: &Mutex<Update>,
+  built: &Mutex<HashSet<String>>,
+) -> anyhow::Result<()> {
+  add_line_to_update(
+    update,
+    &format!(
+      "{}: executing stage: '{}'",
+      muted("INFO"),
+      bold(&stage.name)
+    ),
+  )
+  .await;
+  let timer = Instant::now();
+  execute_stage(
+    stage
+      .executions
+      .iter()
+      .filter(|item| item.enabled)
+      .cloned()
+      .collect(),
+    &procedure.id,
+    &procedure.name,
+    args,
+    user,
+    update,
+    built,
+  )
+  .await
+  .with_context(|| {
+    format!(
+      "failed stage '{}' execution after {:?}",
+      bold(&stage.name),
+      timer.elapsed(),
     )
-    .await;
+  })?;
+  add_line_to_update(
+    update,
+    &format!(
+      "{}: {} stage '{}' execution in {:?}",
+      muted("INFO"),
+      colored("finished", Color::Green),
+      bold(&stage.name),
+      timer.elapsed()
+    ),
+  )
+  .await;
+  Ok(())
+}
+
+/// Interpolates the procedure args into the execution, eg. `[[env]]-api`.
+/// The resulting targets are then validated against the user running the procedure.
+async fn interpolate_args(
+  execution: &mut Execution,
+  args: &HashMap<String, String>,
+  procedure_id: &str,
+  user: &User,
+) -> anyhow::Result<()> {
+  if !resource::uses_procedure_args(execution) {
+    return Ok(());
   }
+  // The args are interpolated into the serialized execution,
+  // so they must be escaped as json strings.
+  let args = args
+    .iter()
+    .map(|(name, value)| {
+      let value = serde_json::to_string(value)?;
+      Ok((name.clone(), value[1..value.len() - 1].to_string()))
+    })
+    .collect::<anyhow::Result<HashMap<_, _>>>()?;
+  let serialized = serde_json::to_string(execution)
+    .context("failed to serialize execution")?;
+  let (interpolated, _) = svi::interpolate_variables(
+    &serialized,
+    &args,
+    svi::Interpolator::DoubleBrackets,
+    true,
+  )
+  .context("failed to interpolate procedure args into execution")?;
+  *execution = serde_json::from_str(&interpolated)
+    .context("failed to parse execution after interpolating args")?;
+  resource::validate_execution(execution, user, Some(procedure_id))
+    .await
+}
 
+/// `built` holds the ids of builds successfully run by earlier
+/// stages of this procedure run. Builds run elsewhere in the
+/// meantime don't satisfy `BuildNewVersion`.
+///
+/// The deployment / stack status is refreshed before it is checked,
+/// as earlier stages may have just changed it.
+async fn stage_condition_met(
+  condition: &StageCondition,
+  built: &Mutex<HashSet<String>>,
+) -> anyhow::Result<bool> {
+  match condition {
+    StageCondition::None => Ok(true),
+    StageCondition::BuildNewVersion { build } => {
+      let build = resource::get::<Build>(build).await?;
+      Ok(built.lock().await.contains(&build.id))
+    }
+    StageCondition::ServerState { server, state } => {
+      let (_, curr) = get_server_with_state(server).await?;
+      Ok(curr == *state)
+    }
+    StageCondition::DeploymentState { deployment, state } => {
+      let deployment =
+        resource::get::<Deployment>(deployment).await?;
+      refresh_server_cache(&deployment.config.server_id).await?;
+      Ok(get_deployment_state(&deployment).await? == *state)
+    }
+    StageCondition::StackState { stack, state } => {
+      let stack = resource::get::<Stack>(stack).await?;
+      refresh_server_cache(&stack.config.server_id).await?;
+      Ok(get_stack_state(&stack).await? == *state)
+    }
+  }
+}
+
+async fn refresh_server_cache(server_id: &str) -> anyhow::Result<()> {
+  if server_id.is_empty() {
+    return Ok(());
+  }
+  let server = resource::get::<Server>(server_id).await?;
+  update_cache_for_server(&server).await;
   Ok(())
 }
 
-#[instrument(skip(update))]
+#[instrument(skip(update, built))]
 async fn execute_stage(
-  executions: Vec<Execution>,
+  executions: Vec<EnabledExecution>,
   parent_id: &str,
   parent_name: &str,
+  args: &HashMap<String, String>,
+  user: &User,
   update: &Mutex<Update>,
+  built: &Mutex<HashSet<String>>,
 ) -> anyhow::Result<()> {
-  let futures = executions.into_iter().map(|execution| async move {
+  let futures = executions.into_iter().map(|mut item| async move {
+    let res = match interpolate_args(
+      &mut item.execution,
+      args,
+      parent_id,
+      user,
+    )
+    .await
+    {
+      Ok(_) => {
+        execute_with_retries(
+          &item,
+          parent_id,
+          parent_name,
+          user,
+          update,
+        )
+        .await
+      }
+      Err(e) => Err(e),
+    };
+    match res {
+      Ok(res) => {
+        if let (Operation::RunBuild, ResourceTarget::Build(id)) =
+          (res.operation, res.target)
+        {
+          built.lock().await.insert(id);
+        }
+        Ok(())
+      }
+      Err(e) if item.continue_on_error => {
+        add_line_to_update(
+          update,
+          &format!(
+            "{}: continuing after failed execution: {:?} | {e:#}",
+            colored("WARN", Color::Orange),
+            item.execution,
+          ),
+        )
+        .await;
+        Ok(())
+      }
+      Err(e) => Err(e),
+    }
+  });
+  join_all(futures)
+    .await
+    .into_iter()
+    .collect::<anyhow::Result<_>>()?;
+  Ok(())
+}
+
+/// Runs the execution, retrying up to `item.retries` times on failure.
+/// Returns the error of the last attempt if all attempts fail.
+async fn execute_with_retries(
+  item: &EnabledExecution,
+  parent_id: &str,
+  parent_name: &str,
+  user: &User,
+  update: &Mutex<Update>,
+) -> anyhow::Result<Update> {
+  let execution = &item.execution;
+  let attempts = item.retries.max(0) + 1;
+  let mut attempt = 1;
+  loop {
     let now = Instant::now();
+    let attempt_log = if attempts > 1 {
+      format!(" (attempt {attempt}/{attempts})")
+    } else {
+      String::new()
+    };
     add_line_to_update(
       update,
-      &format!("{}: executing: {execution:?}", muted("INFO")),
+      &format!(
+        "{}: executing{attempt_log}: {execution:?}",
+        muted("INFO")
+      ),
     )
     .await;
     let fail_log = format!(
       "{}: failed on {execution:?}",
       colored("ERROR", Color::Red)
     );
-    let res =
-      execute_execution(execution.clone(), parent_id, parent_name)
-        .await
-        .context(fail_log);
+    let Some(res) = execute_with_timeout(
+      execution.clone(),
+      item.timeout_ms,
+      parent_id,
+      parent_name,
+      user,
+    )
+    .await
+    else {
+      // The timed out execution may still be running,
+      // so it is never retried.
+      return Err(anyhow!(
+        "execution{attempt_log} timed out after {}ms, not retrying | {execution:?}",
+        item.timeout_ms
+      ))
+      .context(fail_log);
+    };
+    let res = res.context(fail_log);
+    let e = match res {
+      Ok(res) => {
+        add_line_to_update(
+          update,
+          &format!(
+            "{}: {} execution{attempt_log} in {:?}: {execution:?}",
+            muted("INFO"),
+            colored("finished", Color::Green),
+            now.elapsed()
+          ),
+        )
+        .await;
+        return Ok(res);
+      }
+      Err(e) => e,
+    };
+    if attempt >= attempts {
+      return Err(e);
+    }
     add_line_to_update(
       update,
       &format!(
-        "{}: {} execution in {:?}: {execution:?}",
-        muted("INFO"),
-        colored("finished", Color::Green),
-        now.elapsed()
+        "{}: execution{attempt_log} failed after {:?}, retrying in {}ms | {e:#}",
+        colored("WARN", Color::Orange),
+        now.elapsed(),
+        item.retry_delay_ms.max(0),
       ),
     )
     .await;
-    res
-  });
-  join_all(futures)
-    .await
-    .into_iter()
-    .collect::<anyhow::Result<_>>()?;
-  Ok(())
+    tokio::time::sleep(Duration::from_millis(
+      item.retry_delay_ms.max(0) as u64,
+    ))
+    .await;
+    attempt += 1;
+  }
 }
 
+/// Returns None if the execution timed out.
+///
+/// The execution runs on its own task, so timing out never drops
+/// it mid-flight (which would leave its Update in progress).
+/// On timeout, the execution is cancelled if it supports it,
+/// otherwise it is left to finish on its own.
+async fn execute_with_timeout(
+  execution: Execution,
+  timeout_ms: i64,
+  parent_id: &str,
+  parent_name: &str,
+  caller: &User,
+) -> Option<anyhow::Result<Update>> {
+  if timeout_ms <= 0 {
+    return Some(
+      execute_execution(execution, parent_id, parent_name, caller)
+        .await,
+    );
+  }
+  let cancel = cancel_execution(&execution);
+  let handle = {
+    let parent_id = parent_id.to_string();
+    let parent_name = parent_name.to_string();
+    let caller = caller.clone();
+    tokio::spawn(with_api_key_scope(api_key_scope(), async move {
+      execute_execution(execution, &parent_id, &parent_name, &caller)
+        .await
+    }))
+  };
+  let timeout = Duration::from_millis(timeout_ms as u64);
+  match tokio::time::timeout(timeout, handle).await {
+    Ok(res) => {
+      Some(res.context("execution task failed").and_then(|res| res))
+    }
+    Err(_) => {
+      if let Some(cancel) = cancel {
+        if let Err(e) =
+          execute_execution(cancel, parent_id, parent_name, caller)
+            .await
+        {
+          warn!("failed to cancel timed out execution | {e:#}");
+        }
+      }
+      None
+    }
+  }
+}
+
+/// The execution which cancels the given one,
+/// for executions which support cancellation.
+fn cancel_execution(execution: &Execution) -> Option<Execution> {
+  match execution {
+    Execution::RunBuild(req) => {
+      Some(Execution::CancelBuild(CancelBuild {
+        build: req.build.clone(),
+      }))
+    }
+    Execution::BuildRepo(req) => {
+      Some(Execution::CancelRepoBuild(CancelRepoBuild {
+        repo: req.repo.clone(),
+      }))
+    }
+    _ => None,
+  }
+}
+
+/// Returns the finished Update if the execution succeeded.
 async fn execute_execution(
   execution: Execution,
   // used to prevent recursive procedure
   parent_id: &str,
   parent_name: &str,
-) -> anyhow::Result<()> {
+  // the user running the parent procedure
+  caller: &User,
+) -> anyhow::Result<Update> {
   let user = procedure_user().to_owned();
   let update = match execution {
-    Execution::None(_) => return Ok(()),
+    Execution::None(_) => {
+      return Ok(Update {
+        success: true,
+        ..Default::default()
+      })
+    }
     Execution::RunProcedure(req) => {
       if req.procedure == parent_id || req.procedure == parent_name {
         return Err(anyhow!("Self referential procedure detected"));
       }
+      // The nested procedure will validate the executions
+      // it interpolates args into against the user running it,
+      // so it must run as the caller when passing args.
+      let user = if req.args.is_empty() {
+        user
+      } else {
+        caller.clone()
+      };
       let req = ExecuteRequest::RunProcedure(req);
       let update = init_execution_update(&req, &user).await?;
       let ExecuteRequest::RunProcedure(req) = req else {
@@ -193,6 +564,22 @@ async fn execute_execution(
       )
       .await?
     }
+    Execution::RollbackDeployment(req) => {
+      let req = ExecuteRequest::RollbackDeployment(req);
+      let update = init_execution_update(&req, &user).await?;
+      let ExecuteRequest::RollbackDeployment(req) = req else {
+        unreachable!()
+      };
+      let update_id = update.id.clone();
+      handle_resolve_result(
+        State
+          .resolve(req, (user, update))
+          .await
+          .context("failed at RollbackDeployment"),
+        &update_id,
+      )
+      .await?
+    }
     Execution::StartDeployment(req) => {
       let req = ExecuteRequest::StartDeployment(req);
       let update = init_execution_update(&req, &user).await?;
@@ -829,7 +1216,7 @@ async fn execute_execution(
     }
   };
   if update.success {
-    Ok(())
+    Ok(update)
   } else {
     Err(anyhow!(
       "{}: execution not successful. see update '{}'",
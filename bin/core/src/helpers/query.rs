@@ -12,6 +12,7 @@ use komodo_client::entities::{
   repo::Repo,
   server::{Server, ServerState},
   server_template::ServerTemplate,
+  silence::Silence,
   stack::{Stack, StackServiceNames, StackState},
   sync::ResourceSync,
   tag::Tag,
@@ -22,6 +23,7 @@ use komodo_client::entities::{
   Operation, ResourceTarget, ResourceTargetVariant,
 };
 use mungos::{
+  by_id::find_one_by_id,
   find::find_collect,
   mongodb::{
     bson::{doc, oid::ObjectId, Document},
@@ -325,6 +327,95 @@ pub async fn get_variable(name: &str) -> anyhow::Result<Variable> {
     })
 }
 
+pub async fn get_silence(id: &str) -> anyhow::Result<Silence> {
+  find_one_by_id(&db_client().await.silences, id)
+    .await
+    .context("failed at call to db")?
+    .with_context(|| format!("no silence found with id {id}"))
+}
+
+/// Get the tag ids attached to the target resource.
+/// Targets which don't support tags (System) return an empty list.
+pub async fn get_target_tags(
+  target: &ResourceTarget,
+) -> anyhow::Result<Vec<String>> {
+  let tags = match target {
+    ResourceTarget::System(_) => Vec::new(),
+    ResourceTarget::Build(id) => {
+      resource::get::<Build>(id).await?.tags
+    }
+    ResourceTarget::Builder(id) => {
+      resource::get::<Builder>(id).await?.tags
+    }
+    ResourceTarget::Deployment(id) => {
+      resource::get::<Deployment>(id).await?.tags
+    }
+    ResourceTarget::Server(id) => {
+      resource::get::<Server>(id).await?.tags
+    }
+    ResourceTarget::Repo(id) => resource::get::<Repo>(id).await?.tags,
+    ResourceTarget::Alerter(id) => {
+      resource::get::<Alerter>(id).await?.tags
+    }
+    ResourceTarget::Procedure(id) => {
+      resource::get::<Procedure>(id).await?.tags
+    }
+    ResourceTarget::ServerTemplate(id) => {
+      resource::get::<ServerTemplate>(id).await?.tags
+    }
+    ResourceTarget::ResourceSync(id) => {
+      resource::get::<ResourceSync>(id).await?.tags
+    }
+    ResourceTarget::Stack(id) => {
+      resource::get::<Stack>(id).await?.tags
+    }
+  };
+  Ok(tags)
+}
+
+/// Returns the target with the resource name replaced by its id.
+/// Targets which are already ids are checked to exist.
+pub async fn get_target_with_id(
+  target: &ResourceTarget,
+) -> anyhow::Result<ResourceTarget> {
+  let target = match target {
+    ResourceTarget::System(id) => ResourceTarget::System(id.clone()),
+    ResourceTarget::Build(id) => {
+      ResourceTarget::Build(resource::get::<Build>(id).await?.id)
+    }
+    ResourceTarget::Builder(id) => {
+      ResourceTarget::Builder(resource::get::<Builder>(id).await?.id)
+    }
+    ResourceTarget::Deployment(id) => ResourceTarget::Deployment(
+      resource::get::<Deployment>(id).await?.id,
+    ),
+    ResourceTarget::Server(id) => {
+      ResourceTarget::Server(resource::get::<Server>(id).await?.id)
+    }
+    ResourceTarget::Repo(id) => {
+      ResourceTarget::Repo(resource::get::<Repo>(id).await?.id)
+    }
+    ResourceTarget::Alerter(id) => {
+      ResourceTarget::Alerter(resource::get::<Alerter>(id).await?.id)
+    }
+    ResourceTarget::Procedure(id) => ResourceTarget::Procedure(
+      resource::get::<Procedure>(id).await?.id,
+    ),
+    ResourceTarget::ServerTemplate(id) => {
+      ResourceTarget::ServerTemplate(
+        resource::get::<ServerTemplate>(id).await?.id,
+      )
+    }
+    ResourceTarget::ResourceSync(id) => ResourceTarget::ResourceSync(
+      resource::get::<ResourceSync>(id).await?.id,
+    ),
+    ResourceTarget::Stack(id) => {
+      ResourceTarget::Stack(resource::get::<Stack>(id).await?.id)
+    }
+  };
+  Ok(target)
+}
+
 pub async fn get_latest_update(
   resource_type: ResourceTargetVariant,
   id: &str,
@@ -0,0 +1,212 @@
+use std::{
+  collections::HashMap,
+  str::FromStr,
+  sync::{Mutex, OnceLock},
+};
+
+use anyhow::{anyhow, Context};
+use async_timing_util::{
+  get_timelength_in_ms, wait_until_timelength, Timelength,
+};
+use chrono::TimeZone;
+use komodo_client::{
+  api::execute::RunProcedure,
+  entities::{
+    komodo_timestamp,
+    procedure::{Procedure, ScheduleFormat},
+    user::procedure_user,
+  },
+};
+use mungos::find::find_collect;
+use resolver_api::Resolve;
+
+use crate::{
+  api::execute::ExecuteRequest,
+  helpers::update::init_execution_update,
+  state::{db_client, State},
+};
+
+struct ScheduleEntry {
+  format: ScheduleFormat,
+  schedule: String,
+  timezone: String,
+  /// Unix timestamp (ms) of the next run,
+  /// or the error if the schedule is invalid.
+  next_run: Result<i64, String>,
+}
+
+/// Procedure id -> ScheduleEntry
+type ScheduleCache = Mutex<HashMap<String, ScheduleEntry>>;
+
+fn schedules() -> &'static ScheduleCache {
+  static SCHEDULES: OnceLock<ScheduleCache> = OnceLock::new();
+  SCHEDULES.get_or_init(Default::default)
+}
+
+pub fn spawn_schedule_loop() {
+  tokio::spawn(async move {
+    init_schedules().await;
+    loop {
+      let ts =
+        wait_until_timelength(Timelength::OneSecond, 0).await as i64;
+      run_due_procedures(ts);
+    }
+  });
+}
+
+async fn init_schedules() {
+  let Ok(procedures) =
+    find_collect(&db_client().await.procedures, None, None)
+      .await
+      .inspect_err(|e| {
+        warn!(
+          "failed to get procedures from db to init schedules | {e:#}"
+        )
+      })
+  else {
+    return;
+  };
+  for procedure in &procedures {
+    update_schedule(procedure);
+  }
+}
+
+/// Recompute the next run of the procedure schedule.
+/// Should be called whenever the procedure is created / updated.
+pub fn update_schedule(procedure: &Procedure) {
+  let config = &procedure.config;
+  let mut schedules = schedules().lock().unwrap();
+  if !config.schedule_enabled || config.schedule.is_empty() {
+    schedules.remove(&procedure.id);
+    return;
+  }
+  let next_run = find_next_run(
+    config.schedule_format,
+    &config.schedule,
+    &config.schedule_timezone,
+    komodo_timestamp(),
+  )
+  .map_err(|e| format!("{e:#}"));
+  schedules.insert(
+    procedure.id.clone(),
+    ScheduleEntry {
+      format: config.schedule_format,
+      schedule: config.schedule.clone(),
+      timezone: config.schedule_timezone.clone(),
+      next_run,
+    },
+  );
+}
+
+pub fn remove_schedule(procedure_id: &str) {
+  schedules().lock().unwrap().remove(procedure_id);
+}
+
+/// Returns (next run, schedule error) for the procedure.
+pub fn get_schedule_info(
+  procedure_id: &str,
+) -> (Option<i64>, Option<String>) {
+  let schedules = schedules().lock().unwrap();
+  match schedules.get(procedure_id).map(|entry| &entry.next_run) {
+    Some(Ok(next_run)) => (Some(*next_run), None),
+    Some(Err(e)) => (None, Some(e.clone())),
+    None => (None, None),
+  }
+}
+
+/// Finds the next run of the schedule strictly after the given timestamp (ms).
+///
+/// - `Cron`: 5 field expressions are given second `0`.
+///   Evaluated in the given IANA timezone, or UTC if empty.
+/// - `Interval`: Runs are aligned to the unix epoch.
+pub fn find_next_run(
+  format: ScheduleFormat,
+  schedule: &str,
+  timezone: &str,
+  after_ts: i64,
+) -> anyhow::Result<i64> {
+  let schedule = schedule.trim();
+  match format {
+    ScheduleFormat::Cron => {
+      let schedule = if schedule.split_whitespace().count() == 5 {
+        format!("0 {schedule}")
+      } else {
+        schedule.to_string()
+      };
+      let schedule = cron::Schedule::from_str(&schedule)
+        .context("Invalid cron expression")?;
+      let tz = if timezone.is_empty() {
+        chrono_tz::UTC
+      } else {
+        chrono_tz::Tz::from_str(timezone)
+          .map_err(|e| anyhow!("{e}"))
+          .with_context(|| format!("Invalid timezone: {timezone}"))?
+      };
+      let after = tz
+        .timestamp_millis_opt(after_ts)
+        .single()
+        .context("Invalid timestamp")?;
+      schedule
+        .after(&after)
+        .next()
+        .map(|next| next.timestamp_millis())
+        .context("Cron expression has no upcoming runs")
+    }
+    ScheduleFormat::Interval => {
+      let interval =
+        Timelength::from_str(schedule).with_context(|| {
+          format!("Invalid interval: {schedule}. Use eg. '1-hr'")
+        })?;
+      let interval = get_timelength_in_ms(interval) as i64;
+      Ok((after_ts / interval + 1) * interval)
+    }
+  }
+}
+
+fn run_due_procedures(ts: i64) {
+  let due = {
+    let mut schedules = schedules().lock().unwrap();
+    schedules
+      .iter_mut()
+      .filter_map(|(id, entry)| {
+        let next_run = *entry.next_run.as_ref().ok()?;
+        if next_run > ts {
+          return None;
+        }
+        entry.next_run = find_next_run(
+          entry.format,
+          &entry.schedule,
+          &entry.timezone,
+          ts,
+        )
+        .map_err(|e| format!("{e:#}"));
+        Some(id.clone())
+      })
+      .collect::<Vec<_>>()
+  };
+  for id in due {
+    tokio::spawn(async move {
+      if let Err(e) = run_scheduled_procedure(id.clone()).await {
+        warn!("failed to run scheduled procedure {id} | {e:#}");
+      }
+    });
+  }
+}
+
+/// Runs as the procedure user, so the run is recorded
+/// in the procedure history like any other run.
+async fn run_scheduled_procedure(
+  procedure: String,
+) -> anyhow::Result<()> {
+  let user = procedure_user().to_owned();
+  let req = ExecuteRequest::RunProcedure(RunProcedure {
+    procedure,
+    args: Default::default(),
+  });
+  let update = init_execution_update(&req, &user).await?;
+  let ExecuteRequest::RunProcedure(req) = req else {
+    unreachable!()
+  };
+  State.resolve(req, (user, update)).await?;
+  Ok(())
+}
@@ -7,7 +7,7 @@ use komodo_client::{
     build::Build,
     builder::{Builder, BuilderConfig},
     deployment::{Deployment, DeploymentImage},
-    procedure::Procedure,
+    procedure::{Procedure, StageCondition},
     repo::Repo,
     server::Server,
     server_template::ServerTemplate,
@@ -225,6 +225,16 @@ impl ResourceSync for entities::sync::ResourceSync {
   }
 }
 
+/// Keeps `[[arg]]` templates, which only resolve when the
+/// procedure runs. Other unknown ids map to an empty string.
+fn arg_template(target: &str) -> String {
+  if target.contains("[[") {
+    target.to_string()
+  } else {
+    String::new()
+  }
+}
+
 impl ResourceSync for Procedure {
   fn resource_target(id: String) -> ResourceTarget {
     ResourceTarget::Procedure(id)
@@ -235,6 +245,8 @@ impl ResourceSync for Procedure {
     update: Self::PartialConfig,
     resources: &AllResourcesById,
   ) -> anyhow::Result<Self::ConfigDiff> {
+    // `[[arg]]` templates are kept for the diff,
+    // other unknown ids are cleared.
     for stage in &mut original.stages {
       for execution in &mut stage.executions {
         match &mut execution.execution {
@@ -244,305 +256,343 @@ impl ResourceSync for Procedure {
               .procedures
               .get(&config.procedure)
               .map(|p| p.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.procedure));
           }
           Execution::RunBuild(config) => {
             config.build = resources
               .builds
               .get(&config.build)
               .map(|b| b.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.build));
           }
           Execution::CancelBuild(config) => {
             config.build = resources
               .builds
               .get(&config.build)
               .map(|b| b.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.build));
           }
           Execution::Deploy(config) => {
             config.deployment = resources
               .deployments
               .get(&config.deployment)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.deployment));
+          }
+          Execution::RollbackDeployment(config) => {
+            config.deployment = resources
+              .deployments
+              .get(&config.deployment)
+              .map(|d| d.name.clone())
+              .unwrap_or_else(|| arg_template(&config.deployment));
           }
           Execution::StartDeployment(config) => {
             config.deployment = resources
               .deployments
               .get(&config.deployment)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.deployment));
           }
           Execution::RestartDeployment(config) => {
             config.deployment = resources
               .deployments
               .get(&config.deployment)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.deployment));
           }
           Execution::PauseDeployment(config) => {
             config.deployment = resources
               .deployments
               .get(&config.deployment)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.deployment));
           }
           Execution::UnpauseDeployment(config) => {
             config.deployment = resources
               .deployments
               .get(&config.deployment)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.deployment));
           }
           Execution::StopDeployment(config) => {
             config.deployment = resources
               .deployments
               .get(&config.deployment)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.deployment));
           }
           Execution::DestroyDeployment(config) => {
             config.deployment = resources
               .deployments
               .get(&config.deployment)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.deployment));
           }
           Execution::CloneRepo(config) => {
             config.repo = resources
               .repos
               .get(&config.repo)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.repo));
           }
           Execution::PullRepo(config) => {
             config.repo = resources
               .repos
               .get(&config.repo)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.repo));
           }
           Execution::BuildRepo(config) => {
             config.repo = resources
               .repos
               .get(&config.repo)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.repo));
           }
           Execution::CancelRepoBuild(config) => {
             config.repo = resources
               .repos
               .get(&config.repo)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.repo));
           }
           Execution::StartContainer(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::RestartContainer(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::PauseContainer(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::UnpauseContainer(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::StopContainer(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::DestroyContainer(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::StartAllContainers(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::RestartAllContainers(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::PauseAllContainers(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::UnpauseAllContainers(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::StopAllContainers(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::PruneContainers(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::DeleteNetwork(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::PruneNetworks(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::DeleteImage(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::PruneImages(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::DeleteVolume(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::PruneVolumes(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::PruneDockerBuilders(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::PruneBuildx(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::PruneSystem(config) => {
             config.server = resources
               .servers
               .get(&config.server)
               .map(|d| d.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.server));
           }
           Execution::RunSync(config) => {
             config.sync = resources
               .syncs
               .get(&config.sync)
               .map(|s| s.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.sync));
           }
           Execution::DeployStack(config) => {
             config.stack = resources
               .stacks
               .get(&config.stack)
               .map(|s| s.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.stack));
           }
           Execution::StartStack(config) => {
             config.stack = resources
               .stacks
               .get(&config.stack)
               .map(|s| s.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.stack));
           }
           Execution::RestartStack(config) => {
             config.stack = resources
               .stacks
               .get(&config.stack)
               .map(|s| s.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.stack));
           }
           Execution::PauseStack(config) => {
             config.stack = resources
               .stacks
               .get(&config.stack)
               .map(|s| s.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.stack));
           }
           Execution::UnpauseStack(config) => {
             config.stack = resources
               .stacks
               .get(&config.stack)
               .map(|s| s.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.stack));
           }
           Execution::StopStack(config) => {
             config.stack = resources
               .stacks
               .get(&config.stack)
               .map(|s| s.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.stack));
           }
           Execution::DestroyStack(config) => {
             config.stack = resources
               .stacks
               .get(&config.stack)
               .map(|s| s.name.clone())
-              .unwrap_or_default();
+              .unwrap_or_else(|| arg_template(&config.stack));
           }
           Execution::Sleep(_) => {}
         }
       }
+      match &mut stage.condition {
+        StageCondition::None => {}
+        StageCondition::BuildNewVersion { build } => {
+          *build = resources
+            .builds
+            .get(build)
+            .map(|b| b.name.clone())
+            .unwrap_or_else(|| arg_template(build));
+        }
+        StageCondition::ServerState { server, .. } => {
+          *server = resources
+            .servers
+            .get(server)
+            .map(|s| s.name.clone())
+            .unwrap_or_else(|| arg_template(server));
+        }
+        StageCondition::DeploymentState { deployment, .. } => {
+          *deployment = resources
+            .deployments
+            .get(deployment)
+            .map(|d| d.name.clone())
+            .unwrap_or_else(|| arg_template(deployment));
+        }
+        StageCondition::StackState { stack, .. } => {
+          *stack = resources
+            .stacks
+            .get(stack)
+            .map(|s| s.name.clone())
+            .unwrap_or_else(|| arg_template(stack));
+        }
+      }
     }
     Ok(original.partial_diff(update))
   }
@@ -1,4 +1,4 @@
-use std::{cmp::Ordering, collections::HashMap};
+use std::{cmp::Ordering, collections::HashMap, hash::Hash};
 
 use anyhow::Context;
 use formatting::{bold, colored, muted, Color};
@@ -7,7 +7,8 @@ use komodo_client::{
     read::ListUserTargetPermissions,
     write::{
       CreateUserGroup, DeleteUserGroup, SetUsersInUserGroup,
-      UpdatePermissionOnResourceType, UpdatePermissionOnTarget,
+      UpdatePermissionOnResourceType, UpdatePermissionOnTag,
+      UpdatePermissionOnTarget,
     },
   },
   entities::{
@@ -31,6 +32,7 @@ pub struct UpdateItem {
   user_group: UserGroupToml,
   update_users: bool,
   all_diff: HashMap<ResourceTargetVariant, PermissionLevel>,
+  tags_diff: HashMap<String, PermissionLevel>,
 }
 
 pub struct DeleteItem {
@@ -73,6 +75,13 @@ pub async fn get_updates_for_view(
     .map(|user| (user.id.clone(), user))
     .collect::<HashMap<_, _>>();
 
+  let tag_names = find_collect(&db_client().await.tags, None, None)
+    .await
+    .context("failed to query db for Tags")?
+    .into_iter()
+    .map(|tag| (tag.id, tag.name))
+    .collect::<HashMap<_, _>>();
+
   for mut user_group in user_groups {
     user_group
       .permissions
@@ -117,6 +126,13 @@ pub async fn get_updates_for_view(
             user_group.permissions,
           ));
         }
+        if !user_group.tags.is_empty() {
+          update.log.push_str(&format!(
+            "\n{}: {:#?}",
+            muted("tag permissions"),
+            user_group.tags,
+          ));
+        }
         continue;
       }
     };
@@ -226,17 +242,23 @@ pub async fn get_updates_for_view(
     user_group.users.sort();
 
     let all_diff = diff_group_all(&original.all, &user_group.all);
+    let tags_diff = diff_group_all(
+      &tag_ids_to_names(original.tags, &tag_names),
+      &user_group.tags,
+    );
 
     user_group.permissions.sort_by(sort_permissions);
     original_permissions.sort_by(sort_permissions);
 
     let update_users = user_group.users != original_users;
     let update_all = !all_diff.is_empty();
+    let update_tags = !tags_diff.is_empty();
     let update_permissions =
       user_group.permissions != original_permissions;
 
     // only add log after diff detected
-    if update_users || update_all || update_permissions {
+    if update_users || update_all || update_tags || update_permissions
+    {
       update.to_update += 1;
       update.log.push_str(&format!(
         "\n\n{}: user group: '{}'\n-------------------",
@@ -296,6 +318,26 @@ pub async fn get_updates_for_view(
         ))
       }
 
+      if update_tags {
+        let updates = tags_diff
+          .into_iter()
+          .map(|(tag, (orig, incoming))| {
+            format!(
+              "{}: {} {} {}",
+              bold(tag),
+              colored(orig, Color::Red),
+              muted("->"),
+              colored(incoming, Color::Green)
+            )
+          })
+          .collect::<Vec<_>>()
+          .join("\n");
+        lines.push(format!(
+          "{}: 'tag permission'\n{updates}",
+          muted("field"),
+        ))
+      }
+
       if update_permissions {
         let adding = user_group
           .permissions
@@ -421,6 +463,13 @@ pub async fn get_updates_for_execution(
     .map(|user| (user.id.clone(), user))
     .collect::<HashMap<_, _>>();
 
+  let tag_names = find_collect(&db_client().await.tags, None, None)
+    .await
+    .context("failed to query db for Tags")?
+    .into_iter()
+    .map(|tag| (tag.id, tag.name))
+    .collect::<HashMap<_, _>>();
+
   for mut user_group in user_groups {
     user_group
       .permissions
@@ -551,6 +600,10 @@ pub async fn get_updates_for_execution(
     user_group.users.sort();
 
     let all_diff = diff_group_all(&original.all, &user_group.all);
+    let tags_diff = diff_group_all(
+      &tag_ids_to_names(original.tags, &tag_names),
+      &user_group.tags,
+    );
 
     user_group.permissions.sort_by(sort_permissions);
     original_permissions.sort_by(sort_permissions);
@@ -590,6 +643,7 @@ pub async fn get_updates_for_execution(
     // only push update after diff detected
     if update_users
       || !all_diff.is_empty()
+      || !tags_diff.is_empty()
       || !user_group.permissions.is_empty()
     {
       to_update.push(UpdateItem {
@@ -599,6 +653,10 @@ pub async fn get_updates_for_execution(
           .into_iter()
           .map(|(k, (_, v))| (k, v))
           .collect(),
+        tags_diff: tags_diff
+          .into_iter()
+          .map(|(k, (_, v))| (k, v))
+          .collect(),
       });
     }
   }
@@ -679,6 +737,13 @@ pub async fn run_updates(
       &mut has_error,
     )
     .await;
+    run_update_tags(
+      user_group.name.clone(),
+      user_group.tags,
+      &mut log,
+      &mut has_error,
+    )
+    .await;
     run_update_permissions(
       user_group.name,
       user_group.permissions,
@@ -693,6 +758,7 @@ pub async fn run_updates(
     user_group,
     update_users,
     all_diff,
+    tags_diff,
   } in to_update
   {
     if update_users {
@@ -713,6 +779,15 @@ pub async fn run_updates(
       )
       .await;
     }
+    if !tags_diff.is_empty() {
+      run_update_tags(
+        user_group.name.clone(),
+        tags_diff,
+        &mut log,
+        &mut has_error,
+      )
+      .await;
+    }
     if !user_group.permissions.is_empty() {
       run_update_permissions(
         user_group.name,
@@ -823,6 +898,41 @@ async fn run_update_all(
   }
 }
 
+async fn run_update_tags(
+  user_group: String,
+  tags_diff: HashMap<String, PermissionLevel>,
+  log: &mut String,
+  has_error: &mut bool,
+) {
+  for (tag, permission) in tags_diff {
+    if let Err(e) = State
+      .resolve(
+        UpdatePermissionOnTag {
+          user_target: UserTarget::UserGroup(user_group.clone()),
+          tag: tag.clone(),
+          permission,
+        },
+        sync_user().to_owned(),
+      )
+      .await
+    {
+      *has_error = true;
+      log.push_str(&format!(
+        "\n{}: failed to set permissions on tag {tag} in group {} | {e:#}",
+        colored("ERROR", Color::Red),
+        bold(&user_group)
+      ))
+    } else {
+      log.push_str(&format!(
+        "\n{}: {} user group '{}' permissions on tag {tag}",
+        muted("INFO"),
+        colored("updated", Color::Blue),
+        bold(&user_group)
+      ))
+    }
+  }
+}
+
 async fn run_update_permissions(
   user_group: String,
   permissions: Vec<PermissionToml>,
@@ -1007,14 +1117,13 @@ async fn expand_user_group_permissions(
   Ok(expanded)
 }
 
-type AllDiff =
-  HashMap<ResourceTargetVariant, (PermissionLevel, PermissionLevel)>;
+type AllDiff<K> = HashMap<K, (PermissionLevel, PermissionLevel)>;
 
-/// diffs user_group.all
-fn diff_group_all(
-  original: &HashMap<ResourceTargetVariant, PermissionLevel>,
-  incoming: &HashMap<ResourceTargetVariant, PermissionLevel>,
-) -> AllDiff {
+/// diffs user_group.all, or user_group.tags
+fn diff_group_all<K: Clone + Eq + Hash>(
+  original: &HashMap<K, PermissionLevel>,
+  incoming: &HashMap<K, PermissionLevel>,
+) -> AllDiff<K> {
   let mut to_update = HashMap::new();
 
   // need to compare both forward and backward because either hashmap could be sparse.
@@ -1025,7 +1134,7 @@ fn diff_group_all(
     if level == original_level {
       continue;
     }
-    to_update.insert(*variant, (*original_level, *level));
+    to_update.insert(variant.clone(), (*original_level, *level));
   }
 
   // backward direction
@@ -1034,8 +1143,21 @@ fn diff_group_all(
     if level == incoming_level {
       continue;
     }
-    to_update.insert(*variant, (*level, *incoming_level));
+    to_update.insert(variant.clone(), (*level, *incoming_level));
   }
 
   to_update
 }
+
+/// Replaces the tag ids with names, dropping any deleted tags.
+fn tag_ids_to_names(
+  tags: HashMap<String, PermissionLevel>,
+  tag_names: &HashMap<String, String>,
+) -> HashMap<String, PermissionLevel> {
+  tags
+    .into_iter()
+    .filter_map(|(tag_id, level)| {
+      tag_names.get(&tag_id).map(|tag| (tag.clone(), level))
+    })
+    .collect()
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use anyhow::Context;
 use komodo_client::entities::{
   build::Build,
@@ -9,7 +11,7 @@ use komodo_client::entities::{
   server_template::ServerTemplate,
   stack::Stack,
   sync::ResourceSync,
-  update::{Update, UpdateListItem},
+  update::{Log, LogOutput, Update, UpdateListItem},
   user::User,
   Operation, ResourceTarget,
 };
@@ -17,6 +19,8 @@ use mungos::{
   by_id::{find_one_by_id, update_one_by_id},
   mongodb::bson::to_document,
 };
+use periphery_client::PeripheryClient;
+use resolver_api::HasResponse;
 
 use crate::{
   api::execute::ExecuteRequest, resource, state::db_client,
@@ -86,6 +90,81 @@ pub async fn update_update(update: Update) -> anyhow::Result<()> {
   Ok(())
 }
 
+/// How often streamed command output is written to the in progress Update.
+const LIVE_OUTPUT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Makes the periphery request, appending any streamed command output
+/// to the in progress Update so users can follow along live.
+///
+/// The streamed logs are only written to the db copy of the Update.
+/// The caller should add the final logs from the response to its own Update,
+/// which replaces the streamed logs on the next [update_update].
+pub async fn periphery_request_with_live_output<T: HasResponse>(
+  periphery: &PeripheryClient,
+  request: T,
+  mut update: Update,
+) -> anyhow::Result<T::Response> {
+  let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+  let request = periphery.request_streaming(request, move |output| {
+    let _ = tx.send(output);
+  });
+  tokio::pin!(request);
+
+  let base_len = update.logs.len();
+  let mut interval = tokio::time::interval(LIVE_OUTPUT_INTERVAL);
+  let mut pending = false;
+
+  loop {
+    tokio::select! {
+      res = &mut request => return res,
+      Some(output) = rx.recv() => {
+        append_live_output(&mut update, base_len, output);
+        pending = true;
+      }
+      _ = interval.tick(), if pending => {
+        pending = false;
+        if let Err(e) = update_update(update.clone()).await {
+          warn!("failed to write live output to Update {} | {e:#}", update.id);
+        }
+      }
+    }
+  }
+}
+
+fn append_live_output(
+  update: &mut Update,
+  base_len: usize,
+  LogOutput {
+    stage,
+    command,
+    stdout,
+    stderr,
+  }: LogOutput,
+) {
+  let ts = komodo_timestamp();
+  let has_live_log = update.logs.len() > base_len;
+  match update.logs.last_mut() {
+    Some(log)
+      if has_live_log
+        && log.stage == stage
+        && log.command == command =>
+    {
+      log.stdout.push_str(&stdout);
+      log.stderr.push_str(&stderr);
+      log.end_ts = ts;
+    }
+    _ => update.logs.push(Log {
+      stage,
+      command,
+      stdout,
+      stderr,
+      success: true,
+      start_ts: ts,
+      end_ts: ts,
+    }),
+  }
+}
+
 #[instrument(level = "debug")]
 async fn update_list_item(
   update: Update,
@@ -262,6 +341,12 @@ pub async fn init_execution_update(
         resource::get::<Deployment>(&data.deployment).await?.id,
       ),
     ),
+    ExecuteRequest::RollbackDeployment(data) => (
+      Operation::RollbackDeployment,
+      ResourceTarget::Deployment(
+        resource::get::<Deployment>(&data.deployment).await?.id,
+      ),
+    ),
     ExecuteRequest::StartDeployment(data) => (
       Operation::StartDeployment,
       ResourceTarget::Deployment(
@@ -0,0 +1,291 @@
+use anyhow::{anyhow, Context};
+use komodo_client::entities::{
+  config::core::CoreConfig, provider::GitProviderType,
+};
+use mungos::mongodb::bson::doc;
+use reqwest::{Method, RequestBuilder};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::json;
+
+use crate::{config::core_config, state::db_client};
+
+/// Manages repo webhooks on Gitlab and Gitea,
+/// using the token of the git provider account.
+/// Github repo webhooks are managed with the Github webhook app.
+pub struct GitWebhookClient {
+  provider_type: GitProviderType,
+  /// eg. `https://gitlab.com`
+  base_url: String,
+  token: String,
+  http: reqwest::Client,
+}
+
+impl GitWebhookClient {
+  /// Returns None if there is no Gitlab / Gitea git provider account
+  /// matching the domain and username.
+  ///
+  /// Like `git_token`, first checks db for the account,
+  /// then checks core config.
+  pub async fn new(
+    domain: &str,
+    username: &str,
+  ) -> anyhow::Result<Option<GitWebhookClient>> {
+    if username.is_empty() {
+      return Ok(None);
+    }
+    let db_account = db_client()
+      .await
+      .git_accounts
+      .find_one(doc! { "domain": domain, "username": username })
+      .await
+      .context("failed to query db for git provider accounts")?;
+    let (provider_type, https, token) =
+      if let Some(account) = db_account {
+        (account.provider_type, account.https, account.token)
+      } else {
+        let config_account =
+          core_config().git_providers.iter().find_map(|provider| {
+            if provider.domain != domain {
+              return None;
+            }
+            provider
+              .accounts
+              .iter()
+              .find(|account| account.username == username)
+              .map(|account| {
+                (
+                  provider.provider_type,
+                  provider.https,
+                  account.token.clone(),
+                )
+              })
+          });
+        let Some(account) = config_account else {
+          return Ok(None);
+        };
+        account
+      };
+    if provider_type == GitProviderType::Github {
+      return Ok(None);
+    }
+    let protocol = if https { "https" } else { "http" };
+    Ok(Some(GitWebhookClient {
+      provider_type,
+      base_url: format!("{protocol}://{domain}"),
+      token,
+      http: reqwest::Client::new(),
+    }))
+  }
+
+  /// The full listener url for the path, eg. `build/<id>`.
+  pub fn listener_url(&self, path: &str) -> String {
+    let CoreConfig {
+      host,
+      webhook_base_url,
+      ..
+    } = core_config();
+    let host = webhook_base_url.as_ref().unwrap_or(host);
+    let provider = match self.provider_type {
+      GitProviderType::Github => "github",
+      GitProviderType::Gitlab => "gitlab",
+      GitProviderType::Gitea => "gitea",
+    };
+    format!("{host}/listener/{provider}/{path}")
+  }
+
+  /// Whether an active webhook with the url exists on the repo.
+  pub async fn webhook_exists(
+    &self,
+    repo: &str,
+    url: &str,
+  ) -> anyhow::Result<bool> {
+    Ok(self.find_webhook(repo, url).await?.is_some())
+  }
+
+  /// Creates the webhook on the repo, if it doesn't already exist.
+  pub async fn create_webhook(
+    &self,
+    repo: &str,
+    url: &str,
+    secret: &str,
+  ) -> anyhow::Result<()> {
+    if self.find_webhook(repo, url).await?.is_some() {
+      return Ok(());
+    }
+    let body = match self.provider_type {
+      GitProviderType::Gitlab => json!({
+        "url": url,
+        "token": secret,
+        "push_events": true,
+        "enable_ssl_verification": true,
+      }),
+      GitProviderType::Gitea => json!({
+        "type": "gitea",
+        "active": true,
+        "events": ["push"],
+        "config": {
+          "url": url,
+          "content_type": "json",
+          "secret": secret,
+        },
+      }),
+      GitProviderType::Github => unreachable!(),
+    };
+    self
+      .send(self.request(Method::POST, repo, "hooks")?.json(&body))
+      .await
+      .context("failed to create webhook")
+  }
+
+  /// Deletes the webhook from the repo, if it exists.
+  pub async fn delete_webhook(
+    &self,
+    repo: &str,
+    url: &str,
+  ) -> anyhow::Result<()> {
+    let Some(id) = self.find_webhook(repo, url).await? else {
+      // No webhook to delete, all good
+      return Ok(());
+    };
+    self
+      .send(self.request(
+        Method::DELETE,
+        repo,
+        &format!("hooks/{id}"),
+      )?)
+      .await
+      .context("failed to delete webhook")
+  }
+
+  /// Returns the id of the active webhook with the url.
+  /// Pages through the repo webhooks until it is found.
+  async fn find_webhook(
+    &self,
+    repo: &str,
+    url: &str,
+  ) -> anyhow::Result<Option<u64>> {
+    let (page_size, page_size_param) = match self.provider_type {
+      GitProviderType::Gitlab => (100, "per_page"),
+      GitProviderType::Gitea => (50, "limit"),
+      GitProviderType::Github => unreachable!(),
+    };
+    let mut page = 1;
+    loop {
+      let webhooks: Vec<Webhook> = self
+        .send_json(self.request(
+          Method::GET,
+          repo,
+          &format!("hooks?page={page}&{page_size_param}={page_size}"),
+        )?)
+        .await
+        .context("failed to list webhooks on repo")?;
+      let count = webhooks.len();
+      if let Some(webhook) = webhooks.into_iter().find(|webhook| {
+        webhook.active() && webhook.url() == Some(url)
+      }) {
+        return Ok(Some(webhook.id));
+      }
+      if count < page_size {
+        return Ok(None);
+      }
+      page += 1;
+    }
+  }
+
+  fn request(
+    &self,
+    method: Method,
+    repo: &str,
+    path: &str,
+  ) -> anyhow::Result<RequestBuilder> {
+    if repo.is_empty() {
+      return Err(anyhow!("No repo configured"));
+    }
+    let request = match self.provider_type {
+      GitProviderType::Gitlab => {
+        // Gitlab accepts the url encoded namespace / project path
+        // in place of the project id.
+        let project = repo.replace('/', "%2F");
+        self
+          .http
+          .request(
+            method,
+            format!(
+              "{}/api/v4/projects/{project}/{path}",
+              self.base_url
+            ),
+          )
+          .header("PRIVATE-TOKEN", &self.token)
+      }
+      GitProviderType::Gitea => self
+        .http
+        .request(
+          method,
+          format!("{}/api/v1/repos/{repo}/{path}", self.base_url),
+        )
+        .header("Authorization", format!("token {}", self.token)),
+      GitProviderType::Github => unreachable!(),
+    };
+    Ok(request)
+  }
+
+  async fn send(
+    &self,
+    request: RequestBuilder,
+  ) -> anyhow::Result<()> {
+    let res =
+      request.send().await.context("failed to send request")?;
+    let status = res.status();
+    if status.is_success() {
+      Ok(())
+    } else {
+      let text = res.text().await.unwrap_or_default();
+      Err(anyhow!("{status} | {text}"))
+    }
+  }
+
+  async fn send_json<T: DeserializeOwned>(
+    &self,
+    request: RequestBuilder,
+  ) -> anyhow::Result<T> {
+    let res =
+      request.send().await.context("failed to send request")?;
+    let status = res.status();
+    if status.is_success() {
+      res.json().await.context("failed to parse response")
+    } else {
+      let text = res.text().await.unwrap_or_default();
+      Err(anyhow!("{status} | {text}"))
+    }
+  }
+}
+
+/// Covers both the Gitlab and Gitea webhook shapes.
+#[derive(Deserialize)]
+struct Webhook {
+  id: u64,
+  /// Gitlab
+  url: Option<String>,
+  /// Gitea. Gitlab hooks are always active.
+  active: Option<bool>,
+  /// Gitea
+  config: Option<GiteaWebhookConfig>,
+}
+
+#[derive(Deserialize)]
+struct GiteaWebhookConfig {
+  url: String,
+}
+
+impl Webhook {
+  fn active(&self) -> bool {
+    self.active.unwrap_or(true)
+  }
+
+  fn url(&self) -> Option<&str> {
+    self
+      .url
+      .as_deref()
+      .or(self.config.as_ref().map(|config| config.url.as_str()))
+  }
+}
@@ -0,0 +1,72 @@
+use anyhow::Context;
+use axum::http::HeaderMap;
+use serde::Deserialize;
+
+use crate::listener::GitProvider;
+
+use super::{get_header, verify_hmac_sha256};
+
+/// Supports both Bitbucket Cloud and Bitbucket Server / Data Center.
+pub struct Bitbucket;
+
+impl GitProvider for Bitbucket {
+  fn verify_secret(
+    headers: &HeaderMap,
+    body: &str,
+    secret: &str,
+  ) -> anyhow::Result<()> {
+    let signature =
+      get_header(headers, "x-hub-signature")?.replace("sha256=", "");
+    verify_hmac_sha256(&signature, body, secret)
+  }
+
+  fn extract_branch(body: &str) -> anyhow::Result<String> {
+    let body = serde_json::from_str::<BitbucketWebhookBody>(body)
+      .context("failed to parse bitbucket request body")?;
+    match body {
+      BitbucketWebhookBody::Cloud { push } => push
+        .changes
+        .into_iter()
+        .filter_map(|change| change.new)
+        .find(|new| new.ty == "branch")
+        .map(|new| new.name)
+        .context("no branch changes in bitbucket push"),
+      BitbucketWebhookBody::Server { changes } => changes
+        .into_iter()
+        .find(|change| change.ref_id.starts_with("refs/heads/"))
+        .map(|change| change.ref_id.replace("refs/heads/", ""))
+        .context("no branch changes in bitbucket push"),
+    }
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BitbucketWebhookBody {
+  Cloud { push: CloudPush },
+  Server { changes: Vec<ServerChange> },
+}
+
+#[derive(Deserialize)]
+struct CloudPush {
+  changes: Vec<CloudChange>,
+}
+
+#[derive(Deserialize)]
+struct CloudChange {
+  /// Null if the branch was deleted
+  new: Option<CloudChangeNew>,
+}
+
+#[derive(Deserialize)]
+struct CloudChangeNew {
+  #[serde(rename = "type")]
+  ty: String,
+  name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerChange {
+  ref_id: String,
+}
@@ -0,0 +1,24 @@
+use axum::http::HeaderMap;
+
+use crate::listener::GitProvider;
+
+use super::{extract_ref_branch, get_header, verify_hmac_sha256};
+
+/// Also works for Forgejo.
+pub struct Gitea;
+
+impl GitProvider for Gitea {
+  fn verify_secret(
+    headers: &HeaderMap,
+    body: &str,
+    secret: &str,
+  ) -> anyhow::Result<()> {
+    let signature = get_header(headers, "x-gitea-signature")
+      .or_else(|_| get_header(headers, "x-forgejo-signature"))?;
+    verify_hmac_sha256(signature, body, secret)
+  }
+
+  fn extract_branch(body: &str) -> anyhow::Result<String> {
+    extract_ref_branch(body)
+  }
+}
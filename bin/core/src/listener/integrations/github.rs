@@ -0,0 +1,23 @@
+use axum::http::HeaderMap;
+
+use crate::listener::GitProvider;
+
+use super::{extract_ref_branch, get_header, verify_hmac_sha256};
+
+pub struct Github;
+
+impl GitProvider for Github {
+  fn verify_secret(
+    headers: &HeaderMap,
+    body: &str,
+    secret: &str,
+  ) -> anyhow::Result<()> {
+    let signature = get_header(headers, "x-hub-signature-256")?
+      .replace("sha256=", "");
+    verify_hmac_sha256(&signature, body, secret)
+  }
+
+  fn extract_branch(body: &str) -> anyhow::Result<String> {
+    extract_ref_branch(body)
+  }
+}
@@ -0,0 +1,27 @@
+use anyhow::anyhow;
+use axum::http::HeaderMap;
+
+use crate::listener::GitProvider;
+
+use super::{extract_ref_branch, get_header};
+
+pub struct Gitlab;
+
+impl GitProvider for Gitlab {
+  /// Gitlab sends the configured secret token as-is.
+  fn verify_secret(
+    headers: &HeaderMap,
+    _body: &str,
+    secret: &str,
+  ) -> anyhow::Result<()> {
+    if get_header(headers, "x-gitlab-token")? == secret {
+      Ok(())
+    } else {
+      Err(anyhow!("token does not equal expected"))
+    }
+  }
+
+  fn extract_branch(body: &str) -> anyhow::Result<String> {
+    extract_ref_branch(body)
+  }
+}
@@ -0,0 +1,57 @@
+use anyhow::{anyhow, Context};
+use axum::http::HeaderMap;
+use hex::ToHex;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+
+pub mod bitbucket;
+pub mod gitea;
+pub mod github;
+pub mod gitlab;
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn get_header<'a>(
+  headers: &'a HeaderMap,
+  name: &str,
+) -> anyhow::Result<&'a str> {
+  headers
+    .get(name)
+    .with_context(|| format!("no {name} in headers"))?
+    .to_str()
+    .with_context(|| format!("failed to unwrap {name} header"))
+}
+
+/// Checks the signature is the hex encoded HMAC SHA256 of the body.
+fn verify_hmac_sha256(
+  signature: &str,
+  body: &str,
+  secret: &str,
+) -> anyhow::Result<()> {
+  let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+    .context("failed to create hmac sha256")?;
+  mac.update(body.as_bytes());
+  let expected = mac.finalize().into_bytes().encode_hex::<String>();
+  if signature == expected {
+    Ok(())
+  } else {
+    Err(anyhow!("signature does not equal expected"))
+  }
+}
+
+#[derive(Deserialize)]
+struct RefWebhookBody {
+  #[serde(rename = "ref")]
+  branch: String,
+}
+
+/// Github, Gitlab and Gitea push payloads
+/// all give the branch as `ref: refs/heads/<branch>`.
+fn extract_ref_branch(body: &str) -> anyhow::Result<String> {
+  let branch = serde_json::from_str::<RefWebhookBody>(body)
+    .context("failed to parse request body")?
+    .branch
+    .replace("refs/heads/", "");
+  Ok(branch)
+}
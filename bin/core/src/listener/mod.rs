@@ -1,7 +1,72 @@
-use axum::Router;
+use std::sync::Arc;
 
-mod github;
+use anyhow::anyhow;
+use axum::{http::HeaderMap, Router};
+use tokio::sync::Mutex;
+
+use crate::{
+  config::core_config,
+  helpers::{cache::Cache, random_duration},
+};
+
+mod integrations;
+mod resources;
+mod router;
+
+use integrations::{
+  bitbucket::Bitbucket, gitea::Gitea, github::Github, gitlab::Gitlab,
+};
 
 pub fn router() -> Router {
-  Router::new().nest("/github", github::router())
+  Router::new()
+    .nest("/github", router::router::<Github>())
+    .nest("/gitlab", router::router::<Gitlab>())
+    .nest("/gitea", router::router::<Gitea>())
+    .nest("/bitbucket", router::router::<Bitbucket>())
+}
+
+/// Implemented by each git provider integration.
+pub trait GitProvider: Send + Sync + 'static {
+  /// Check the request was sent by the git provider,
+  /// using the provider-specific signature / token header.
+  fn verify_secret(
+    headers: &HeaderMap,
+    body: &str,
+    secret: &str,
+  ) -> anyhow::Result<()>;
+
+  /// Extract the pushed branch from the provider-specific push payload.
+  fn extract_branch(body: &str) -> anyhow::Result<String>;
 }
+
+/// Verifies the request with the resource custom secret,
+/// or the core webhook secret if the custom secret is empty.
+#[instrument(skip_all)]
+async fn verify_secret<P: GitProvider>(
+  headers: &HeaderMap,
+  body: &str,
+  custom_secret: &str,
+) -> anyhow::Result<()> {
+  // wait random amount of time
+  tokio::time::sleep(random_duration(0, 500)).await;
+  let secret = if custom_secret.is_empty() {
+    core_config().webhook_secret.as_str()
+  } else {
+    custom_secret
+  };
+  P::verify_secret(headers, body, secret)
+}
+
+/// Extracts the branch and checks it matches the expected branch.
+fn verify_branch<P: GitProvider>(
+  body: &str,
+  expected: &str,
+) -> anyhow::Result<()> {
+  if P::extract_branch(body)? == expected {
+    Ok(())
+  } else {
+    Err(anyhow!("request branch does not match expected"))
+  }
+}
+
+type ListenerLockCache = Cache<String, Arc<Mutex<()>>>;
@@ -13,14 +13,16 @@ use crate::{
   helpers::update::init_execution_update, resource, state::State,
 };
 
-use super::{extract_branch, verify_gh_signature, ListenerLockCache};
+use crate::listener::{
+  verify_branch, verify_secret, GitProvider, ListenerLockCache,
+};
 
 fn build_locks() -> &'static ListenerLockCache {
   static BUILD_LOCKS: OnceLock<ListenerLockCache> = OnceLock::new();
   BUILD_LOCKS.get_or_init(Default::default)
 }
 
-pub async fn handle_build_webhook(
+pub async fn handle_build_webhook<P: GitProvider>(
   build_id: String,
   headers: HeaderMap,
   body: String,
@@ -33,17 +35,14 @@ pub async fn handle_build_webhook(
 
   let build = resource::get::<Build>(&build_id).await?;
 
-  verify_gh_signature(headers, &body, &build.config.webhook_secret)
+  verify_secret::<P>(&headers, &body, &build.config.webhook_secret)
     .await?;
 
   if !build.config.webhook_enabled {
     return Err(anyhow!("build does not have webhook enabled"));
   }
 
-  let request_branch = extract_branch(&body)?;
-  if request_branch != build.config.branch {
-    return Err(anyhow!("request branch does not match expected"));
-  }
+  verify_branch::<P>(&body, &build.config.branch)?;
 
   let user = git_webhook_user().to_owned();
   let req = ExecuteRequest::RunBuild(RunBuild { build: build_id });
@@ -0,0 +1,5 @@
+pub mod build;
+pub mod procedure;
+pub mod repo;
+pub mod stack;
+pub mod sync;
@@ -13,14 +13,16 @@ use crate::{
   helpers::update::init_execution_update, resource, state::State,
 };
 
-use super::{extract_branch, verify_gh_signature, ListenerLockCache};
+use crate::listener::{
+  verify_branch, verify_secret, GitProvider, ListenerLockCache,
+};
 
 fn procedure_locks() -> &'static ListenerLockCache {
   static BUILD_LOCKS: OnceLock<ListenerLockCache> = OnceLock::new();
   BUILD_LOCKS.get_or_init(Default::default)
 }
 
-pub async fn handle_procedure_webhook(
+pub async fn handle_procedure_webhook<P: GitProvider>(
   procedure_id: String,
   target_branch: String,
   headers: HeaderMap,
@@ -35,8 +37,8 @@ pub async fn handle_procedure_webhook(
 
   let procedure = resource::get::<Procedure>(&procedure_id).await?;
 
-  verify_gh_signature(
-    headers,
+  verify_secret::<P>(
+    &headers,
     &body,
     &procedure.config.webhook_secret,
   )
@@ -46,14 +48,12 @@ pub async fn handle_procedure_webhook(
     return Err(anyhow!("procedure does not have webhook enabled"));
   }
 
-  let request_branch = extract_branch(&body)?;
-  if request_branch != target_branch {
-    return Err(anyhow!("request branch does not match expected"));
-  }
+  verify_branch::<P>(&body, &target_branch)?;
 
   let user = git_webhook_user().to_owned();
   let req = ExecuteRequest::RunProcedure(RunProcedure {
     procedure: procedure_id,
+    args: Default::default(),
   });
   let update = init_execution_update(&req, &user).await?;
   let ExecuteRequest::RunProcedure(req) = req else {
@@ -12,14 +12,16 @@ use crate::{
   helpers::update::init_execution_update, resource, state::State,
 };
 
-use super::{extract_branch, verify_gh_signature, ListenerLockCache};
+use crate::listener::{
+  verify_branch, verify_secret, GitProvider, ListenerLockCache,
+};
 
 fn repo_locks() -> &'static ListenerLockCache {
   static REPO_LOCKS: OnceLock<ListenerLockCache> = OnceLock::new();
   REPO_LOCKS.get_or_init(Default::default)
 }
 
-pub async fn handle_repo_clone_webhook(
+pub async fn handle_repo_clone_webhook<P: GitProvider>(
   repo_id: String,
   headers: HeaderMap,
   body: String,
@@ -32,17 +34,14 @@ pub async fn handle_repo_clone_webhook(
 
   let repo = resource::get::<Repo>(&repo_id).await?;
 
-  verify_gh_signature(headers, &body, &repo.config.webhook_secret)
+  verify_secret::<P>(&headers, &body, &repo.config.webhook_secret)
     .await?;
 
   if !repo.config.webhook_enabled {
     return Err(anyhow!("repo does not have webhook enabled"));
   }
 
-  let request_branch = extract_branch(&body)?;
-  if request_branch != repo.config.branch {
-    return Err(anyhow!("request branch does not match expected"));
-  }
+  verify_branch::<P>(&body, &repo.config.branch)?;
 
   let user = git_webhook_user().to_owned();
   let req =
@@ -58,7 +57,7 @@ pub async fn handle_repo_clone_webhook(
   Ok(())
 }
 
-pub async fn handle_repo_pull_webhook(
+pub async fn handle_repo_pull_webhook<P: GitProvider>(
   repo_id: String,
   headers: HeaderMap,
   body: String,
@@ -71,17 +70,14 @@ pub async fn handle_repo_pull_webhook(
 
   let repo = resource::get::<Repo>(&repo_id).await?;
 
-  verify_gh_signature(headers, &body, &repo.config.webhook_secret)
+  verify_secret::<P>(&headers, &body, &repo.config.webhook_secret)
     .await?;
 
   if !repo.config.webhook_enabled {
     return Err(anyhow!("repo does not have webhook enabled"));
   }
 
-  let request_branch = extract_branch(&body)?;
-  if request_branch != repo.config.branch {
-    return Err(anyhow!("request branch does not match expected"));
-  }
+  verify_branch::<P>(&body, &repo.config.branch)?;
 
   let user = git_webhook_user().to_owned();
   let req = crate::api::execute::ExecuteRequest::PullRepo(PullRepo {
@@ -95,7 +91,7 @@ pub async fn handle_repo_pull_webhook(
   Ok(())
 }
 
-pub async fn handle_repo_build_webhook(
+pub async fn handle_repo_build_webhook<P: GitProvider>(
   repo_id: String,
   headers: HeaderMap,
   body: String,
@@ -108,17 +104,14 @@ pub async fn handle_repo_build_webhook(
 
   let repo = resource::get::<Repo>(&repo_id).await?;
 
-  verify_gh_signature(headers, &body, &repo.config.webhook_secret)
+  verify_secret::<P>(&headers, &body, &repo.config.webhook_secret)
     .await?;
 
   if !repo.config.webhook_enabled {
     return Err(anyhow!("repo does not have webhook enabled"));
   }
 
-  let request_branch = extract_branch(&body)?;
-  if request_branch != repo.config.branch {
-    return Err(anyhow!("request branch does not match expected"));
-  }
+  verify_branch::<P>(&body, &repo.config.branch)?;
 
   let user = git_webhook_user().to_owned();
   let req =
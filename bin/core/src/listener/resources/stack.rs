@@ -13,14 +13,16 @@ use crate::{
   helpers::update::init_execution_update, resource, state::State,
 };
 
-use super::{extract_branch, verify_gh_signature, ListenerLockCache};
+use crate::listener::{
+  verify_branch, verify_secret, GitProvider, ListenerLockCache,
+};
 
 fn stack_locks() -> &'static ListenerLockCache {
   static STACK_LOCKS: OnceLock<ListenerLockCache> = OnceLock::new();
   STACK_LOCKS.get_or_init(Default::default)
 }
 
-pub async fn handle_stack_refresh_webhook(
+pub async fn handle_stack_refresh_webhook<P: GitProvider>(
   stack_id: String,
   headers: HeaderMap,
   body: String,
@@ -33,17 +35,14 @@ pub async fn handle_stack_refresh_webhook(
 
   let stack = resource::get::<Stack>(&stack_id).await?;
 
-  verify_gh_signature(headers, &body, &stack.config.webhook_secret)
+  verify_secret::<P>(&headers, &body, &stack.config.webhook_secret)
     .await?;
 
   if !stack.config.webhook_enabled {
     return Err(anyhow!("stack does not have webhook enabled"));
   }
 
-  let request_branch = extract_branch(&body)?;
-  if request_branch != stack.config.branch {
-    return Err(anyhow!("request branch does not match expected"));
-  }
+  verify_branch::<P>(&body, &stack.config.branch)?;
 
   let user = git_webhook_user().to_owned();
   State
@@ -52,7 +51,7 @@ pub async fn handle_stack_refresh_webhook(
   Ok(())
 }
 
-pub async fn handle_stack_deploy_webhook(
+pub async fn handle_stack_deploy_webhook<P: GitProvider>(
   stack_id: String,
   headers: HeaderMap,
   body: String,
@@ -65,17 +64,14 @@ pub async fn handle_stack_deploy_webhook(
 
   let stack = resource::get::<Stack>(&stack_id).await?;
 
-  verify_gh_signature(headers, &body, &stack.config.webhook_secret)
+  verify_secret::<P>(&headers, &body, &stack.config.webhook_secret)
     .await?;
 
   if !stack.config.webhook_enabled {
     return Err(anyhow!("stack does not have webhook enabled"));
   }
 
-  let request_branch = extract_branch(&body)?;
-  if request_branch != stack.config.branch {
-    return Err(anyhow!("request branch does not match expected"));
-  }
+  verify_branch::<P>(&body, &stack.config.branch)?;
 
   let user = git_webhook_user().to_owned();
   let req = ExecuteRequest::DeployStack(DeployStack {
@@ -13,14 +13,16 @@ use crate::{
   helpers::update::init_execution_update, resource, state::State,
 };
 
-use super::{extract_branch, verify_gh_signature, ListenerLockCache};
+use crate::listener::{
+  verify_branch, verify_secret, GitProvider, ListenerLockCache,
+};
 
 fn sync_locks() -> &'static ListenerLockCache {
   static SYNC_LOCKS: OnceLock<ListenerLockCache> = OnceLock::new();
   SYNC_LOCKS.get_or_init(Default::default)
 }
 
-pub async fn handle_sync_refresh_webhook(
+pub async fn handle_sync_refresh_webhook<P: GitProvider>(
   sync_id: String,
   headers: HeaderMap,
   body: String,
@@ -33,17 +35,14 @@ pub async fn handle_sync_refresh_webhook(
 
   let sync = resource::get::<ResourceSync>(&sync_id).await?;
 
-  verify_gh_signature(headers, &body, &sync.config.webhook_secret)
+  verify_secret::<P>(&headers, &body, &sync.config.webhook_secret)
     .await?;
 
   if !sync.config.webhook_enabled {
     return Err(anyhow!("sync does not have webhook enabled"));
   }
 
-  let request_branch = extract_branch(&body)?;
-  if request_branch != sync.config.branch {
-    return Err(anyhow!("request branch does not match expected"));
-  }
+  verify_branch::<P>(&body, &sync.config.branch)?;
 
   let user = git_webhook_user().to_owned();
   State
@@ -52,7 +51,7 @@ pub async fn handle_sync_refresh_webhook(
   Ok(())
 }
 
-pub async fn handle_sync_execute_webhook(
+pub async fn handle_sync_execute_webhook<P: GitProvider>(
   sync_id: String,
   headers: HeaderMap,
   body: String,
@@ -65,17 +64,14 @@ pub async fn handle_sync_execute_webhook(
 
   let sync = resource::get::<ResourceSync>(&sync_id).await?;
 
-  verify_gh_signature(headers, &body, &sync.config.webhook_secret)
+  verify_secret::<P>(&headers, &body, &sync.config.webhook_secret)
     .await?;
 
   if !sync.config.webhook_enabled {
     return Err(anyhow!("sync does not have webhook enabled"));
   }
 
-  let request_branch = extract_branch(&body)?;
-  if request_branch != sync.config.branch {
-    return Err(anyhow!("request branch does not match expected"));
-  }
+  verify_branch::<P>(&body, &sync.config.branch)?;
 
   let user = git_webhook_user().to_owned();
   let req = ExecuteRequest::RunSync(RunSync { sync: sync_id });
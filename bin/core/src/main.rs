@@ -19,6 +19,7 @@ mod config;
 mod db;
 mod helpers;
 mod listener;
+mod metrics;
 mod monitor;
 mod resource;
 mod state;
@@ -45,6 +46,7 @@ async fn app() -> anyhow::Result<()> {
   helpers::sync::spawn_sync_refresh_loop();
   helpers::build::spawn_build_refresh_loop();
   helpers::repo::spawn_repo_refresh_loop();
+  helpers::schedule::spawn_schedule_loop();
   resource::spawn_build_state_refresh_loop();
   resource::spawn_repo_state_refresh_loop();
   resource::spawn_procedure_state_refresh_loop();
@@ -57,14 +59,20 @@ async fn app() -> anyhow::Result<()> {
   let serve_dir = ServeDir::new(frontend_path)
     .not_found_service(frontend_index.clone());
 
-  let app = Router::new()
+  let mut app = Router::new()
     .nest("/auth", api::auth::router())
     .nest("/user", api::user::router())
     .nest("/read", api::read::router())
     .nest("/write", api::write::router())
     .nest("/execute", api::execute::router())
     .nest("/listener", listener::router())
-    .nest("/ws", ws::router())
+    .nest("/ws", ws::router());
+
+  if config.enable_metrics {
+    app = app.nest("/metrics", metrics::router());
+  }
+
+  let app = app
     .nest_service("/", serve_dir)
     .fallback_service(frontend_index)
     .layer(cors()?);
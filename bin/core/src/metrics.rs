@@ -0,0 +1,589 @@
+use std::{
+  collections::{HashMap, HashSet},
+  fmt::Write,
+  sync::OnceLock,
+};
+
+use anyhow::{anyhow, Context};
+use async_timing_util::unix_timestamp_ms;
+use axum::{
+  http::{header::CONTENT_TYPE, HeaderMap, StatusCode},
+  response::IntoResponse,
+  routing::get,
+  Router,
+};
+use futures::TryStreamExt;
+use komodo_client::entities::{
+  build::Build, docker::container::ContainerStats,
+  procedure::Procedure, server::ServerState, update::UpdateStatus,
+};
+use mungos::mongodb::bson::{doc, Document};
+use serde::Serialize;
+use serror::AddStatusCode;
+use tokio::sync::Mutex;
+
+use crate::{
+  config::core_config,
+  resource,
+  state::{
+    container_stats_cache, db_client, deployment_status_cache,
+    server_status_cache, stack_status_cache,
+  },
+};
+
+const BYTES_PER_GB: f64 = 1073741824.0;
+
+pub fn router() -> Router {
+  // Start counting runs from Core startup.
+  run_counts();
+  Router::new().route("/", get(handler))
+}
+
+async fn handler(
+  headers: HeaderMap,
+) -> serror::Result<impl IntoResponse> {
+  verify_token(&headers).status_code(StatusCode::UNAUTHORIZED)?;
+  let metrics = render_metrics().await?;
+  Ok((
+    [(CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
+    metrics,
+  ))
+}
+
+fn verify_token(headers: &HeaderMap) -> anyhow::Result<()> {
+  let token = &core_config().metrics_token;
+  if token.is_empty() {
+    return Ok(());
+  }
+  let provided = headers
+    .get("authorization")
+    .context("Missing Authorization header")?
+    .to_str()
+    .context("Invalid Authorization header")?
+    .strip_prefix("Bearer ")
+    .context("Authorization header must be a Bearer token")?;
+  if provided == token {
+    Ok(())
+  } else {
+    Err(anyhow!("Invalid metrics token"))
+  }
+}
+
+/// Renders the Prometheus text exposition format
+/// from the status caches kept by the monitor loop.
+async fn render_metrics() -> anyhow::Result<String> {
+  let (servers, deployments, stacks) = tokio::join!(
+    server_status_cache().get_list(),
+    deployment_status_cache().get_list(),
+    stack_status_cache().get_list(),
+  );
+
+  let server_names = servers
+    .iter()
+    .map(|server| (server.id.as_str(), server.name.as_str()))
+    .collect::<HashMap<_, _>>();
+  let server_name =
+    |id: &str| server_names.get(id).copied().unwrap_or_default();
+
+  let mut metrics = Metrics::default();
+
+  // SERVERS
+  let up = metrics.gauge(
+    "komodo_server_up",
+    "Whether Core can reach the server's Periphery agent",
+  );
+  let cpu = metrics.gauge(
+    "komodo_server_cpu_usage_percent",
+    "Server CPU usage percentage",
+  );
+  let mem_used = metrics.gauge(
+    "komodo_server_memory_used_bytes",
+    "Server memory used in bytes",
+  );
+  let mem_total = metrics.gauge(
+    "komodo_server_memory_total_bytes",
+    "Server total memory in bytes",
+  );
+  let disk_used = metrics.gauge(
+    "komodo_server_disk_used_bytes",
+    "Server disk used in bytes, per mount point",
+  );
+  let disk_total = metrics.gauge(
+    "komodo_server_disk_total_bytes",
+    "Server disk size in bytes, per mount point",
+  );
+  let container_state = metrics.gauge(
+    "komodo_container_state",
+    "Container state, always 1 for the current state",
+  );
+  let container_cpu = metrics.gauge(
+    "komodo_container_cpu_usage_percent",
+    "Container CPU usage percentage",
+  );
+  let container_mem = metrics.gauge(
+    "komodo_container_memory_usage_percent",
+    "Container memory usage percentage of its limit",
+  );
+  let container_mem_used = metrics.gauge(
+    "komodo_container_memory_used_bytes",
+    "Container memory used in bytes",
+  );
+  for status in &servers {
+    if status.state == ServerState::Disabled {
+      continue;
+    }
+    let labels = [("server", status.name.as_str())];
+    let reachable = status.state == ServerState::Ok;
+    metrics.push(up, &labels, if reachable { 1.0 } else { 0.0 });
+    if !reachable {
+      continue;
+    }
+    if let Some(stats) = &status.stats {
+      metrics.push(cpu, &labels, stats.cpu_perc as f64);
+      metrics.push(
+        mem_used,
+        &labels,
+        stats.mem_used_gb * BYTES_PER_GB,
+      );
+      metrics.push(
+        mem_total,
+        &labels,
+        stats.mem_total_gb * BYTES_PER_GB,
+      );
+      for disk in &stats.disks {
+        let mount = disk.mount.to_string_lossy();
+        let labels =
+          [("server", status.name.as_str()), ("mount", &mount)];
+        metrics.push(disk_used, &labels, disk.used_gb * BYTES_PER_GB);
+        metrics.push(
+          disk_total,
+          &labels,
+          disk.total_gb * BYTES_PER_GB,
+        );
+      }
+    }
+    for container in status.containers.iter().flatten() {
+      let state = serialized(&container.state);
+      metrics.push(
+        container_state,
+        &[
+          ("server", status.name.as_str()),
+          ("container", &container.name),
+          ("state", &state),
+        ],
+        1.0,
+      );
+    }
+    let container_stats = container_stats_cache()
+      .get(&status.id)
+      .await
+      .unwrap_or_default();
+    for stats in container_stats.iter() {
+      let labels = [
+        ("server", status.name.as_str()),
+        ("container", stats.name.as_str()),
+      ];
+      let ParsedContainerStats {
+        cpu_perc,
+        mem_perc,
+        mem_used_bytes,
+      } = ParsedContainerStats::parse(stats);
+      if let Some(cpu_perc) = cpu_perc {
+        metrics.push(container_cpu, &labels, cpu_perc);
+      }
+      if let Some(mem_perc) = mem_perc {
+        metrics.push(container_mem, &labels, mem_perc);
+      }
+      if let Some(mem_used_bytes) = mem_used_bytes {
+        metrics.push(container_mem_used, &labels, mem_used_bytes);
+      }
+    }
+  }
+
+  // DEPLOYMENTS
+  let deployment_state = metrics.gauge(
+    "komodo_deployment_state",
+    "Deployment state, always 1 for the current state",
+  );
+  for status in &deployments {
+    let status = &status.curr;
+    let state = serialized(&status.state);
+    metrics.push(
+      deployment_state,
+      &[
+        ("deployment", status.name.as_str()),
+        ("server", server_name(&status.server_id)),
+        ("state", &state),
+      ],
+      1.0,
+    );
+  }
+
+  // STACKS
+  let stack_state = metrics.gauge(
+    "komodo_stack_state",
+    "Stack state, always 1 for the current state",
+  );
+  for status in &stacks {
+    let status = &status.curr;
+    let state = serialized(&status.state);
+    metrics.push(
+      stack_state,
+      &[
+        ("stack", status.name.as_str()),
+        ("server", server_name(&status.server_id)),
+        ("state", &state),
+      ],
+      1.0,
+    );
+  }
+
+  // BUILDS / PROCEDURES
+  let runs = count_runs().await?;
+  let build_runs = metrics.counter(
+    "komodo_build_runs_total",
+    "Completed build runs since Core started, by success",
+  );
+  let procedure_runs = metrics.counter(
+    "komodo_procedure_runs_total",
+    "Completed procedure runs since Core started, by success",
+  );
+  let mut counts = runs.counts.into_iter().collect::<Vec<_>>();
+  counts.sort();
+  for ((operation, id, success), count) in counts {
+    let (metric, label) = match operation {
+      "RunBuild" => (build_runs, "build"),
+      _ => (procedure_runs, "procedure"),
+    };
+    let name = runs.names.get(&id).unwrap_or(&id);
+    metrics.push(
+      metric,
+      &[
+        (label, name.as_str()),
+        ("success", if success { "true" } else { "false" }),
+      ],
+      count as f64,
+    );
+  }
+
+  // ALERTS
+  let open_alerts = metrics.gauge(
+    "komodo_alerts_open",
+    "Unresolved alerts, by level and alert type",
+  );
+  for ((level, variant), count) in count_open_alerts().await? {
+    metrics.push(
+      open_alerts,
+      &[("level", &level), ("type", &variant)],
+      count as f64,
+    );
+  }
+
+  Ok(metrics.render())
+}
+
+/// (operation, resource id, success) -> count
+type RunCountMap = HashMap<(&'static str, String, bool), i64>;
+
+#[derive(Clone, Default)]
+struct RunTotals {
+  counts: RunCountMap,
+  /// Resource id -> name, looked up when a run is counted.
+  names: HashMap<String, String>,
+}
+
+/// The build / procedure run counts since Core started.
+/// They are kept in process so they only increase,
+/// and each scrape only queries the runs started since
+/// the oldest run which was unfinished on the previous scrape.
+struct RunCounts {
+  /// All runs started before this are already counted.
+  start_ts: i64,
+  /// The runs started since `start_ts` which are already counted,
+  /// update id -> start_ts.
+  counted: HashMap<String, i64>,
+  totals: RunTotals,
+}
+
+fn run_counts() -> &'static Mutex<RunCounts> {
+  static RUN_COUNTS: OnceLock<Mutex<RunCounts>> = OnceLock::new();
+  RUN_COUNTS.get_or_init(|| {
+    Mutex::new(RunCounts {
+      start_ts: unix_timestamp_ms() as i64,
+      counted: Default::default(),
+      totals: Default::default(),
+    })
+  })
+}
+
+async fn count_runs() -> anyhow::Result<RunTotals> {
+  let mut run_counts = run_counts().lock().await;
+  let pipeline = [
+    doc! {
+      "$match": {
+        "operation": { "$in": ["RunBuild", "RunProcedure"] },
+        "start_ts": { "$gte": run_counts.start_ts },
+      }
+    },
+    doc! {
+      "$project": {
+        "operation": 1,
+        "id": "$target.id",
+        "success": 1,
+        "status": 1,
+        "start_ts": 1,
+      }
+    },
+  ];
+  let docs: Vec<Document> = db_client()
+    .await
+    .updates
+    .aggregate(pipeline)
+    .await
+    .context("failed to aggregate run counts")?
+    .try_collect()
+    .await
+    .context("failed to collect run counts")?;
+
+  let complete = serialized(&UpdateStatus::Complete);
+  let mut next_start_ts = unix_timestamp_ms() as i64;
+  let mut counted = HashSet::new();
+  let mut newly_counted = HashSet::new();
+  for doc in &docs {
+    let (Ok(update_id), Ok(start_ts), Ok(status)) = (
+      doc.get_object_id("_id"),
+      doc.get_i64("start_ts"),
+      doc.get_str("status"),
+    ) else {
+      continue;
+    };
+    if status != complete {
+      next_start_ts = next_start_ts.min(start_ts);
+      continue;
+    }
+    let update_id = update_id.to_hex();
+    if !run_counts.counted.contains_key(&update_id) {
+      let operation = match doc.get_str("operation") {
+        Ok("RunBuild") => "RunBuild",
+        Ok("RunProcedure") => "RunProcedure",
+        _ => continue,
+      };
+      let (Ok(id), Ok(success)) =
+        (doc.get_str("id"), doc.get_bool("success"))
+      else {
+        continue;
+      };
+      *run_counts
+        .totals
+        .counts
+        .entry((operation, id.to_string(), success))
+        .or_default() += 1;
+      newly_counted.insert((operation, id.to_string()));
+    }
+    counted.insert((update_id, start_ts));
+  }
+
+  // Only look up names when runs are counted,
+  // this also picks up renames.
+  for (operation, id) in newly_counted {
+    let name = match operation {
+      "RunBuild" => resource::get::<Build>(&id).await.map(|r| r.name),
+      _ => resource::get::<Procedure>(&id).await.map(|r| r.name),
+    };
+    // Fall back to the id if the resource was deleted.
+    run_counts
+      .totals
+      .names
+      .insert(id.clone(), name.unwrap_or(id));
+  }
+
+  run_counts.start_ts = next_start_ts;
+  run_counts.counted = counted
+    .into_iter()
+    .filter(|(_, start_ts)| *start_ts >= next_start_ts)
+    .collect();
+
+  Ok(run_counts.totals.clone())
+}
+
+/// (level, alert type) -> count
+async fn count_open_alerts(
+) -> anyhow::Result<Vec<((String, String), i64)>> {
+  let pipeline = [
+    doc! { "$match": { "resolved": false } },
+    doc! {
+      "$group": {
+        "_id": { "level": "$level", "type": "$data.type" },
+        "count": { "$sum": 1 },
+      }
+    },
+  ];
+  let docs: Vec<Document> = db_client()
+    .await
+    .alerts
+    .aggregate(pipeline)
+    .await
+    .context("failed to aggregate open alerts")?
+    .try_collect()
+    .await
+    .context("failed to collect open alerts")?;
+  let mut counts = docs
+    .iter()
+    .filter_map(|doc| {
+      let id = doc.get_document("_id").ok()?;
+      let key = (
+        id.get_str("level").ok()?.to_string(),
+        id.get_str("type").ok()?.to_string(),
+      );
+      Some((key, get_count(doc)))
+    })
+    .collect::<Vec<_>>();
+  counts.sort();
+  Ok(counts)
+}
+
+/// `$sum` gives i32 or i64 depending on the size.
+fn get_count(doc: &Document) -> i64 {
+  doc
+    .get_i64("count")
+    .or_else(|_| doc.get_i32("count").map(i64::from))
+    .unwrap_or_default()
+}
+
+/// Gets the serialized name of an enum variant, eg. `running`.
+fn serialized<T: Serialize>(value: &T) -> String {
+  match serde_json::to_value(value) {
+    Ok(serde_json::Value::String(value)) => value,
+    _ => String::new(),
+  }
+}
+
+/// Docker reports container stats as display strings,
+/// eg. `1.25%` and `12.5MiB / 1.9GiB`.
+struct ParsedContainerStats {
+  cpu_perc: Option<f64>,
+  mem_perc: Option<f64>,
+  mem_used_bytes: Option<f64>,
+}
+
+impl ParsedContainerStats {
+  fn parse(stats: &ContainerStats) -> ParsedContainerStats {
+    ParsedContainerStats {
+      cpu_perc: parse_perc(&stats.cpu_perc),
+      mem_perc: parse_perc(&stats.mem_perc),
+      mem_used_bytes: stats
+        .mem_usage
+        .split('/')
+        .next()
+        .and_then(parse_bytes),
+    }
+  }
+}
+
+fn parse_perc(perc: &str) -> Option<f64> {
+  perc.trim().trim_end_matches('%').parse().ok()
+}
+
+fn parse_bytes(bytes: &str) -> Option<f64> {
+  let bytes = bytes.trim();
+  let split = bytes
+    .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+    .unwrap_or(bytes.len());
+  let (value, unit) = bytes.split_at(split);
+  let value = value.parse::<f64>().ok()?;
+  let multiplier = match unit.trim() {
+    "" | "B" => 1.0,
+    "kB" | "KB" => 1e3,
+    "MB" => 1e6,
+    "GB" => 1e9,
+    "TB" => 1e12,
+    "KiB" => 1024.0,
+    "MiB" => 1048576.0,
+    "GiB" => BYTES_PER_GB,
+    "TiB" => BYTES_PER_GB * 1024.0,
+    _ => return None,
+  };
+  Some(value * multiplier)
+}
+
+#[derive(Clone, Copy)]
+struct MetricIndex(usize);
+
+struct Metric {
+  name: &'static str,
+  help: &'static str,
+  kind: &'static str,
+  samples: Vec<String>,
+}
+
+#[derive(Default)]
+struct Metrics(Vec<Metric>);
+
+impl Metrics {
+  fn gauge(
+    &mut self,
+    name: &'static str,
+    help: &'static str,
+  ) -> MetricIndex {
+    self.add(name, help, "gauge")
+  }
+
+  fn counter(
+    &mut self,
+    name: &'static str,
+    help: &'static str,
+  ) -> MetricIndex {
+    self.add(name, help, "counter")
+  }
+
+  fn add(
+    &mut self,
+    name: &'static str,
+    help: &'static str,
+    kind: &'static str,
+  ) -> MetricIndex {
+    self.0.push(Metric {
+      name,
+      help,
+      kind,
+      samples: Vec::new(),
+    });
+    MetricIndex(self.0.len() - 1)
+  }
+
+  fn push(
+    &mut self,
+    MetricIndex(index): MetricIndex,
+    labels: &[(&str, &str)],
+    value: f64,
+  ) {
+    let metric = &mut self.0[index];
+    let labels = labels
+      .iter()
+      .map(|(key, value)| format!("{key}=\"{}\"", escape(value)))
+      .collect::<Vec<_>>()
+      .join(",");
+    metric
+      .samples
+      .push(format!("{}{{{labels}}} {value}", metric.name));
+  }
+
+  fn render(&self) -> String {
+    let mut res = String::new();
+    for metric in &self.0 {
+      let _ = writeln!(res, "# HELP {} {}", metric.name, metric.help);
+      let _ = writeln!(res, "# TYPE {} {}", metric.name, metric.kind);
+      for sample in &metric.samples {
+        let _ = writeln!(res, "{sample}");
+      }
+    }
+    res
+  }
+}
+
+fn escape(value: &str) -> String {
+  value
+    .replace('\\', "\\\\")
+    .replace('"', "\\\"")
+    .replace('\n', "\\n")
+}
@@ -7,8 +7,10 @@ use komodo_client::entities::{
 };
 
 use crate::{
-  helpers::alert::send_alerts, monitor::deployment_status_cache,
-  resource, state::db_client,
+  helpers::alert::{send_alerts, silence_alerts},
+  monitor::deployment_status_cache,
+  resource,
+  state::db_client,
 };
 
 #[instrument(level = "debug")]
@@ -62,6 +64,7 @@ pub async fn alert_deployments(
         level: SeverityLevel::Warning,
         resolved: true,
         resolved_ts: ts.into(),
+        silenced: false,
         target,
         data,
         ts,
@@ -72,6 +75,7 @@ pub async fn alert_deployments(
   if alerts.is_empty() {
     return;
   }
+  silence_alerts(alerts.iter_mut().collect()).await;
   send_alerts(&alerts).await;
   let res = db_client().await.alerts.insert_many(alerts).await;
   if let Err(e) = res {
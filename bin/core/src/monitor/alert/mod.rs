@@ -7,7 +7,7 @@ use komodo_client::entities::{
   user::User,
 };
 
-use crate::resource;
+use crate::{helpers::alert::send_alert_digests, resource};
 
 mod deployment;
 mod server;
@@ -29,6 +29,8 @@ pub async fn check_alerts(ts: i64) {
     deployment::alert_deployments(ts, &server_names),
     stack::alert_stacks(ts, &server_names)
   );
+
+  send_alert_digests().await;
 }
 
 #[instrument(level = "debug")]
@@ -16,7 +16,7 @@ use mungos::{
 };
 
 use crate::{
-  helpers::alert::send_alerts,
+  helpers::alert::{send_alerts, silence_alerts},
   state::{db_client, server_status_cache},
 };
 
@@ -65,6 +65,7 @@ pub async fn alert_servers(
           ts,
           resolved: false,
           resolved_ts: None,
+          silenced: false,
           level: SeverityLevel::Critical,
           target: ResourceTarget::Server(server_status.id.clone()),
           data: AlertData::ServerUnreachable {
@@ -127,6 +128,7 @@ pub async fn alert_servers(
           ts,
           resolved: false,
           resolved_ts: None,
+          silenced: false,
           level: health.cpu,
           target: ResourceTarget::Server(server_status.id.clone()),
           data: AlertData::ServerCpu {
@@ -194,6 +196,7 @@ pub async fn alert_servers(
           ts,
           resolved: false,
           resolved_ts: None,
+          silenced: false,
           level: health.mem,
           target: ResourceTarget::Server(server_status.id.clone()),
           data: AlertData::ServerMem {
@@ -283,6 +286,7 @@ pub async fn alert_servers(
             ts,
             resolved: false,
             resolved_ts: None,
+            silenced: false,
             level: *health,
             target: ResourceTarget::Server(server_status.id.clone()),
             data: AlertData::ServerDisk {
@@ -353,6 +357,16 @@ pub async fn alert_servers(
     }
   }
 
+  // Only opened / updated alerts are checked against the silences.
+  // Resolved alerts keep the silenced state they were recorded with,
+  // so a resolution is only sent for alerts which were sent when opened.
+  let to_silence = alerts_to_open
+    .iter_mut()
+    .chain(alerts_to_update.iter_mut())
+    .map(|(alert, _)| alert)
+    .collect();
+  silence_alerts(to_silence).await;
+
   tokio::join!(
     open_alerts(&alerts_to_open),
     update_alerts(&alerts_to_update),
@@ -7,7 +7,7 @@ use komodo_client::entities::{
 };
 
 use crate::{
-  helpers::alert::send_alerts,
+  helpers::alert::{send_alerts, silence_alerts},
   resource,
   state::{db_client, stack_status_cache},
 };
@@ -61,6 +61,7 @@ pub async fn alert_stacks(
         level: SeverityLevel::Warning,
         resolved: true,
         resolved_ts: ts.into(),
+        silenced: false,
         target,
         data,
         ts,
@@ -71,6 +72,7 @@ pub async fn alert_stacks(
   if alerts.is_empty() {
     return;
   }
+  silence_alerts(alerts.iter_mut().collect()).await;
   send_alerts(&alerts).await;
   let res = db_client().await.alerts.insert_many(alerts).await;
   if let Err(e) = res {
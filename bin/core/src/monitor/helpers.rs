@@ -30,6 +30,8 @@ pub async fn insert_deployments_status_unknown(
         History {
           curr: CachedDeploymentStatus {
             id: deployment.id,
+            name: deployment.name,
+            server_id: deployment.config.server_id,
             state: DeploymentState::Unknown,
             container: None,
           },
@@ -70,6 +72,8 @@ pub async fn insert_stacks_status_unknown(stacks: Vec<Stack>) {
         History {
           curr: CachedStackStatus {
             id: stack.id,
+            name: stack.name,
+            server_id: stack.config.server_id,
             state: StackState::Unknown,
             services: Vec::new(),
           },
@@ -104,6 +108,7 @@ pub async fn insert_server_status(
       server.id.clone(),
       CachedServerStatus {
         id: server.id.clone(),
+        name: server.name.clone(),
         state,
         version,
         stats,
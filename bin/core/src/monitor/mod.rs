@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use async_timing_util::wait_until_timelength;
 use futures::future::join_all;
 use helpers::insert_stacks_status_unknown;
@@ -13,14 +15,20 @@ use komodo_client::entities::{
   stats::SystemStats,
 };
 use mungos::{find::find_collect, mongodb::bson::doc};
-use periphery_client::api::{self, git::GetLatestCommit};
+use periphery_client::{
+  api::{self, git::GetLatestCommit},
+  PeripheryClient,
+};
 use serror::Serror;
 
 use crate::{
   config::core_config,
   helpers::periphery_client,
   monitor::{alert::check_alerts, record::record_server_stats},
-  state::{db_client, deployment_status_cache, repo_status_cache},
+  state::{
+    container_stats_cache, db_client, deployment_status_cache,
+    repo_status_cache,
+  },
 };
 
 use self::helpers::{
@@ -43,6 +51,7 @@ pub struct History<Curr: Default, Prev> {
 #[derive(Default, Clone, Debug)]
 pub struct CachedServerStatus {
   pub id: String,
+  pub name: String,
   pub state: ServerState,
   pub version: String,
   pub stats: Option<SystemStats>,
@@ -60,6 +69,10 @@ pub struct CachedServerStatus {
 pub struct CachedDeploymentStatus {
   /// The deployment id
   pub id: String,
+  /// The deployment name
+  pub name: String,
+  /// The server the deployment is on
+  pub server_id: String,
   pub state: DeploymentState,
   pub container: Option<ContainerListItem>,
 }
@@ -74,6 +87,10 @@ pub struct CachedRepoStatus {
 pub struct CachedStackStatus {
   /// The stack id
   pub id: String,
+  /// The stack name
+  pub name: String,
+  /// The server the stack is on
+  pub server_id: String,
   /// The stack state
   pub state: StackState,
   /// The services connected to the stack
@@ -231,6 +248,11 @@ pub async fn update_cache_for_server(server: &Server) {
         None,
       )
       .await;
+      if core_config().enable_metrics
+        && server.config.stats_monitoring
+      {
+        update_container_stats_cache(server, &periphery).await;
+      }
     }
     Err(e) => {
       warn!(
@@ -289,3 +311,28 @@ fn filter_volumes(
   });
   stats
 }
+
+/// Container stats are only used by the metrics exporter,
+/// so failures are just logged.
+async fn update_container_stats_cache(
+  server: &Server,
+  periphery: &PeripheryClient,
+) {
+  match periphery
+    .request(api::container::GetContainerStatsList {})
+    .await
+  {
+    Ok(stats) => {
+      container_stats_cache()
+        .insert(server.id.clone(), Arc::new(stats))
+        .await;
+    }
+    Err(e) => {
+      warn!(
+        "could not get container stats | server: {} | {e:#}",
+        server.name
+      );
+      container_stats_cache().remove(&server.id).await;
+    }
+  }
+}
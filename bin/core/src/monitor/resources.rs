@@ -42,6 +42,8 @@ pub async fn update_deployment_cache(
         History {
           curr: CachedDeploymentStatus {
             id: deployment.id,
+            name: deployment.name,
+            server_id: deployment.config.server_id,
             state,
             container,
           },
@@ -93,6 +95,8 @@ pub async fn update_stack_cache(
       .map(|s| s.curr.state);
     let status = CachedStackStatus {
       id: stack.id.clone(),
+      name: stack.name.clone(),
+      server_id: stack.config.server_id.clone(),
       state: get_stack_state_from_containers(
         &stack.config.ignore_services,
         &services,
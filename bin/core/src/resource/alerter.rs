@@ -1,8 +1,10 @@
+use anyhow::anyhow;
 use derive_variants::ExtractVariant;
 use komodo_client::entities::{
   alerter::{
-    Alerter, AlerterConfig, AlerterConfigDiff, AlerterListItem,
-    AlerterListItemInfo, AlerterQuerySpecifics, PartialAlerterConfig,
+    Alerter, AlerterConfig, AlerterConfigDiff, AlerterEndpoint,
+    AlerterListItem, AlerterListItemInfo, AlerterQuerySpecifics,
+    PartialAlerterConfig,
   },
   resource::Resource,
   update::Update,
@@ -60,10 +62,10 @@ impl super::KomodoResource for Alerter {
   }
 
   async fn validate_create_config(
-    _config: &mut Self::PartialConfig,
+    config: &mut Self::PartialConfig,
     _user: &User,
   ) -> anyhow::Result<()> {
-    Ok(())
+    validate_config(config)
   }
 
   async fn post_create(
@@ -81,10 +83,10 @@ impl super::KomodoResource for Alerter {
 
   async fn validate_update_config(
     _id: &str,
-    _config: &mut Self::PartialConfig,
+    config: &mut Self::PartialConfig,
     _user: &User,
   ) -> anyhow::Result<()> {
-    Ok(())
+    validate_config(config)
   }
 
   async fn post_update(
@@ -114,3 +116,33 @@ impl super::KomodoResource for Alerter {
     Ok(())
   }
 }
+
+fn validate_config(
+  config: &PartialAlerterConfig,
+) -> anyhow::Result<()> {
+  if let Some(AlerterEndpoint::Email(endpoint)) = &config.endpoint {
+    validate_email_password(&endpoint.password)?;
+  }
+  Ok(())
+}
+
+/// The SMTP password is not stored on the alerter,
+/// it must reference a Variable or core secret, eg. `[[SMTP_PASSWORD]]`.
+fn validate_email_password(password: &str) -> anyhow::Result<()> {
+  if password.is_empty() {
+    return Ok(());
+  }
+  let is_reference = password
+    .strip_prefix("[[")
+    .and_then(|password| password.strip_suffix("]]"))
+    .is_some_and(|name| {
+      !name.trim().is_empty() && !name.contains(['[', ']'])
+    });
+  if is_reference {
+    Ok(())
+  } else {
+    Err(anyhow!(
+      "Email alerter password must reference a Variable or core secret, eg. [[SMTP_PASSWORD]]"
+    ))
+  }
+}
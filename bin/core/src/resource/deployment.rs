@@ -1,11 +1,11 @@
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use formatting::format_serror;
 use komodo_client::entities::{
   build::Build,
   deployment::{
     Deployment, DeploymentConfig, DeploymentConfigDiff,
     DeploymentImage, DeploymentListItem, DeploymentListItemInfo,
-    DeploymentQuerySpecifics, DeploymentState,
+    DeploymentQuerySpecifics, DeploymentState, DeploymentStrategy,
     PartialDeploymentConfig,
   },
   permission::PermissionLevel,
@@ -15,7 +15,7 @@ use komodo_client::entities::{
   user::User,
   Operation, ResourceTargetVariant,
 };
-use mungos::mongodb::Collection;
+use mungos::mongodb::{bson::doc, Collection};
 use periphery_client::api::container::RemoveContainer;
 
 use crate::{
@@ -123,7 +123,8 @@ impl super::KomodoResource for Deployment {
     config: &mut Self::PartialConfig,
     user: &User,
   ) -> anyhow::Result<()> {
-    validate_config(config, user).await
+    validate_config(config, user).await?;
+    validate_strategy(config, &DeploymentConfig::default())
   }
 
   async fn post_create(
@@ -145,11 +146,13 @@ impl super::KomodoResource for Deployment {
   }
 
   async fn validate_update_config(
-    _id: &str,
+    id: &str,
     config: &mut Self::PartialConfig,
     user: &User,
   ) -> anyhow::Result<()> {
-    validate_config(config, user).await
+    validate_config(config, user).await?;
+    let deployment = resource::get::<Deployment>(id).await?;
+    validate_strategy(config, &deployment.config)
   }
 
   async fn post_update(
@@ -246,9 +249,16 @@ impl super::KomodoResource for Deployment {
   }
 
   async fn post_delete(
-    _resource: &Resource<Self::Config, Self::Info>,
+    resource: &Resource<Self::Config, Self::Info>,
     _update: &mut Update,
   ) -> anyhow::Result<()> {
+    db_client()
+      .await
+      .deployment_history
+      .delete_many(doc! { "deployment_id": &resource.id })
+      .await
+      .context("failed to delete deployment history")?;
+    deployment_status_cache().remove(&resource.id).await;
     Ok(())
   }
 }
@@ -302,3 +312,29 @@ async fn validate_config(
   }
   Ok(())
 }
+
+/// BlueGreen runs the old and new containers at the same time,
+/// so they can't publish the same ports or use the host network.
+/// Fields missing from the partial config are taken from `current`.
+fn validate_strategy(
+  config: &PartialDeploymentConfig,
+  current: &DeploymentConfig,
+) -> anyhow::Result<()> {
+  let strategy = config.strategy.unwrap_or(current.strategy);
+  if strategy != DeploymentStrategy::BlueGreen {
+    return Ok(());
+  }
+  let ports = config.ports.as_ref().unwrap_or(&current.ports);
+  if !ports.is_empty() {
+    return Err(anyhow!(
+      "BlueGreen deployments can't publish ports. Route traffic through a reverse proxy on the same network instead."
+    ));
+  }
+  let network = config.network.as_ref().unwrap_or(&current.network);
+  if network == "host" {
+    return Err(anyhow!(
+      "BlueGreen deployments can't use the host network. Use a user-defined network instead."
+    ));
+  }
+  Ok(())
+}
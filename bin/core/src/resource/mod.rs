@@ -9,6 +9,7 @@ use futures::{future::join_all, FutureExt};
 use komodo_client::{
   api::write::CreateTag,
   entities::{
+    api_key::ApiKeyScope,
     komodo_timestamp,
     permission::PermissionLevel,
     resource::{AddFilters, Resource, ResourceQuery},
@@ -32,6 +33,7 @@ use resolver_api::Resolve;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
+  auth::{api_key_scope, RequestApiKeyScope},
   config::core_config,
   helpers::{
     create_permission, flatten_document,
@@ -60,6 +62,7 @@ pub use build::{
 };
 pub use procedure::{
   refresh_procedure_state_cache, spawn_procedure_state_refresh_loop,
+  uses_procedure_args, validate_execution,
 };
 pub use repo::{
   refresh_repo_state_cache, spawn_repo_state_refresh_loop,
@@ -208,6 +211,20 @@ pub async fn get_check_permissions<T: KomodoResource>(
   permission_level: PermissionLevel,
 ) -> anyhow::Result<Resource<T::Config, T::Info>> {
   let resource = get::<T>(id_or_name).await?;
+  if let RequestApiKeyScope(Some(scope)) = api_key_scope() {
+    let allowed = scope.clamp_permission(
+      T::resource_type(),
+      &resource.id,
+      &resource.tags,
+      PermissionLevel::Write,
+    );
+    if allowed < permission_level {
+      return Err(anyhow!(
+        "api key scope does not allow {permission_level} permissions on this {}",
+        T::resource_type()
+      ));
+    }
+  }
   if user.admin
     // Allow if its just read or below, and transparent mode enabled
     || (permission_level <= PermissionLevel::Read
@@ -237,6 +254,51 @@ pub async fn get_check_permissions<T: KomodoResource>(
 #[instrument(level = "debug")]
 pub async fn get_resource_ids_for_user<T: KomodoResource>(
   user: &User,
+) -> anyhow::Result<Option<Vec<ObjectId>>> {
+  let ids = get_resource_ids_for_user_unscoped::<T>(user).await?;
+  let RequestApiKeyScope(Some(scope)) = api_key_scope() else {
+    return Ok(ids);
+  };
+  if scope.max_permission == PermissionLevel::None {
+    return Ok(Some(Vec::new()));
+  }
+  if scope.resources.is_empty() && scope.tags.is_empty() {
+    return Ok(ids);
+  }
+  let resource_type = T::resource_type();
+  let scope_ids = scope
+    .resources
+    .iter()
+    .map(|target| target.extract_variant_id())
+    .filter(|(variant, _)| *variant == resource_type)
+    .flat_map(|(_, id)| ObjectId::from_str(id))
+    .collect::<Vec<_>>();
+  let scoped = find_collect(
+    T::coll().await,
+    doc! { "$or": [
+      { "_id": { "$in": scope_ids } },
+      { "tags": { "$in": &scope.tags } },
+    ] },
+    None,
+  )
+  .await
+  .with_context(|| {
+    format!("failed to query {resource_type} in api key scope")
+  })?
+  .into_iter()
+  .flat_map(|resource| ObjectId::from_str(&resource.id));
+  let ids = match ids {
+    Some(ids) => {
+      let ids = ids.into_iter().collect::<HashSet<_>>();
+      scoped.filter(|id| ids.contains(id)).collect()
+    }
+    None => scoped.collect(),
+  };
+  Ok(Some(ids))
+}
+
+async fn get_resource_ids_for_user_unscoped<T: KomodoResource>(
+  user: &User,
 ) -> anyhow::Result<Option<Vec<ObjectId>>> {
   // Check admin or transparent mode
   if user.admin || core_config().transparent_mode {
@@ -262,11 +324,26 @@ pub async fn get_resource_ids_for_user<T: KomodoResource>(
     }
   }
 
+  // Get tags the user or groups have permissions on
+  let tags = user
+    .tags
+    .iter()
+    .chain(groups.iter().flat_map(|group| group.tags.iter()))
+    .filter(|(_, level)| **level > PermissionLevel::None)
+    .map(|(tag, _)| tag.as_str())
+    .collect::<HashSet<_>>()
+    .into_iter()
+    .collect::<Vec<_>>();
+
   let (base, perms) = tokio::try_join!(
     // Get any resources with non-none base permission,
+    // or with any of the permitted tags
     find_collect(
       T::coll().await,
-      doc! { "base_permission": { "$ne": "None" } },
+      doc! { "$or": [
+        { "base_permission": { "$ne": "None" } },
+        { "tags": { "$in": tags } },
+      ] },
       None,
     )
     .map(|res| res.with_context(|| format!(
@@ -304,6 +381,27 @@ pub async fn get_resource_ids_for_user<T: KomodoResource>(
 pub async fn get_user_permission_on_resource<T: KomodoResource>(
   user: &User,
   resource_id: &str,
+) -> anyhow::Result<PermissionLevel> {
+  let level =
+    get_user_permission_on_resource_unscoped::<T>(user, resource_id)
+      .await?;
+  let RequestApiKeyScope(Some(scope)) = api_key_scope() else {
+    return Ok(level);
+  };
+  let resource = get::<T>(resource_id).await?;
+  Ok(scope.clamp_permission(
+    T::resource_type(),
+    &resource.id,
+    &resource.tags,
+    level,
+  ))
+}
+
+async fn get_user_permission_on_resource_unscoped<
+  T: KomodoResource,
+>(
+  user: &User,
+  resource_id: &str,
 ) -> anyhow::Result<PermissionLevel> {
   if user.admin {
     return Ok(PermissionLevel::Write);
@@ -319,9 +417,9 @@ pub async fn get_user_permission_on_resource<T: KomodoResource>(
   };
 
   // Add in the resource level global base permission
-  let resource_base = get::<T>(resource_id).await?.base_permission;
-  if resource_base > base {
-    base = resource_base;
+  let resource = get::<T>(resource_id).await?;
+  if resource.base_permission > base {
+    base = resource.base_permission;
   }
 
   // Overlay users base on resource variant
@@ -330,6 +428,11 @@ pub async fn get_user_permission_on_resource<T: KomodoResource>(
       base = level;
     }
   }
+  // Overlay users permissions on the resource tags
+  let level = tag_permission(&user.tags, &resource.tags);
+  if level > base {
+    base = level;
+  }
   if base == PermissionLevel::Write {
     // No reason to keep going if already Write at this point.
     return Ok(PermissionLevel::Write);
@@ -343,6 +446,10 @@ pub async fn get_user_permission_on_resource<T: KomodoResource>(
         base = level;
       }
     }
+    let level = tag_permission(&group.tags, &resource.tags);
+    if level > base {
+      base = level;
+    }
   }
   if base == PermissionLevel::Write {
     // No reason to keep going if already Write at this point.
@@ -373,6 +480,19 @@ pub async fn get_user_permission_on_resource<T: KomodoResource>(
   Ok(permission)
 }
 
+/// The highest permission level given on any of the resource tags.
+fn tag_permission(
+  tag_permissions: &HashMap<String, PermissionLevel>,
+  resource_tags: &[String],
+) -> PermissionLevel {
+  resource_tags
+    .iter()
+    .filter_map(|tag| tag_permissions.get(tag))
+    .max()
+    .copied()
+    .unwrap_or_default()
+}
+
 #[instrument(level = "debug")]
 pub async fn list_for_user<T: KomodoResource>(
   mut query: ResourceQuery<T::QuerySpecifics>,
@@ -456,7 +576,12 @@ pub async fn create<T: KomodoResource>(
   mut config: T::PartialConfig,
   user: &User,
 ) -> anyhow::Result<Resource<T::Config, T::Info>> {
-  if !T::user_can_create(user) {
+  if !T::user_can_create(user)
+    || api_key_scope()
+      .0
+      .as_ref()
+      .is_some_and(ApiKeyScope::restricts_resources)
+  {
     return Err(anyhow!(
       "User does not have permissions to create {}.",
       T::resource_type()
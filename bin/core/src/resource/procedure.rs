@@ -2,15 +2,16 @@ use std::time::Duration;
 
 use anyhow::{anyhow, Context};
 use komodo_client::{
-  api::execute::Execution,
+  api::execute::*,
   entities::{
     build::Build,
     deployment::Deployment,
+    komodo_timestamp,
     permission::PermissionLevel,
     procedure::{
       PartialProcedureConfig, Procedure, ProcedureConfig,
       ProcedureConfigDiff, ProcedureListItem, ProcedureListItemInfo,
-      ProcedureQuerySpecifics, ProcedureState,
+      ProcedureQuerySpecifics, ProcedureState, StageCondition,
     },
     repo::Repo,
     resource::Resource,
@@ -27,7 +28,13 @@ use mungos::{
   mongodb::{bson::doc, options::FindOneOptions, Collection},
 };
 
-use crate::state::{action_states, db_client, procedure_state_cache};
+use crate::{
+  helpers::schedule::{
+    find_next_run, get_schedule_info, remove_schedule,
+    update_schedule,
+  },
+  state::{action_states, db_client, procedure_state_cache},
+};
 
 impl super::KomodoResource for Procedure {
   type Config = ProcedureConfig;
@@ -50,6 +57,8 @@ impl super::KomodoResource for Procedure {
     procedure: Resource<Self::Config, Self::Info>,
   ) -> Self::ListItem {
     let state = get_procedure_state(&procedure.id).await;
+    let (next_scheduled_run, schedule_error) =
+      get_schedule_info(&procedure.id);
     ProcedureListItem {
       name: procedure.name,
       id: procedure.id,
@@ -58,6 +67,8 @@ impl super::KomodoResource for Procedure {
       info: ProcedureListItemInfo {
         stages: procedure.config.stages.len() as i64,
         state,
+        next_scheduled_run,
+        schedule_error,
       },
     }
   }
@@ -89,9 +100,10 @@ impl super::KomodoResource for Procedure {
   }
 
   async fn post_create(
-    _created: &Resource<Self::Config, Self::Info>,
+    created: &Resource<Self::Config, Self::Info>,
     _update: &mut Update,
   ) -> anyhow::Result<()> {
+    update_schedule(created);
     refresh_procedure_state_cache().await;
     Ok(())
   }
@@ -111,9 +123,10 @@ impl super::KomodoResource for Procedure {
   }
 
   async fn post_update(
-    _updated: &Self,
+    updated: &Self,
     _update: &mut Update,
   ) -> anyhow::Result<()> {
+    update_schedule(updated);
     refresh_procedure_state_cache().await;
     Ok(())
   }
@@ -132,9 +145,10 @@ impl super::KomodoResource for Procedure {
   }
 
   async fn post_delete(
-    _resource: &Resource<Self::Config, Self::Info>,
+    resource: &Resource<Self::Config, Self::Info>,
     _update: &mut Update,
   ) -> anyhow::Result<()> {
+    remove_schedule(&resource.id);
     Ok(())
   }
 }
@@ -145,420 +159,633 @@ async fn validate_config(
   user: &User,
   id: Option<&str>,
 ) -> anyhow::Result<()> {
+  validate_schedule(config, id).await?;
   let Some(stages) = &mut config.stages else {
     return Ok(());
   };
   for stage in stages {
+    validate_stage_condition(&mut stage.condition, user).await?;
     for exec in &mut stage.executions {
-      match &mut exec.execution {
-        Execution::None(_) => {}
-        Execution::RunProcedure(params) => {
-          let procedure = super::get_check_permissions::<Procedure>(
-            &params.procedure,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          match id {
-            Some(id) if procedure.id == id => {
-              return Err(anyhow!(
-                "Cannot have self-referential procedure"
-              ))
-            }
-            _ => {}
-          }
-          params.procedure = procedure.id;
-        }
-        Execution::RunBuild(params) => {
-          let build = super::get_check_permissions::<Build>(
-            &params.build,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.build = build.id;
-        }
-        Execution::CancelBuild(params) => {
-          let build = super::get_check_permissions::<Build>(
-            &params.build,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.build = build.id;
-        }
-        Execution::Deploy(params) => {
-          let deployment =
-            super::get_check_permissions::<Deployment>(
-              &params.deployment,
-              user,
-              PermissionLevel::Execute,
-            )
-            .await?;
-          params.deployment = deployment.id;
-        }
-        Execution::StartDeployment(params) => {
-          let deployment =
-            super::get_check_permissions::<Deployment>(
-              &params.deployment,
-              user,
-              PermissionLevel::Execute,
-            )
-            .await?;
-          params.deployment = deployment.id;
-        }
-        Execution::RestartDeployment(params) => {
-          let deployment =
-            super::get_check_permissions::<Deployment>(
-              &params.deployment,
-              user,
-              PermissionLevel::Execute,
-            )
-            .await?;
-          params.deployment = deployment.id;
-        }
-        Execution::PauseDeployment(params) => {
-          let deployment =
-            super::get_check_permissions::<Deployment>(
-              &params.deployment,
-              user,
-              PermissionLevel::Execute,
-            )
-            .await?;
-          params.deployment = deployment.id;
-        }
-        Execution::UnpauseDeployment(params) => {
-          let deployment =
-            super::get_check_permissions::<Deployment>(
-              &params.deployment,
-              user,
-              PermissionLevel::Execute,
-            )
-            .await?;
-          params.deployment = deployment.id;
-        }
-        Execution::StopDeployment(params) => {
-          let deployment =
-            super::get_check_permissions::<Deployment>(
-              &params.deployment,
-              user,
-              PermissionLevel::Execute,
-            )
-            .await?;
-          params.deployment = deployment.id;
-        }
-        Execution::DestroyDeployment(params) => {
-          let deployment =
-            super::get_check_permissions::<Deployment>(
-              &params.deployment,
-              user,
-              PermissionLevel::Execute,
-            )
-            .await?;
-          params.deployment = deployment.id;
-        }
-        Execution::CloneRepo(params) => {
-          let repo = super::get_check_permissions::<Repo>(
-            &params.repo,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.repo = repo.id;
-        }
-        Execution::PullRepo(params) => {
-          let repo = super::get_check_permissions::<Repo>(
-            &params.repo,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.repo = repo.id;
-        }
-        Execution::BuildRepo(params) => {
-          let repo = super::get_check_permissions::<Repo>(
-            &params.repo,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.repo = repo.id;
-        }
-        Execution::CancelRepoBuild(params) => {
-          let repo = super::get_check_permissions::<Repo>(
-            &params.repo,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.repo = repo.id;
-        }
-        Execution::StartContainer(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::RestartContainer(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::PauseContainer(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::UnpauseContainer(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::StopContainer(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::DestroyContainer(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::StartAllContainers(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::RestartAllContainers(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::PauseAllContainers(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::UnpauseAllContainers(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::StopAllContainers(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::PruneContainers(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::DeleteNetwork(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::PruneNetworks(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::DeleteImage(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::PruneImages(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::DeleteVolume(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::PruneVolumes(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::PruneDockerBuilders(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::PruneBuildx(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::PruneSystem(params) => {
-          let server = super::get_check_permissions::<Server>(
-            &params.server,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.server = server.id;
-        }
-        Execution::RunSync(params) => {
-          let sync = super::get_check_permissions::<ResourceSync>(
-            &params.sync,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.sync = sync.id;
-        }
-        Execution::DeployStack(params) => {
-          let stack = super::get_check_permissions::<Stack>(
-            &params.stack,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.stack = stack.id;
-        }
-        Execution::StartStack(params) => {
-          let stack = super::get_check_permissions::<Stack>(
-            &params.stack,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.stack = stack.id;
-        }
-        Execution::RestartStack(params) => {
-          let stack = super::get_check_permissions::<Stack>(
-            &params.stack,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.stack = stack.id;
-        }
-        Execution::PauseStack(params) => {
-          let stack = super::get_check_permissions::<Stack>(
-            &params.stack,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.stack = stack.id;
-        }
-        Execution::UnpauseStack(params) => {
-          let stack = super::get_check_permissions::<Stack>(
-            &params.stack,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.stack = stack.id;
-        }
-        Execution::StopStack(params) => {
-          let stack = super::get_check_permissions::<Stack>(
-            &params.stack,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.stack = stack.id;
-        }
-        Execution::DestroyStack(params) => {
-          let stack = super::get_check_permissions::<Stack>(
-            &params.stack,
-            user,
-            PermissionLevel::Execute,
-          )
-          .await?;
-          params.stack = stack.id;
-        }
-        Execution::Sleep(_) => {}
+      // Executions referencing procedure args are validated
+      // at run time, after the args are interpolated.
+      if uses_procedure_args(&exec.execution) {
+        continue;
       }
+      validate_execution(&mut exec.execution, user, id).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Resolves the execution targets to ids, and checks
+/// the user has permission to run them.
+pub async fn validate_execution(
+  execution: &mut Execution,
+  user: &User,
+  id: Option<&str>,
+) -> anyhow::Result<()> {
+  match execution {
+    Execution::None(_) => {}
+    Execution::RunProcedure(params) => {
+      let procedure = super::get_check_permissions::<Procedure>(
+        &params.procedure,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      match id {
+        Some(id) if procedure.id == id => {
+          return Err(anyhow!(
+            "Cannot have self-referential procedure"
+          ))
+        }
+        _ => {}
+      }
+      params.procedure = procedure.id;
+    }
+    Execution::RunBuild(params) => {
+      let build = super::get_check_permissions::<Build>(
+        &params.build,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.build = build.id;
+    }
+    Execution::CancelBuild(params) => {
+      let build = super::get_check_permissions::<Build>(
+        &params.build,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.build = build.id;
+    }
+    Execution::Deploy(params) => {
+      let deployment = super::get_check_permissions::<Deployment>(
+        &params.deployment,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.deployment = deployment.id;
+    }
+    Execution::RollbackDeployment(params) => {
+      let deployment = super::get_check_permissions::<Deployment>(
+        &params.deployment,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.deployment = deployment.id;
+    }
+    Execution::StartDeployment(params) => {
+      let deployment = super::get_check_permissions::<Deployment>(
+        &params.deployment,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.deployment = deployment.id;
+    }
+    Execution::RestartDeployment(params) => {
+      let deployment = super::get_check_permissions::<Deployment>(
+        &params.deployment,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.deployment = deployment.id;
+    }
+    Execution::PauseDeployment(params) => {
+      let deployment = super::get_check_permissions::<Deployment>(
+        &params.deployment,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.deployment = deployment.id;
+    }
+    Execution::UnpauseDeployment(params) => {
+      let deployment = super::get_check_permissions::<Deployment>(
+        &params.deployment,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.deployment = deployment.id;
+    }
+    Execution::StopDeployment(params) => {
+      let deployment = super::get_check_permissions::<Deployment>(
+        &params.deployment,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.deployment = deployment.id;
+    }
+    Execution::DestroyDeployment(params) => {
+      let deployment = super::get_check_permissions::<Deployment>(
+        &params.deployment,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.deployment = deployment.id;
+    }
+    Execution::CloneRepo(params) => {
+      let repo = super::get_check_permissions::<Repo>(
+        &params.repo,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.repo = repo.id;
+    }
+    Execution::PullRepo(params) => {
+      let repo = super::get_check_permissions::<Repo>(
+        &params.repo,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.repo = repo.id;
+    }
+    Execution::BuildRepo(params) => {
+      let repo = super::get_check_permissions::<Repo>(
+        &params.repo,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.repo = repo.id;
+    }
+    Execution::CancelRepoBuild(params) => {
+      let repo = super::get_check_permissions::<Repo>(
+        &params.repo,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.repo = repo.id;
+    }
+    Execution::StartContainer(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::RestartContainer(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::PauseContainer(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
     }
+    Execution::UnpauseContainer(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::StopContainer(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::DestroyContainer(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::StartAllContainers(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::RestartAllContainers(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::PauseAllContainers(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::UnpauseAllContainers(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::StopAllContainers(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::PruneContainers(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::DeleteNetwork(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::PruneNetworks(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::DeleteImage(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::PruneImages(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::DeleteVolume(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::PruneVolumes(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::PruneDockerBuilders(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::PruneBuildx(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::PruneSystem(params) => {
+      let server = super::get_check_permissions::<Server>(
+        &params.server,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.server = server.id;
+    }
+    Execution::RunSync(params) => {
+      let sync = super::get_check_permissions::<ResourceSync>(
+        &params.sync,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.sync = sync.id;
+    }
+    Execution::DeployStack(params) => {
+      let stack = super::get_check_permissions::<Stack>(
+        &params.stack,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.stack = stack.id;
+    }
+    Execution::StartStack(params) => {
+      let stack = super::get_check_permissions::<Stack>(
+        &params.stack,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.stack = stack.id;
+    }
+    Execution::RestartStack(params) => {
+      let stack = super::get_check_permissions::<Stack>(
+        &params.stack,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.stack = stack.id;
+    }
+    Execution::PauseStack(params) => {
+      let stack = super::get_check_permissions::<Stack>(
+        &params.stack,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.stack = stack.id;
+    }
+    Execution::UnpauseStack(params) => {
+      let stack = super::get_check_permissions::<Stack>(
+        &params.stack,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.stack = stack.id;
+    }
+    Execution::StopStack(params) => {
+      let stack = super::get_check_permissions::<Stack>(
+        &params.stack,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.stack = stack.id;
+    }
+    Execution::DestroyStack(params) => {
+      let stack = super::get_check_permissions::<Stack>(
+        &params.stack,
+        user,
+        PermissionLevel::Execute,
+      )
+      .await?;
+      params.stack = stack.id;
+    }
+    Execution::Sleep(_) => {}
   }
+  Ok(())
+}
+
+/// Whether the execution references procedure args,
+/// eg. `[[env]]-api`.
+pub fn uses_procedure_args(execution: &Execution) -> bool {
+  execution_params(execution)
+    .into_iter()
+    .any(|param| param.contains("[["))
+}
 
+/// The string params of the execution,
+/// which procedure args can be interpolated into.
+fn execution_params(execution: &Execution) -> Vec<&str> {
+  match execution {
+    Execution::None(_) | Execution::Sleep(_) => Vec::new(),
+    Execution::RunProcedure(params) => {
+      let mut res = vec![params.procedure.as_str()];
+      res.extend(params.args.values().map(String::as_str));
+      res
+    }
+    Execution::RunBuild(RunBuild { build })
+    | Execution::CancelBuild(CancelBuild { build }) => vec![build],
+    Execution::RollbackDeployment(params) => {
+      let mut res = vec![params.deployment.as_str()];
+      res.extend(params.record.as_deref());
+      res
+    }
+    Execution::Deploy(Deploy { deployment, .. })
+    | Execution::StartDeployment(StartDeployment { deployment })
+    | Execution::RestartDeployment(RestartDeployment {
+      deployment,
+    })
+    | Execution::PauseDeployment(PauseDeployment { deployment })
+    | Execution::UnpauseDeployment(UnpauseDeployment {
+      deployment,
+    })
+    | Execution::StopDeployment(StopDeployment {
+      deployment, ..
+    })
+    | Execution::DestroyDeployment(DestroyDeployment {
+      deployment,
+      ..
+    }) => vec![deployment],
+    Execution::CloneRepo(CloneRepo { repo })
+    | Execution::PullRepo(PullRepo { repo })
+    | Execution::BuildRepo(BuildRepo { repo })
+    | Execution::CancelRepoBuild(CancelRepoBuild { repo }) => {
+      vec![repo]
+    }
+    Execution::StartContainer(StartContainer {
+      server,
+      container,
+    })
+    | Execution::RestartContainer(RestartContainer {
+      server,
+      container,
+    })
+    | Execution::PauseContainer(PauseContainer {
+      server,
+      container,
+    })
+    | Execution::UnpauseContainer(UnpauseContainer {
+      server,
+      container,
+    })
+    | Execution::StopContainer(StopContainer {
+      server,
+      container,
+      ..
+    })
+    | Execution::DestroyContainer(DestroyContainer {
+      server,
+      container,
+      ..
+    }) => vec![server, container],
+    Execution::DeleteNetwork(DeleteNetwork { server, name })
+    | Execution::DeleteImage(DeleteImage { server, name })
+    | Execution::DeleteVolume(DeleteVolume { server, name }) => {
+      vec![server, name]
+    }
+    Execution::StartAllContainers(StartAllContainers { server })
+    | Execution::RestartAllContainers(RestartAllContainers {
+      server,
+    })
+    | Execution::PauseAllContainers(PauseAllContainers { server })
+    | Execution::UnpauseAllContainers(UnpauseAllContainers {
+      server,
+    })
+    | Execution::StopAllContainers(StopAllContainers {
+      server,
+      ..
+    })
+    | Execution::PruneContainers(PruneContainers { server })
+    | Execution::PruneNetworks(PruneNetworks { server })
+    | Execution::PruneImages(PruneImages { server })
+    | Execution::PruneVolumes(PruneVolumes { server })
+    | Execution::PruneDockerBuilders(PruneDockerBuilders {
+      server,
+    })
+    | Execution::PruneBuildx(PruneBuildx { server })
+    | Execution::PruneSystem(PruneSystem { server }) => vec![server],
+    Execution::RunSync(RunSync { sync }) => vec![sync],
+    Execution::DeployStack(DeployStack { stack, .. })
+    | Execution::DestroyStack(DestroyStack { stack, .. }) => {
+      vec![stack]
+    }
+    Execution::StartStack(StartStack { stack, service })
+    | Execution::RestartStack(RestartStack { stack, service })
+    | Execution::PauseStack(PauseStack { stack, service })
+    | Execution::UnpauseStack(UnpauseStack { stack, service })
+    | Execution::StopStack(StopStack { stack, service, .. }) => {
+      let mut res = vec![stack.as_str()];
+      res.extend(service.as_deref());
+      res
+    }
+  }
+}
+
+async fn validate_stage_condition(
+  condition: &mut StageCondition,
+  user: &User,
+) -> anyhow::Result<()> {
+  match condition {
+    StageCondition::None => {}
+    StageCondition::BuildNewVersion { build } => {
+      *build = super::get_check_permissions::<Build>(
+        build,
+        user,
+        PermissionLevel::Read,
+      )
+      .await?
+      .id;
+    }
+    StageCondition::ServerState { server, .. } => {
+      *server = super::get_check_permissions::<Server>(
+        server,
+        user,
+        PermissionLevel::Read,
+      )
+      .await?
+      .id;
+    }
+    StageCondition::DeploymentState { deployment, .. } => {
+      *deployment = super::get_check_permissions::<Deployment>(
+        deployment,
+        user,
+        PermissionLevel::Read,
+      )
+      .await?
+      .id;
+    }
+    StageCondition::StackState { stack, .. } => {
+      *stack = super::get_check_permissions::<Stack>(
+        stack,
+        user,
+        PermissionLevel::Read,
+      )
+      .await?
+      .id;
+    }
+  }
+  Ok(())
+}
+
+async fn validate_schedule(
+  config: &PartialProcedureConfig,
+  id: Option<&str>,
+) -> anyhow::Result<()> {
+  if config.schedule_format.is_none()
+    && config.schedule.is_none()
+    && config.schedule_timezone.is_none()
+  {
+    return Ok(());
+  }
+  // Fill in the fields not being updated from the existing config
+  let existing = match id {
+    Some(id) => super::get::<Procedure>(id).await?.config,
+    None => Default::default(),
+  };
+  let format =
+    config.schedule_format.unwrap_or(existing.schedule_format);
+  let schedule =
+    config.schedule.as_ref().unwrap_or(&existing.schedule);
+  let timezone = config
+    .schedule_timezone
+    .as_ref()
+    .unwrap_or(&existing.schedule_timezone);
+  if schedule.is_empty() {
+    return Ok(());
+  }
+  find_next_run(format, schedule, timezone, komodo_timestamp())
+    .context("Invalid procedure schedule")?;
   Ok(())
 }
 
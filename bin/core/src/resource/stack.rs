@@ -284,9 +284,10 @@ impl super::KomodoResource for Stack {
   }
 
   async fn post_delete(
-    _resource: &Resource<Self::Config, Self::Info>,
+    resource: &Resource<Self::Config, Self::Info>,
     _update: &mut Update,
   ) -> anyhow::Result<()> {
+    stack_status_cache().remove(&resource.id).await;
     Ok(())
   }
 }
@@ -8,6 +8,7 @@ use komodo_client::entities::{
   build::BuildState,
   config::core::{CoreConfig, GithubWebhookAppConfig},
   deployment::DeploymentState,
+  docker::container::ContainerStats,
   procedure::ProcedureState,
   repo::RepoState,
   stack::StackState,
@@ -163,6 +164,17 @@ pub fn server_status_cache() -> &'static ServerStatusCache {
   SERVER_STATUS_CACHE.get_or_init(Default::default)
 }
 
+/// Server id -> container stats.
+/// Only populated when metrics are enabled.
+pub type ContainerStatsCache =
+  Cache<String, Arc<Vec<ContainerStats>>>;
+
+pub fn container_stats_cache() -> &'static ContainerStatsCache {
+  static CONTAINER_STATS_CACHE: OnceLock<ContainerStatsCache> =
+    OnceLock::new();
+  CONTAINER_STATS_CACHE.get_or_init(Default::default)
+}
+
 pub type RepoStatusCache = Cache<String, Arc<CachedRepoStatus>>;
 
 pub fn repo_status_cache() -> &'static RepoStatusCache {
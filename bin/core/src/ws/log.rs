@@ -0,0 +1,184 @@
+use std::cmp;
+
+use axum::{
+  extract::{
+    ws::{self, WebSocket},
+    Query, WebSocketUpgrade,
+  },
+  response::IntoResponse,
+};
+use futures::{SinkExt, StreamExt};
+use komodo_client::{
+  entities::{
+    api_key::ApiKeyRequestType, permission::PermissionLevel,
+  },
+  ws::{
+    DeploymentLogQuery, LogFollowMessage, LogLine, LogSearch,
+    StackLogQuery,
+  },
+};
+use periphery_client::api::container::ConnectContainerLog;
+use tokio::sync::watch;
+use tokio_tungstenite::tungstenite;
+
+use crate::{auth::with_api_key_scope, helpers::periphery_client};
+
+use super::{
+  deployment_container, stack_service_container, ws_login,
+  ContainerTarget,
+};
+
+const MAX_LOG_TAIL: u64 = 5000;
+
+#[instrument(level = "debug")]
+pub async fn deployment_log(
+  Query(DeploymentLogQuery {
+    deployment,
+    tail,
+    timestamps,
+    since,
+    until,
+  }): Query<DeploymentLogQuery>,
+  ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+  ws.on_upgrade(move |socket| async move {
+    let Some((socket, user, scope)) =
+      ws_login(socket, ApiKeyRequestType::Read).await
+    else {
+      return;
+    };
+    let target = with_api_key_scope(
+      scope,
+      deployment_container(&deployment, &user, PermissionLevel::Read),
+    )
+    .await;
+    handle_log(socket, target, tail, timestamps, since, until).await
+  })
+}
+
+#[instrument(level = "debug")]
+pub async fn stack_log(
+  Query(StackLogQuery {
+    stack,
+    service,
+    tail,
+    timestamps,
+    since,
+    until,
+  }): Query<StackLogQuery>,
+  ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+  ws.on_upgrade(move |socket| async move {
+    let Some((socket, user, scope)) =
+      ws_login(socket, ApiKeyRequestType::Read).await
+    else {
+      return;
+    };
+    let target = with_api_key_scope(
+      scope,
+      stack_service_container(
+        &stack,
+        &service,
+        &user,
+        PermissionLevel::Read,
+      ),
+    )
+    .await;
+    handle_log(socket, target, tail, timestamps, since, until).await
+  })
+}
+
+async fn handle_log(
+  mut socket: WebSocket,
+  target: anyhow::Result<ContainerTarget>,
+  tail: u64,
+  timestamps: bool,
+  since: Option<i64>,
+  until: Option<i64>,
+) {
+  let res = async {
+    let ContainerTarget {
+      server, container, ..
+    } = target?;
+    periphery_client(&server)?
+      .connect_container_log(ConnectContainerLog {
+        container,
+        tail: cmp::min(tail, MAX_LOG_TAIL),
+        timestamps,
+        since,
+        until,
+      })
+      .await
+  }
+  .await;
+
+  let periphery = match res {
+    Ok(periphery) => periphery,
+    Err(e) => {
+      let _ = socket
+        .send(ws::Message::Text(format!(
+          "failed to follow log | {e:#}"
+        )))
+        .await;
+      let _ = socket.close().await;
+      return;
+    }
+  };
+
+  let (mut client_sender, mut client_receiver) = socket.split();
+  let (mut periphery_sender, mut periphery_receiver) =
+    periphery.split();
+  let (search_sender, search_receiver) =
+    watch::channel(LogSearch::default());
+
+  // The client can update the search filter at any time.
+  let handle_client = async {
+    while let Some(Ok(msg)) = client_receiver.next().await {
+      match msg {
+        ws::Message::Text(text) => {
+          match serde_json::from_str::<LogFollowMessage>(&text) {
+            Ok(LogFollowMessage::Search(search)) => {
+              let _ = search_sender.send(search);
+            }
+            Err(e) => {
+              warn!("invalid log follow message | {e:?}");
+            }
+          }
+        }
+        ws::Message::Close(_) => break,
+        _ => {}
+      }
+    }
+  };
+
+  let forward_log = async {
+    while let Some(Ok(msg)) = periphery_receiver.next().await {
+      let text = match msg {
+        tungstenite::Message::Text(text) => text,
+        tungstenite::Message::Close(_) => break,
+        _ => continue,
+      };
+      let line = match serde_json::from_str::<LogLine>(&text) {
+        Ok(line) => line,
+        Err(e) => {
+          warn!("invalid log line from periphery | {e:?}");
+          continue;
+        }
+      };
+      if !search_receiver.borrow().matches(&line.line) {
+        continue;
+      }
+      if client_sender.send(ws::Message::Text(text)).await.is_err() {
+        break;
+      }
+    }
+  };
+
+  tokio::select! {
+    _ = handle_client => {}
+    _ = forward_log => {}
+  }
+
+  let _ = periphery_sender.close().await;
+  let _ = client_sender.close().await;
+}
@@ -11,7 +11,12 @@ use axum::{
 use futures::{SinkExt, StreamExt};
 use komodo_client::{
   entities::{
-    permission::PermissionLevel, user::User, ResourceTarget,
+    api_key::ApiKeyRequestType,
+    deployment::Deployment,
+    permission::PermissionLevel,
+    server::{Server, ServerState},
+    user::User,
+    ResourceTarget,
   },
   ws::WsLoginMessage,
 };
@@ -22,16 +27,30 @@ use tokio::select;
 use tokio_util::sync::CancellationToken;
 
 use crate::{
-  auth::{auth_api_key_check_enabled, auth_jwt_check_enabled},
+  auth::{
+    auth_api_key_check_enabled, auth_jwt_check_enabled,
+    with_api_key_scope, RequestApiKeyScope,
+  },
   db::DbClient,
   helpers::{
-    channel::update_channel, query::get_user_permission_on_target,
+    channel::update_channel,
+    query::{get_server_with_state, get_user_permission_on_target},
+    stack::get_stack_and_server,
   },
-  state::db_client,
+  resource,
+  state::{db_client, stack_status_cache},
 };
 
+mod log;
+mod terminal;
+
 pub fn router() -> Router {
-  Router::new().route("/update", get(ws_handler))
+  Router::new()
+    .route("/update", get(ws_handler))
+    .route("/terminal/deployment", get(terminal::deployment_terminal))
+    .route("/terminal/stack", get(terminal::stack_terminal))
+    .route("/log/deployment", get(log::deployment_log))
+    .route("/log/stack", get(log::stack_log))
 }
 
 #[instrument(level = "debug")]
@@ -41,8 +60,10 @@ async fn ws_handler(ws: WebSocketUpgrade) -> impl IntoResponse {
 
   // handle http -> ws updgrade
   ws.on_upgrade(|socket| async move {
-    let Some((socket, user)) = ws_login(socket).await else {
-      return
+    let Some((socket, user, scope)) =
+      ws_login(socket, ApiKeyRequestType::Read).await
+    else {
+      return;
     };
 
     let (mut ws_sender, mut ws_reciever) = socket.split();
@@ -50,7 +71,7 @@ async fn ws_handler(ws: WebSocketUpgrade) -> impl IntoResponse {
     let cancel = CancellationToken::new();
     let cancel_clone = cancel.clone();
 
-    tokio::spawn(async move {
+    tokio::spawn(with_api_key_scope(scope, async move {
       let db_client = db_client().await;
       loop {
         // poll for updates off the receiver / await cancel.
@@ -80,7 +101,7 @@ async fn ws_handler(ws: WebSocketUpgrade) -> impl IntoResponse {
             .await;
         }
       }
-    });
+    }));
 
     // Handle messages from the client.
     // After login, only handles close message.
@@ -104,7 +125,8 @@ async fn ws_handler(ws: WebSocketUpgrade) -> impl IntoResponse {
 #[instrument(level = "debug")]
 async fn ws_login(
   mut socket: WebSocket,
-) -> Option<(WebSocket, User)> {
+  request_type: ApiKeyRequestType,
+) -> Option<(WebSocket, User, RequestApiKeyScope)> {
   let login_msg = match socket.recv().await {
     Some(Ok(Message::Text(login_msg))) => LoginMessage::Ok(login_msg),
     Some(Ok(msg)) => {
@@ -132,7 +154,7 @@ async fn ws_login(
         Ok(user) => {
           let _ =
             socket.send(Message::Text("LOGGED_IN".to_string())).await;
-          Some((socket, user))
+          Some((socket, user, RequestApiKeyScope::default()))
         }
         Err(e) => {
           let _ = socket
@@ -147,11 +169,13 @@ async fn ws_login(
     }
     // login using api keys
     Ok(WsLoginMessage::ApiKeys { key, secret }) => {
-      match auth_api_key_check_enabled(&key, &secret).await {
-        Ok(user) => {
+      match auth_api_key_check_enabled(&key, &secret, request_type)
+        .await
+      {
+        Ok((user, scope)) => {
           let _ =
             socket.send(Message::Text("LOGGED_IN".to_string())).await;
-          Some((socket, user))
+          Some((socket, user, scope))
         }
         Err(e) => {
           let _ = socket
@@ -176,6 +200,69 @@ async fn ws_login(
   }
 }
 
+/// The container backing a Deployment or Stack service,
+/// used by the terminal and log websockets.
+struct ContainerTarget {
+  target: ResourceTarget,
+  server: Server,
+  container: String,
+}
+
+async fn deployment_container(
+  deployment: &str,
+  user: &User,
+  level: PermissionLevel,
+) -> anyhow::Result<ContainerTarget> {
+  let deployment = resource::get_check_permissions::<Deployment>(
+    deployment, user, level,
+  )
+  .await?;
+  if deployment.config.server_id.is_empty() {
+    return Err(anyhow!("Deployment has no server configured"));
+  }
+  let (server, state) =
+    get_server_with_state(&deployment.config.server_id).await?;
+  if state != ServerState::Ok {
+    return Err(anyhow!(
+      "Cannot connect to container when server is unreachable or disabled"
+    ));
+  }
+  Ok(ContainerTarget {
+    target: ResourceTarget::Deployment(deployment.id),
+    server,
+    container: deployment.name,
+  })
+}
+
+async fn stack_service_container(
+  stack: &str,
+  service: &str,
+  user: &User,
+  level: PermissionLevel,
+) -> anyhow::Result<ContainerTarget> {
+  let (stack, server) =
+    get_stack_and_server(stack, user, level, true).await?;
+  let container = stack_status_cache()
+    .get(&stack.id)
+    .await
+    .context("Stack status not found")?
+    .curr
+    .services
+    .iter()
+    .find(|s| s.service == service)
+    .with_context(|| format!("Service {service} not found"))?
+    .container
+    .as_ref()
+    .with_context(|| format!("Service {service} has no container"))?
+    .name
+    .clone();
+  Ok(ContainerTarget {
+    target: ResourceTarget::Stack(stack.id),
+    server,
+    container,
+  })
+}
+
 enum LoginMessage {
   /// The text message
   Ok(String),
@@ -0,0 +1,225 @@
+use std::time::Instant;
+
+use axum::{
+  extract::{
+    ws::{self, WebSocket},
+    Query, WebSocketUpgrade,
+  },
+  response::IntoResponse,
+};
+use formatting::{bold, muted};
+use futures::{SinkExt, StreamExt};
+use komodo_client::{
+  entities::{
+    api_key::ApiKeyRequestType, permission::PermissionLevel,
+    update::Update, user::User, Operation,
+  },
+  ws::{DeploymentTerminalQuery, StackTerminalQuery},
+};
+use periphery_client::api::container::ConnectContainerExec;
+use tokio_tungstenite::tungstenite;
+
+use crate::{
+  auth::with_api_key_scope,
+  helpers::{
+    periphery_client,
+    update::{add_update, make_update, update_update},
+  },
+};
+
+use super::{
+  deployment_container, stack_service_container, ws_login,
+  ContainerTarget,
+};
+
+#[instrument(level = "debug")]
+pub async fn deployment_terminal(
+  Query(DeploymentTerminalQuery { deployment, shell }): Query<
+    DeploymentTerminalQuery,
+  >,
+  ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+  ws.on_upgrade(|socket| async move {
+    let Some((socket, user, scope)) =
+      ws_login(socket, ApiKeyRequestType::Execute).await
+    else {
+      return;
+    };
+    let target = with_api_key_scope(
+      scope,
+      deployment_container(
+        &deployment,
+        &user,
+        PermissionLevel::Execute,
+      ),
+    )
+    .await;
+    handle_terminal(
+      socket,
+      user,
+      target,
+      Operation::ExecDeployment,
+      shell,
+    )
+    .await
+  })
+}
+
+#[instrument(level = "debug")]
+pub async fn stack_terminal(
+  Query(StackTerminalQuery {
+    stack,
+    service,
+    shell,
+  }): Query<StackTerminalQuery>,
+  ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+  ws.on_upgrade(|socket| async move {
+    let Some((socket, user, scope)) =
+      ws_login(socket, ApiKeyRequestType::Execute).await
+    else {
+      return;
+    };
+    let target = with_api_key_scope(
+      scope,
+      stack_service_container(
+        &stack,
+        &service,
+        &user,
+        PermissionLevel::Execute,
+      ),
+    )
+    .await;
+    handle_terminal(
+      socket,
+      user,
+      target,
+      Operation::ExecStackService,
+      shell,
+    )
+    .await
+  })
+}
+
+async fn handle_terminal(
+  mut socket: WebSocket,
+  user: User,
+  target: anyhow::Result<ContainerTarget>,
+  operation: Operation,
+  shell: Option<String>,
+) {
+  let shell = shell
+    .filter(|shell| !shell.is_empty())
+    .unwrap_or_else(|| String::from("sh"));
+  let res = async {
+    let ContainerTarget {
+      target,
+      server,
+      container,
+    } = target?;
+    let periphery = periphery_client(&server)?
+      .connect_container_exec(ConnectContainerExec {
+        container: container.clone(),
+        shell: shell.clone(),
+      })
+      .await?;
+    let mut update = make_update(target, operation, &user);
+    update.in_progress();
+    update.push_simple_log(
+      "Open terminal",
+      format!(
+        "Opened {} terminal in container {} on server {}",
+        bold(&shell),
+        bold(&container),
+        bold(&server.name)
+      ),
+    );
+    update.id = add_update(update.clone()).await?;
+    anyhow::Ok((periphery, update))
+  }
+  .await;
+
+  let (periphery, update) = match res {
+    Ok(res) => res,
+    Err(e) => {
+      let _ = socket
+        .send(ws::Message::Text(format!(
+          "failed to open terminal | {e:#}"
+        )))
+        .await;
+      let _ = socket.close().await;
+      return;
+    }
+  };
+
+  let timer = Instant::now();
+  proxy_terminal(socket, periphery).await;
+  if let Err(e) = close_terminal_update(update, timer).await {
+    warn!("failed to update terminal Update on close | {e:#}");
+  }
+}
+
+/// Forwards messages both ways until either side closes.
+async fn proxy_terminal(
+  socket: WebSocket,
+  periphery: periphery_client::PeripheryWebsocket,
+) {
+  let (mut client_sender, mut client_receiver) = socket.split();
+  let (mut periphery_sender, mut periphery_receiver) =
+    periphery.split();
+
+  let client_to_periphery = async {
+    while let Some(Ok(msg)) = client_receiver.next().await {
+      let msg = match msg {
+        ws::Message::Text(text) => tungstenite::Message::Text(text),
+        ws::Message::Binary(bytes) => {
+          tungstenite::Message::Binary(bytes)
+        }
+        ws::Message::Close(_) => break,
+        _ => continue,
+      };
+      if periphery_sender.send(msg).await.is_err() {
+        break;
+      }
+    }
+    let _ = periphery_sender.close().await;
+  };
+
+  let periphery_to_client = async {
+    while let Some(Ok(msg)) = periphery_receiver.next().await {
+      let msg = match msg {
+        tungstenite::Message::Text(text) => ws::Message::Text(text),
+        tungstenite::Message::Binary(bytes) => {
+          ws::Message::Binary(bytes)
+        }
+        tungstenite::Message::Close(_) => break,
+        _ => continue,
+      };
+      if client_sender.send(msg).await.is_err() {
+        break;
+      }
+    }
+    let _ = client_sender.close().await;
+  };
+
+  tokio::select! {
+    _ = client_to_periphery => {}
+    _ = periphery_to_client => {}
+  }
+}
+
+async fn close_terminal_update(
+  mut update: Update,
+  timer: Instant,
+) -> anyhow::Result<()> {
+  let elapsed = timer.elapsed().as_secs();
+  update.push_simple_log(
+    "Close terminal",
+    format!(
+      "Terminal session closed {}",
+      muted(format!("after {}m {}s", elapsed / 60, elapsed % 60))
+    ),
+  );
+  update.finalize();
+  update_update(update).await
+}
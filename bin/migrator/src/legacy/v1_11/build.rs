@@ -22,6 +22,7 @@ impl From<Build> for komodo_client::entities::build::Build {
         built_message: None,
         latest_hash: None,
         latest_message: None,
+        latest_scan: None,
       },
       config: value.config.into(),
       base_permission: Default::default(),
@@ -164,6 +165,17 @@ impl From<BuildConfig>
       labels: value.labels.into_iter().map(Into::into).collect(),
       extra_args: value.extra_args,
       use_buildx: value.use_buildx,
+      platforms: Default::default(),
+      buildx_builder: String::from("komodo"),
+      cache_from: Default::default(),
+      cache_to: Default::default(),
+      cache_registry: Default::default(),
+      scanner: Default::default(),
+      scan_command: Default::default(),
+      scan_alert_severity:
+        komodo_client::entities::build::VulnerabilitySeverity::High,
+      scan_alert_threshold: Default::default(),
+      sbom: Default::default(),
       webhook_enabled: value.webhook_enabled,
       webhook_secret: Default::default(),
       image_registry: value.image_registry.into(),
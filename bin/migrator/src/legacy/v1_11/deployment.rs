@@ -153,6 +153,7 @@ impl From<DeploymentConfig>
       send_alerts: value.send_alerts,
       network: value.network,
       restart: value.restart,
+      strategy: Default::default(),
       command: value.command,
       termination_signal: value.termination_signal,
       termination_timeout: value.termination_timeout,
@@ -163,6 +164,10 @@ impl From<DeploymentConfig>
       environment: value.environment,
       labels: value.labels,
       links: Default::default(),
+      health_gate: Default::default(),
+      health_gate_target: Default::default(),
+      health_gate_timeout: 60,
+      health_gate_restore: Default::default(),
     }
   }
 }
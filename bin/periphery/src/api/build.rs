@@ -1,14 +1,27 @@
+use std::{
+  io::Write,
+  os::unix::fs::{DirBuilderExt, OpenOptionsExt},
+  path::PathBuf,
+};
+
 use anyhow::{anyhow, Context};
-use command::run_komodo_command;
+use command::{run_komodo_command, run_komodo_command_streaming};
 use formatting::format_serror;
 use komodo_client::entities::{
-  build::{Build, BuildConfig},
-  get_image_name, optional_string, to_komodo_name,
+  build::{
+    Build, BuildConfig, ImageScanner, VulnerabilityScan,
+    VulnerabilitySeverity,
+  },
+  get_image_name, komodo_timestamp, optional_string, to_komodo_name,
   update::Log,
   EnvironmentVar, Version,
 };
-use periphery_client::api::build::{self, PruneBuilders, PruneBuildx};
+use periphery_client::api::build::{
+  self, PruneBuilders, PruneBuildx, ScanImage, ScanImageResponse,
+};
 use resolver_api::Resolve;
+use serde::Deserialize;
+use uuid::Uuid;
 
 use crate::{
   config::periphery_config,
@@ -25,6 +38,8 @@ impl Resolve<build::Build> for State {
       build,
       aws_ecr,
       registry_token,
+      cache_aws_ecr,
+      cache_registry_token,
       additional_tags,
       replacers: core_replacers,
     }: build::Build,
@@ -44,6 +59,12 @@ impl Resolve<build::Build> for State {
           labels,
           extra_args,
           use_buildx,
+          platforms,
+          buildx_builder,
+          cache_from,
+          cache_to,
+          cache_registry,
+          sbom,
           image_registry,
           ..
         },
@@ -72,6 +93,23 @@ impl Resolve<build::Build> for State {
       }
     };
 
+    // Maybe login to cache registry
+    if let Err(e) = docker_login(
+      cache_registry,
+      cache_registry_token.as_deref(),
+      cache_aws_ecr.as_ref(),
+    )
+    .await
+    {
+      logs.push(Log::error(
+        "docker login",
+        format_serror(
+          &e.context("failed to login to cache registry").into(),
+        ),
+      ));
+      return Ok(logs);
+    }
+
     let name = to_komodo_name(name);
 
     // Get paths
@@ -86,28 +124,93 @@ impl Resolve<build::Build> for State {
     let image_name = get_image_name(&build, |_| aws_ecr)
       .context("failed to make image name")?;
     let build_args = parse_build_args(build_args);
-    let _secret_args =
-      parse_secret_args(secret_args, *skip_secret_interp)?;
+    // The files are deleted when this is dropped, after the build.
+    let (_secret_files, secret_args) =
+      write_secret_files(secret_args, *skip_secret_interp)?;
     let labels = parse_labels(labels);
     let extra_args = parse_extra_args(extra_args);
-    let buildx = if *use_buildx { " buildx" } else { "" };
+    let cache_args = parse_cache_args(cache_from, cache_to);
     let image_tags =
       image_tags(&image_name, image_tag, version, &additional_tags);
-    let push_command = should_push
-      .then(|| {
-        format!(" && docker image push --all-tags {image_name}")
-      })
-      .unwrap_or_default();
+    let platforms = platforms
+      .iter()
+      .map(|platform| platform.trim())
+      .filter(|platform| !platform.is_empty())
+      .collect::<Vec<_>>();
+
+    // SBOM attestations are attached to the pushed image.
+    let sbom = *sbom && should_push;
 
     // Construct command
-    let command = format!(
-      "cd {} && docker{buildx} build{build_args}{_secret_args}{extra_args}{labels}{image_tags} -f {dockerfile_path} .{push_command}",
-      build_dir.display()
-    );
+    let use_builder =
+      !platforms.is_empty() || !cache_to.is_empty() || sbom;
+    let command = if !use_builder {
+      let buildx = if *use_buildx { " buildx" } else { "" };
+      let push_command = if should_push {
+        format!(" && docker image push --all-tags {image_name}")
+      } else {
+        String::new()
+      };
+      format!(
+        "cd {} && docker{buildx} build{build_args}{secret_args}{cache_args}{extra_args}{labels}{image_tags} -f {dockerfile_path} .{push_command}",
+        build_dir.display()
+      )
+    } else {
+      // Multi-platform images can't be loaded into the local image store,
+      // so they must be pushed directly by buildx.
+      if platforms.len() > 1 && !should_push {
+        logs.push(Log::error(
+          "build",
+          String::from(
+            "Multi-platform builds must be pushed, configure an image registry",
+          ),
+        ));
+        return Ok(logs);
+      }
+      let builder = if buildx_builder.is_empty() {
+        "komodo"
+      } else {
+        buildx_builder.as_str()
+      };
+      // The builder name is used in shell commands.
+      if !builder
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+      {
+        logs.push(Log::error(
+          "buildx builder",
+          format!(
+            "Invalid buildx builder name '{builder}'. Only letters, numbers, '_' and '-' are allowed."
+          ),
+        ));
+        return Ok(logs);
+      }
+      let builder_log = ensure_buildx_builder(builder).await;
+      let success = builder_log.success;
+      logs.push(builder_log);
+      if !success {
+        return Ok(logs);
+      }
+      let platform = if platforms.is_empty() {
+        String::new()
+      } else {
+        format!(" --platform {}", platforms.join(","))
+      };
+      let sbom = if sbom { " --sbom=true" } else { "" };
+      let output = if should_push { " --push" } else { " --load" };
+      format!(
+        "cd {} && docker buildx build --builder {builder}{platform}{build_args}{secret_args}{cache_args}{extra_args}{labels}{image_tags}{sbom}{output} -f {dockerfile_path} .",
+        build_dir.display(),
+      )
+    };
 
     if *skip_secret_interp {
-      let build_log =
-        run_komodo_command("docker build", command).await;
+      let build_log = run_komodo_command_streaming(
+        "docker build",
+        command,
+        &Default::default(),
+      )
+      .await;
       logs.push(build_log);
     } else {
       // Interpolate any missing secrets
@@ -122,23 +225,187 @@ impl Resolve<build::Build> for State {
       )?;
       replacers.extend(core_replacers);
 
-      let mut build_log =
-        run_komodo_command("docker build", command).await;
-      build_log.command =
-        svi::replace_in_string(&build_log.command, &replacers);
-      build_log.stdout =
-        svi::replace_in_string(&build_log.stdout, &replacers);
-      build_log.stderr =
-        svi::replace_in_string(&build_log.stderr, &replacers);
+      let build_log = run_komodo_command_streaming(
+        "docker build",
+        command,
+        &replacers,
+      )
+      .await;
       logs.push(build_log);
     }
 
-    cleanup_secret_env_vars(secret_args);
-
     Ok(logs)
   }
 }
 
+//
+
+impl Resolve<ScanImage> for State {
+  #[instrument(name = "ScanImage", skip(self))]
+  async fn resolve(
+    &self,
+    ScanImage {
+      image_name,
+      tag,
+      scanner,
+      command,
+      sbom,
+    }: ScanImage,
+    _: (),
+  ) -> anyhow::Result<ScanImageResponse> {
+    let image = format!("{image_name}:{tag}");
+    let command = if command.is_empty() {
+      match scanner {
+        ImageScanner::None => {
+          return Err(anyhow!("No image scanner configured"))
+        }
+        ImageScanner::Trivy => {
+          "trivy image --quiet --format json [[IMAGE]]"
+        }
+        ImageScanner::Grype => "grype [[IMAGE]] --output json",
+      }
+    } else {
+      command.as_str()
+    }
+    .replace("[[IMAGE]]", &image);
+
+    let mut logs = Vec::new();
+    let mut scan_log =
+      run_komodo_command("scan image", command).await;
+    if !scan_log.success {
+      logs.push(scan_log);
+      return Ok(ScanImageResponse { logs, scan: None });
+    }
+
+    let mut scan = match parse_scan_report(scanner, &scan_log.stdout)
+    {
+      Ok(scan) => scan,
+      Err(e) => {
+        scan_log.stdout = String::new();
+        logs.push(scan_log);
+        logs.push(Log::error(
+          "scan image",
+          format_serror(
+            &e.context("failed to parse scan report").into(),
+          ),
+        ));
+        return Ok(ScanImageResponse { logs, scan: None });
+      }
+    };
+    scan.ts = komodo_timestamp();
+    scan.image = image.clone();
+    // The full json report is too large to keep on the Update.
+    scan_log.stdout = format!(
+      "critical: {} | high: {} | medium: {} | low: {} | unknown: {}",
+      scan.critical, scan.high, scan.medium, scan.low, scan.unknown
+    );
+    logs.push(scan_log);
+
+    if sbom {
+      let command = format!(
+        "docker buildx imagetools inspect {image} --format '{{{{json .Manifest.Digest}}}}'"
+      );
+      let log = run_komodo_command("sbom reference", command).await;
+      if log.success {
+        let digest = log.stdout.trim().trim_matches('"');
+        scan.sbom = Some(format!("{image_name}@{digest}"));
+      }
+      logs.push(log);
+    }
+
+    Ok(ScanImageResponse {
+      logs,
+      scan: Some(scan),
+    })
+  }
+}
+
+/// Counts the vulnerabilities by severity in the scanner's json report.
+fn parse_scan_report(
+  scanner: ImageScanner,
+  report: &str,
+) -> anyhow::Result<VulnerabilityScan> {
+  let mut scan = VulnerabilityScan {
+    scanner,
+    ..Default::default()
+  };
+  let severities = match scanner {
+    ImageScanner::None => Vec::new(),
+    ImageScanner::Trivy => {
+      serde_json::from_str::<TrivyReport>(report)?
+        .results
+        .into_iter()
+        .flat_map(|result| result.vulnerabilities)
+        .map(|vulnerability| vulnerability.severity)
+        .collect()
+    }
+    ImageScanner::Grype => {
+      serde_json::from_str::<GrypeReport>(report)?
+        .matches
+        .into_iter()
+        .map(|m| m.vulnerability.severity)
+        .collect()
+    }
+  };
+  for severity in severities {
+    scan.add(parse_severity(&severity));
+  }
+  Ok(scan)
+}
+
+fn parse_severity(severity: &str) -> VulnerabilitySeverity {
+  match severity.to_lowercase().as_str() {
+    "critical" => VulnerabilitySeverity::Critical,
+    "high" => VulnerabilitySeverity::High,
+    "medium" => VulnerabilitySeverity::Medium,
+    "low" => VulnerabilitySeverity::Low,
+    _ => VulnerabilitySeverity::Unknown,
+  }
+}
+
+#[derive(Deserialize)]
+struct TrivyReport {
+  #[serde(default, rename = "Results")]
+  results: Vec<TrivyResult>,
+}
+
+#[derive(Deserialize)]
+struct TrivyResult {
+  #[serde(default, rename = "Vulnerabilities")]
+  vulnerabilities: Vec<TrivyVulnerability>,
+}
+
+#[derive(Deserialize)]
+struct TrivyVulnerability {
+  #[serde(rename = "Severity")]
+  severity: String,
+}
+
+#[derive(Deserialize)]
+struct GrypeReport {
+  #[serde(default)]
+  matches: Vec<GrypeMatch>,
+}
+
+#[derive(Deserialize)]
+struct GrypeMatch {
+  vulnerability: GrypeVulnerability,
+}
+
+#[derive(Deserialize)]
+struct GrypeVulnerability {
+  severity: String,
+}
+
+/// Creates the buildx builder instance if it doesn't already exist.
+/// The `docker-container` driver is required for multi-platform builds.
+async fn ensure_buildx_builder(builder: &str) -> Log {
+  let command = format!(
+    "docker buildx inspect {builder} > /dev/null 2>&1 || docker buildx create --name {builder} --driver docker-container --bootstrap"
+  );
+  run_komodo_command("buildx builder", command).await
+}
+
 fn image_tags(
   image_name: &str,
   custom_tag: &str,
@@ -161,6 +428,21 @@ fn image_tags(
   )
 }
 
+fn parse_cache_args(
+  cache_from: &[String],
+  cache_to: &[String],
+) -> String {
+  let cache_from = cache_from
+    .iter()
+    .filter(|cache| !cache.is_empty())
+    .map(|cache| format!(" --cache-from {cache}"));
+  let cache_to = cache_to
+    .iter()
+    .filter(|cache| !cache.is_empty())
+    .map(|cache| format!(" --cache-to {cache}"));
+  cache_from.chain(cache_to).collect()
+}
+
 fn parse_build_args(build_args: &[EnvironmentVar]) -> String {
   build_args
     .iter()
@@ -169,45 +451,79 @@ fn parse_build_args(build_args: &[EnvironmentVar]) -> String {
     .join("")
 }
 
-fn parse_secret_args(
+/// Build secrets written to files, to be mounted with
+/// `docker build --secret id=...,src=...`.
+/// The files are deleted when this is dropped.
+struct SecretFiles {
+  dir: PathBuf,
+}
+
+impl Drop for SecretFiles {
+  fn drop(&mut self) {
+    if let Err(e) = std::fs::remove_dir_all(&self.dir) {
+      warn!(
+        "failed to remove build secrets at {} | {e:?}",
+        self.dir.display()
+      );
+    }
+  }
+}
+
+/// Writes each secret to its own file, readable only by the owner,
+/// and returns the `--secret` args to mount them in the build.
+fn write_secret_files(
   secret_args: &[EnvironmentVar],
   skip_secret_interp: bool,
-) -> anyhow::Result<String> {
+) -> anyhow::Result<(Option<SecretFiles>, String)> {
+  if secret_args.is_empty() {
+    return Ok((None, String::new()));
+  }
+  let dir = std::env::temp_dir()
+    .join(format!("komodo-build-secrets-{}", Uuid::new_v4()));
+  std::fs::DirBuilder::new()
+    .mode(0o700)
+    .create(&dir)
+    .context("failed to create build secrets directory")?;
+  // Constructed now so the directory is cleaned up on early return.
+  let secret_files = SecretFiles { dir };
   let periphery_config = periphery_config();
-  Ok(
-    secret_args
-      .iter()
-      .map(|EnvironmentVar { variable, value }| {
-        if variable.is_empty() {
-          return Err(anyhow!("secret variable cannot be empty string"))
-        } else if variable.contains('=') {
-          return Err(anyhow!("invalid variable {variable}. variable cannot contain '='"))
-        }
-        let value = if skip_secret_interp {
-          value.to_string()
-        } else {
-          svi::interpolate_variables(
-            value,
-            &periphery_config.secrets,
-            svi::Interpolator::DoubleBrackets,
-            true,
-          )
-          .context(
-            "failed to interpolate periphery secrets into build secrets",
-          )?.0
-        };
-        std::env::set_var(variable, value);
-        anyhow::Ok(format!(" --secret id={variable}"))
-      })
-      .collect::<anyhow::Result<Vec<_>>>()?
-      .join(""),
-  )
-}
-
-fn cleanup_secret_env_vars(secret_args: &[EnvironmentVar]) {
-  secret_args.iter().for_each(
-    |EnvironmentVar { variable, .. }| std::env::remove_var(variable),
-  )
+  let mut args = String::new();
+  for EnvironmentVar { variable, value } in secret_args {
+    if variable.is_empty() {
+      return Err(anyhow!("secret variable cannot be empty string"));
+    } else if variable.contains(['=', ',', '/']) {
+      return Err(anyhow!("invalid variable {variable}. variable cannot contain '=', ',' or '/'"));
+    }
+    let value = if skip_secret_interp {
+      value.to_string()
+    } else {
+      svi::interpolate_variables(
+        value,
+        &periphery_config.secrets,
+        svi::Interpolator::DoubleBrackets,
+        true,
+      )
+      .context(
+        "failed to interpolate periphery secrets into build secrets",
+      )?
+      .0
+    };
+    let path = secret_files.dir.join(variable);
+    std::fs::OpenOptions::new()
+      .write(true)
+      .create_new(true)
+      .mode(0o600)
+      .open(&path)
+      .and_then(|mut file| file.write_all(value.as_bytes()))
+      .with_context(|| {
+        format!("failed to write build secret {variable}")
+      })?;
+    args.push_str(&format!(
+      " --secret id={variable},src={}",
+      path.display()
+    ));
+  }
+  Ok((Some(secret_files), args))
 }
 
 //
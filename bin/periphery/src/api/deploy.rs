@@ -1,22 +1,34 @@
-use anyhow::Context;
-use command::run_komodo_command;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context};
+use command::{run_komodo_command, run_komodo_command_streaming};
 use formatting::format_serror;
 use komodo_client::entities::{
   build::{ImageRegistry, StandardRegistryConfig},
   deployment::{
     extract_registry_domain, Conversion, Deployment,
-    DeploymentConfig, DeploymentImage, RestartMode,
+    DeploymentConfig, DeploymentImage, DeploymentStrategy,
+    HealthGate, RestartMode,
   },
-  to_komodo_name,
+  docker::container::{ContainerStateStatusEnum, HealthStatusEnum},
+  komodo_timestamp, to_komodo_name,
   update::Log,
   EnvironmentVar, NoData,
 };
-use periphery_client::api::container::{Deploy, RemoveContainer};
+use periphery_client::api::container::{
+  blue_green_container_name, Deploy, RemoveContainer,
+  RemovePreviousContainer, ReplaceContainer,
+  RestorePreviousContainer, SwitchContainerAlias,
+  WaitContainerHealthy,
+};
 use resolver_api::Resolve;
+use tokio::net::TcpStream;
 
 use crate::{
   config::periphery_config,
-  docker::{docker_login, pull_image},
+  docker::{
+    docker_client, docker_login, pull_image, stop_container_command,
+  },
   helpers::{parse_extra_args, parse_labels},
   State,
 };
@@ -84,23 +96,62 @@ impl Resolve<Deploy> for State {
 
     let _ = pull_image(image).await;
     debug!("image pulled");
-    let _ = State
-      .resolve(
-        RemoveContainer {
-          name: deployment.name.clone(),
-          signal: stop_signal,
-          time: stop_time,
-        },
-        (),
+    let container_name = if deployment.config.strategy
+      == DeploymentStrategy::BlueGreen
+    {
+      // The old container keeps running until
+      // the new one is healthy and replaces it.
+      let next = blue_green_container_name(&deployment.name);
+      let _ = run_komodo_command(
+        "remove stale container",
+        format!("docker container rm -f {next}"),
       )
       .await;
-    debug!("container stopped and removed");
+      next
+    } else if deployment.config.health_gate != HealthGate::None
+      && deployment.config.health_gate_restore
+    {
+      // Keep the previous container around to restore
+      // if the new one fails the health gate.
+      let name = to_komodo_name(&deployment.name);
+      let previous = previous_container_name(&name);
+      let stop_command =
+        stop_container_command(&name, stop_signal, stop_time);
+      let _ = run_komodo_command(
+        "keep previous container",
+        format!("docker container rm -f {previous}; {stop_command} && docker rename {name} {previous}"),
+      )
+      .await;
+      debug!("container stopped and kept as {previous}");
+      name
+    } else {
+      let _ = State
+        .resolve(
+          RemoveContainer {
+            name: deployment.name.clone(),
+            signal: stop_signal,
+            time: stop_time,
+          },
+          (),
+        )
+        .await;
+      debug!("container stopped and removed");
+      to_komodo_name(&deployment.name)
+    };
 
-    let command = docker_run_command(&deployment, image);
+    let command =
+      docker_run_command(&deployment, &container_name, image);
     debug!("docker run command: {command}");
 
     if deployment.config.skip_secret_interp {
-      Ok(run_komodo_command("docker run", command).await)
+      Ok(
+        run_komodo_command_streaming(
+          "docker run",
+          command,
+          &Default::default(),
+        )
+        .await,
+      )
     } else {
       let command = svi::interpolate_variables(
         &command,
@@ -120,19 +171,269 @@ impl Resolve<Deploy> for State {
       };
 
       replacers.extend(core_replacers);
-      let mut log = run_komodo_command("docker run", command).await;
-      log.command = svi::replace_in_string(&log.command, &replacers);
-      log.stdout = svi::replace_in_string(&log.stdout, &replacers);
-      log.stderr = svi::replace_in_string(&log.stderr, &replacers);
+      Ok(
+        run_komodo_command_streaming(
+          "docker run",
+          command,
+          &replacers,
+        )
+        .await,
+      )
+    }
+  }
+}
 
-      Ok(log)
+const HEALTH_GATE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const HEALTH_GATE_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+impl Resolve<WaitContainerHealthy> for State {
+  #[instrument(name = "WaitContainerHealthy", skip(self))]
+  async fn resolve(
+    &self,
+    WaitContainerHealthy {
+      name,
+      gate,
+      target,
+      timeout,
+    }: WaitContainerHealthy,
+    _: (),
+  ) -> anyhow::Result<Log> {
+    let stage = "health gate";
+    let start_ts = komodo_timestamp();
+    let timer = Instant::now();
+    let timeout = Duration::from_secs(timeout.max(1) as u64);
+    loop {
+      let mut log = match probe_container(&name, gate, &target).await
+      {
+        Ok(HealthProbe::Healthy) => Log::simple(
+          stage,
+          format!(
+            "Container passed {gate} health gate after {}s",
+            timer.elapsed().as_secs()
+          ),
+        ),
+        Ok(HealthProbe::Pending(reason)) => {
+          if timer.elapsed() < timeout {
+            tokio::time::sleep(HEALTH_GATE_POLL_INTERVAL).await;
+            continue;
+          }
+          Log::error(
+            stage,
+            format!(
+              "Container did not pass {gate} health gate within {}s | {reason}",
+              timeout.as_secs()
+            ),
+          )
+        }
+        Err(e) => Log::error(
+          stage,
+          format_serror(
+            &e.context(format!(
+              "Container failed {gate} health gate"
+            ))
+            .into(),
+          ),
+        ),
+      };
+      log.start_ts = start_ts;
+      return Ok(log);
     }
   }
 }
 
+enum HealthProbe {
+  Healthy,
+  /// Not healthy yet, with the reason
+  Pending(String),
+}
+
+/// Errors mean the container failed the gate and
+/// won't become healthy with more time.
+async fn probe_container(
+  name: &str,
+  gate: HealthGate,
+  target: &str,
+) -> anyhow::Result<HealthProbe> {
+  let container = docker_client()
+    .inspect_container(name)
+    .await
+    .context("failed to inspect container")?;
+  let state = container.state.unwrap_or_default();
+  match state.status {
+    ContainerStateStatusEnum::Running => {}
+    ContainerStateStatusEnum::Created
+    | ContainerStateStatusEnum::Restarting => {
+      return Ok(HealthProbe::Pending(format!(
+        "container is {:?} (restarts: {})",
+        state.status,
+        container.restart_count.unwrap_or_default()
+      )))
+    }
+    status => {
+      return Err(anyhow!(
+        "container is {status:?} (exit code: {})",
+        state.exit_code.unwrap_or_default()
+      ))
+    }
+  }
+  match gate {
+    HealthGate::None => Ok(HealthProbe::Healthy),
+    HealthGate::Docker => {
+      match state.health.map(|health| health.status) {
+        Some(HealthStatusEnum::Healthy) => Ok(HealthProbe::Healthy),
+        Some(HealthStatusEnum::Starting) => Ok(HealthProbe::Pending(
+          String::from("health check is starting"),
+        )),
+        Some(HealthStatusEnum::Unhealthy) => {
+          Err(anyhow!("health check reports unhealthy"))
+        }
+        _ => Err(anyhow!("container has no HEALTHCHECK configured")),
+      }
+    }
+    HealthGate::Http => {
+      let res = reqwest::Client::new()
+        .get(target)
+        .timeout(HEALTH_GATE_PROBE_TIMEOUT)
+        .send()
+        .await;
+      match res {
+        Ok(res) if res.status().is_success() => {
+          Ok(HealthProbe::Healthy)
+        }
+        Ok(res) => Ok(HealthProbe::Pending(format!(
+          "GET {target} returned {}",
+          res.status()
+        ))),
+        Err(e) => Ok(HealthProbe::Pending(format!(
+          "GET {target} failed | {e}"
+        ))),
+      }
+    }
+    HealthGate::Tcp => {
+      let res = tokio::time::timeout(
+        HEALTH_GATE_PROBE_TIMEOUT,
+        TcpStream::connect(target),
+      )
+      .await;
+      match res {
+        Ok(Ok(_)) => Ok(HealthProbe::Healthy),
+        Ok(Err(e)) => Ok(HealthProbe::Pending(format!(
+          "connect to {target} failed | {e}"
+        ))),
+        Err(_) => Ok(HealthProbe::Pending(format!(
+          "connect to {target} timed out"
+        ))),
+      }
+    }
+  }
+}
+
+//
+
+impl Resolve<RestorePreviousContainer> for State {
+  #[instrument(name = "RestorePreviousContainer", skip(self))]
+  async fn resolve(
+    &self,
+    RestorePreviousContainer { name }: RestorePreviousContainer,
+    _: (),
+  ) -> anyhow::Result<Log> {
+    let stage = "restore previous container";
+    let name = to_komodo_name(&name);
+    let previous = previous_container_name(&name);
+    if docker_client().inspect_container(&previous).await.is_err() {
+      return Ok(Log::simple(
+        stage,
+        String::from("No previous container to restore"),
+      ));
+    }
+    let command = format!(
+      "docker container rm -f {name}; docker rename {previous} {name} && docker start {name}"
+    );
+    Ok(run_komodo_command(stage, command).await)
+  }
+}
+
+//
+
+impl Resolve<RemovePreviousContainer> for State {
+  #[instrument(name = "RemovePreviousContainer", skip(self))]
+  async fn resolve(
+    &self,
+    RemovePreviousContainer { name }: RemovePreviousContainer,
+    _: (),
+  ) -> anyhow::Result<Log> {
+    let stage = "remove previous container";
+    let previous = previous_container_name(&to_komodo_name(&name));
+    if docker_client().inspect_container(&previous).await.is_err() {
+      return Ok(Log::simple(
+        stage,
+        String::from("No previous container to remove"),
+      ));
+    }
+    let command = format!("docker container rm -f {previous}");
+    Ok(run_komodo_command(stage, command).await)
+  }
+}
+
+impl Resolve<SwitchContainerAlias> for State {
+  #[instrument(name = "SwitchContainerAlias", skip(self))]
+  async fn resolve(
+    &self,
+    SwitchContainerAlias { name, network }: SwitchContainerAlias,
+    _: (),
+  ) -> anyhow::Result<Log> {
+    let stage = "switch network alias";
+    if matches!(network.as_str(), "" | "bridge" | "host" | "none")
+      || network.starts_with("container:")
+    {
+      return Ok(Log::simple(
+        stage,
+        format!(
+          "Network {network} doesn't support aliases, skipping"
+        ),
+      ));
+    }
+    let next = blue_green_container_name(&name);
+    let name = to_komodo_name(&name);
+    let command = format!(
+      "docker network disconnect {network} {next} && docker network connect --alias {name} {network} {next}"
+    );
+    Ok(run_komodo_command(stage, command).await)
+  }
+}
+
+//
+
+impl Resolve<ReplaceContainer> for State {
+  #[instrument(name = "ReplaceContainer", skip(self))]
+  async fn resolve(
+    &self,
+    ReplaceContainer { name, signal, time }: ReplaceContainer,
+    _: (),
+  ) -> anyhow::Result<Log> {
+    let next = blue_green_container_name(&name);
+    let name = to_komodo_name(&name);
+    let stop_command = stop_container_command(&name, signal, time);
+    // The old container may not exist on the first deploy.
+    // If it does, it must be removed before the rename,
+    // otherwise both containers would be left running.
+    let command = format!(
+      "if docker container inspect {name} > /dev/null 2>&1; then {stop_command} && docker container rm {name}; fi && docker rename {next} {name}"
+    );
+    Ok(run_komodo_command("replace container", command).await)
+  }
+}
+
+//
+
+fn previous_container_name(name: &str) -> String {
+  format!("{name}-previous")
+}
+
+//
+
 fn docker_run_command(
   Deployment {
-    name,
     config:
       DeploymentConfig {
         volumes,
@@ -147,9 +448,9 @@ fn docker_run_command(
       },
     ..
   }: &Deployment,
+  name: &str,
   image: &str,
 ) -> String {
-  let name = to_komodo_name(name);
   let ports = parse_conversions(ports, "-p");
   let volumes = volumes.to_owned();
   let volumes = parse_conversions(&volumes, "-v");
@@ -66,6 +66,7 @@ pub enum PeripheryRequest {
 
   // Build
   Build(Build),
+  ScanImage(ScanImage),
   PruneBuilders(PruneBuilders),
   PruneBuildx(PruneBuildx),
 
@@ -87,6 +88,11 @@ pub enum PeripheryRequest {
 
   // Container (Write)
   Deploy(Deploy),
+  WaitContainerHealthy(WaitContainerHealthy),
+  SwitchContainerAlias(SwitchContainerAlias),
+  ReplaceContainer(ReplaceContainer),
+  RestorePreviousContainer(RestorePreviousContainer),
+  RemovePreviousContainer(RemovePreviousContainer),
   StartContainer(StartContainer),
   RestartContainer(RestartContainer),
   PauseContainer(PauseContainer),
@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
 use anyhow::{anyhow, Context};
-use command::run_komodo_command;
+use command::{run_komodo_command, run_komodo_command_streaming};
 use formatting::format_serror;
 use git::write_environment_file;
 use komodo_client::entities::{
@@ -151,7 +151,12 @@ pub async fn compose_up(
       "cd {run_dir} && {docker_compose} -p {project_name} -f {file_args} build{build_extra_args}{service_arg}",
     );
     if stack.config.skip_secret_interp {
-      let log = run_komodo_command("compose build", command).await;
+      let log = run_komodo_command_streaming(
+        "compose build",
+        command,
+        &Default::default(),
+      )
+      .await;
       res.logs.push(log);
     } else {
       let (command, mut replacers) = svi::interpolate_variables(
@@ -162,12 +167,12 @@ pub async fn compose_up(
       ).context("failed to interpolate periphery secrets into stack build command")?;
       replacers.extend(core_replacers.clone());
 
-      let mut log =
-        run_komodo_command("compose build", command).await;
-
-      log.command = svi::replace_in_string(&log.command, &replacers);
-      log.stdout = svi::replace_in_string(&log.stdout, &replacers);
-      log.stderr = svi::replace_in_string(&log.stderr, &replacers);
+      let log = run_komodo_command_streaming(
+        "compose build",
+        command,
+        &replacers,
+      )
+      .await;
 
       res.logs.push(log);
     }
@@ -182,11 +187,12 @@ pub async fn compose_up(
   if stack.config.auto_pull {
     // Pull images before destroying to minimize downtime.
     // If this fails, do not continue.
-    let log = run_komodo_command(
+    let log = run_komodo_command_streaming(
       "compose pull",
       format!(
         "cd {run_dir} && {docker_compose} -p {project_name} -f {file_args} pull{service_arg}",
       ),
+      &Default::default(),
     )
     .await;
 
@@ -214,7 +220,12 @@ pub async fn compose_up(
     "cd {run_dir} && {docker_compose} -p {project_name} -f {file_args}{env_file} up -d{extra_args}{service_arg}",
   );
   if stack.config.skip_secret_interp {
-    let log = run_komodo_command("compose up", command).await;
+    let log = run_komodo_command_streaming(
+      "compose up",
+      command,
+      &Default::default(),
+    )
+    .await;
     res.deployed = log.success;
     res.logs.push(log);
   } else {
@@ -226,11 +237,9 @@ pub async fn compose_up(
     ).context("failed to interpolate periphery secrets into stack run command")?;
     replacers.extend(core_replacers);
 
-    let mut log = run_komodo_command("compose up", command).await;
-
-    log.command = svi::replace_in_string(&log.command, &replacers);
-    log.stdout = svi::replace_in_string(&log.stdout, &replacers);
-    log.stderr = svi::replace_in_string(&log.stderr, &replacers);
+    let log =
+      run_komodo_command_streaming("compose up", command, &replacers)
+        .await;
 
     res.logs.push(log);
   }
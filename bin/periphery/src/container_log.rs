@@ -0,0 +1,93 @@
+use axum::{
+  extract::{
+    ws::{Message, WebSocket},
+    Query, WebSocketUpgrade,
+  },
+  response::Response,
+};
+use futures::{SinkExt, StreamExt};
+use komodo_client::ws::LogLine;
+use periphery_client::api::container::ConnectContainerLog;
+
+use crate::docker::docker_client;
+
+/// Follows the container log, and sends each line
+/// over the websocket as a json `LogLine`.
+#[instrument(name = "FollowContainerLog", skip(ws))]
+pub async fn handler(
+  Query(query): Query<ConnectContainerLog>,
+  ws: WebSocketUpgrade,
+) -> Response {
+  ws.on_upgrade(move |socket| async move {
+    let container = query.container.clone();
+    follow_log(socket, query).await;
+    debug!("stopped following log for container {container}");
+  })
+}
+
+async fn follow_log(socket: WebSocket, query: ConnectContainerLog) {
+  let ConnectContainerLog {
+    container,
+    tail,
+    timestamps,
+    since,
+    until,
+  } = query;
+  let (mut ws_sender, mut ws_receiver) = socket.split();
+  let mut log = Box::pin(docker_client().follow_container_log(
+    &container, tail, timestamps, since, until,
+  ));
+
+  let forward_log = async {
+    while let Some(output) = log.next().await {
+      let (stderr, bytes) = match output {
+        Ok(bollard::container::LogOutput::StdErr { message }) => {
+          (true, message)
+        }
+        Ok(output) => (false, output.into_bytes()),
+        Err(e) => {
+          let line = LogLine {
+            stderr: true,
+            line: format!("failed to follow log | {e}"),
+          };
+          let _ = send_line(&mut ws_sender, &line).await;
+          break;
+        }
+      };
+      // Docker frames may hold several lines
+      for line in String::from_utf8_lossy(&bytes).lines() {
+        let line = LogLine {
+          stderr,
+          line: line.to_string(),
+        };
+        if send_line(&mut ws_sender, &line).await.is_err() {
+          return;
+        }
+      }
+    }
+    let _ = ws_sender.close().await;
+  };
+
+  // Only listens for the client closing the connection.
+  let client_closed = async {
+    while let Some(Ok(msg)) = ws_receiver.next().await {
+      if let Message::Close(_) = msg {
+        break;
+      }
+    }
+  };
+
+  tokio::select! {
+    _ = forward_log => {}
+    _ = client_closed => {}
+  }
+}
+
+async fn send_line(
+  ws_sender: &mut futures::stream::SplitSink<WebSocket, Message>,
+  line: &LogLine,
+) -> anyhow::Result<()> {
+  let json = serde_json::to_string(line)?;
+  ws_sender.send(Message::Text(json)).await?;
+  Ok(())
+}
@@ -1,12 +1,19 @@
-use std::sync::OnceLock;
+use std::{pin::Pin, sync::OnceLock};
 
 use anyhow::{anyhow, Context};
 use bollard::{
-  container::{InspectContainerOptions, ListContainersOptions},
+  container::{
+    InspectContainerOptions, ListContainersOptions, LogsOptions,
+  },
+  exec::{
+    CreateExecOptions, ResizeExecOptions, StartExecOptions,
+    StartExecResults,
+  },
   network::InspectNetworkOptions,
   Docker,
 };
-use command::run_komodo_command;
+use command::run_komodo_command_streaming;
+use futures::Stream;
 use komodo_client::entities::{
   build::{ImageRegistry, StandardRegistryConfig},
   config::core::AwsEcrConfig,
@@ -19,6 +26,7 @@ use komodo_client::entities::{
   TerminationSignal,
 };
 use run_command::async_run_command;
+use tokio::io::AsyncWrite;
 
 pub fn docker_client() -> &'static DockerClient {
   static DOCKER_CLIENT: OnceLock<DockerClient> = OnceLock::new();
@@ -38,7 +46,120 @@ impl Default for DockerClient {
   }
 }
 
+/// An interactive `docker exec` session with a TTY attached.
+pub struct ExecSession {
+  pub id: String,
+  pub output: Pin<
+    Box<
+      dyn Stream<
+          Item = Result<
+            bollard::container::LogOutput,
+            bollard::errors::Error,
+          >,
+        > + Send,
+    >,
+  >,
+  pub input: Pin<Box<dyn AsyncWrite + Send>>,
+}
+
 impl DockerClient {
+  pub async fn start_exec_session(
+    &self,
+    container: &str,
+    shell: &str,
+  ) -> anyhow::Result<ExecSession> {
+    let exec = self
+      .docker
+      .create_exec(
+        container,
+        CreateExecOptions {
+          attach_stdin: Some(true),
+          attach_stdout: Some(true),
+          attach_stderr: Some(true),
+          tty: Some(true),
+          cmd: Some(vec![shell]),
+          ..Default::default()
+        },
+      )
+      .await
+      .with_context(|| {
+        format!("failed to create exec in container {container}")
+      })?;
+    let res = self
+      .docker
+      .start_exec(
+        &exec.id,
+        Some(StartExecOptions {
+          detach: false,
+          tty: true,
+          output_capacity: None,
+        }),
+      )
+      .await
+      .context("failed to start exec")?;
+    match res {
+      StartExecResults::Attached { output, input } => {
+        Ok(ExecSession {
+          id: exec.id,
+          output,
+          input,
+        })
+      }
+      StartExecResults::Detached => {
+        Err(anyhow!("exec started detached, expected attached"))
+      }
+    }
+  }
+
+  pub async fn resize_exec(
+    &self,
+    id: &str,
+    rows: u16,
+    cols: u16,
+  ) -> anyhow::Result<()> {
+    self
+      .docker
+      .resize_exec(
+        id,
+        ResizeExecOptions {
+          height: rows,
+          width: cols,
+        },
+      )
+      .await
+      .context("failed to resize exec")
+  }
+
+  /// Follows the container log, like `docker logs -f`.
+  /// `since` and `until` are unix timestamps in ms.
+  pub fn follow_container_log(
+    &self,
+    container: &str,
+    tail: u64,
+    timestamps: bool,
+    since: Option<i64>,
+    until: Option<i64>,
+  ) -> impl Stream<
+    Item = Result<
+      bollard::container::LogOutput,
+      bollard::errors::Error,
+    >,
+  > {
+    self.docker.logs(
+      container,
+      Some(LogsOptions {
+        follow: true,
+        stdout: true,
+        stderr: true,
+        // Docker takes these in seconds
+        since: since.map(|since| since / 1000).unwrap_or_default(),
+        until: until.map(|until| until / 1000).unwrap_or_default(),
+        timestamps,
+        tail: tail.to_string(),
+      }),
+    )
+  }
+
   pub async fn list_containers(
     &self,
   ) -> anyhow::Result<Vec<ContainerListItem>> {
@@ -971,7 +1092,12 @@ pub async fn docker_login(
 #[instrument]
 pub async fn pull_image(image: &str) -> Log {
   let command = format!("docker pull {image}");
-  run_komodo_command("docker pull", command).await
+  run_komodo_command_streaming(
+    "docker pull",
+    command,
+    &Default::default(),
+  )
+  .await
 }
 
 pub fn stop_container_command(
@@ -8,10 +8,12 @@ use anyhow::Context;
 mod api;
 mod compose;
 mod config;
+mod container_log;
 mod docker;
 mod helpers;
 mod router;
 mod stats;
+mod terminal;
 
 struct State;
 
@@ -1,4 +1,4 @@
-use std::{net::SocketAddr, time::Instant};
+use std::{convert::Infallible, net::SocketAddr, time::Instant};
 
 use anyhow::{anyhow, Context};
 use axum::{
@@ -7,19 +7,29 @@ use axum::{
   http::{Request, StatusCode},
   middleware::{self, Next},
   response::Response,
-  routing::post,
+  routing::{get, post},
   Router,
 };
 use axum_extra::{headers::ContentType, TypedHeader};
+use command::with_output_stream;
+use periphery_client::api::StreamMessage;
 use resolver_api::Resolver;
-use serror::{AddStatusCode, AddStatusCodeError, Json};
+use serror::{
+  serialize_error, AddStatusCode, AddStatusCodeError, Json,
+};
+use tokio::sync::mpsc::unbounded_channel;
 use uuid::Uuid;
 
-use crate::{config::periphery_config, State};
+use crate::{
+  config::periphery_config, container_log, terminal, State,
+};
 
 pub fn router() -> Router {
   Router::new()
     .route("/", post(handler))
+    .route("/stream", post(stream_handler))
+    .route("/terminal", get(terminal::handler))
+    .route("/log", get(container_log::handler))
     .layer(middleware::from_fn(guard_request_by_ip))
     .layer(middleware::from_fn(guard_request_by_passkey))
 }
@@ -40,6 +50,61 @@ async fn handler(
   Ok((TypedHeader(ContentType::json()), res??))
 }
 
+/// Responds with newline delimited json [StreamMessage]s.
+/// Streams the output of commands run with
+/// `run_komodo_command_streaming`, followed by the response.
+async fn stream_handler(
+  Json(request): Json<crate::api::PeripheryRequest>,
+) -> Body {
+  let req_id = Uuid::new_v4();
+  let (output_tx, mut output_rx) = unbounded_channel();
+  let (tx, rx) = unbounded_channel();
+
+  tokio::spawn(async move {
+    let send = |message: StreamMessage| {
+      let mut line = serde_json::to_string(&message)
+        .expect("failed to serialize stream message");
+      line.push('\n');
+      let _ = tx.send(line);
+    };
+    let mut handle = tokio::spawn(with_output_stream(
+      output_tx,
+      task(req_id, request),
+    ));
+    let res = loop {
+      tokio::select! {
+        Some(output) = output_rx.recv() => {
+          send(StreamMessage::Output(output))
+        }
+        res = &mut handle => break res,
+      }
+    };
+    // Flush any output not yet received
+    while let Ok(output) = output_rx.try_recv() {
+      send(StreamMessage::Output(output))
+    }
+    let message = match res {
+      Ok(Ok(res)) => StreamMessage::Response(res),
+      Ok(Err(e)) => StreamMessage::Error(serialize_error(&e)),
+      Err(e) => {
+        warn!("request {req_id} spawn error: {e:#}");
+        StreamMessage::Error(serialize_error(
+          &anyhow!("{e:#}").context("task handler spawn error"),
+        ))
+      }
+    };
+    send(message);
+  });
+
+  Body::from_stream(futures::stream::unfold(
+    rx,
+    |mut rx| async move {
+      let line = rx.recv().await?;
+      Some((Ok::<_, Infallible>(line), rx))
+    },
+  ))
+}
+
 #[instrument(name = "PeripheryHandler")]
 async fn task(
   req_id: Uuid,
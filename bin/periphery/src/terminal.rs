@@ -0,0 +1,88 @@
+use axum::{
+  extract::{
+    ws::{Message, WebSocket},
+    Query, WebSocketUpgrade,
+  },
+  response::Response,
+};
+use futures::{SinkExt, StreamExt};
+use komodo_client::ws::TerminalMessage;
+use periphery_client::api::container::ConnectContainerExec;
+use tokio::io::AsyncWriteExt;
+
+use crate::docker::{docker_client, ExecSession};
+
+/// Runs the shell in the container with a TTY attached,
+/// and proxies it over the websocket.
+#[instrument(name = "ContainerTerminal", skip(ws))]
+pub async fn handler(
+  Query(ConnectContainerExec { container, shell }): Query<
+    ConnectContainerExec,
+  >,
+  ws: WebSocketUpgrade,
+) -> serror::Result<Response> {
+  let session = docker_client()
+    .start_exec_session(&container, &shell)
+    .await?;
+  info!("started terminal session in container {container}");
+  Ok(ws.on_upgrade(move |socket| async move {
+    handle_session(socket, session).await;
+    info!("terminal session in container {container} closed");
+  }))
+}
+
+async fn handle_session(socket: WebSocket, session: ExecSession) {
+  let ExecSession {
+    id,
+    mut output,
+    mut input,
+  } = session;
+  let (mut ws_sender, mut ws_receiver) = socket.split();
+
+  let forward_output = async {
+    while let Some(Ok(chunk)) = output.next().await {
+      if ws_sender
+        .send(Message::Binary(chunk.into_bytes().to_vec()))
+        .await
+        .is_err()
+      {
+        break;
+      }
+    }
+  };
+
+  let forward_input = async {
+    while let Some(Ok(msg)) = ws_receiver.next().await {
+      let bytes = match msg {
+        Message::Binary(bytes) => bytes,
+        Message::Text(text) => {
+          match serde_json::from_str::<TerminalMessage>(&text) {
+            Ok(TerminalMessage::Resize { rows, cols }) => {
+              if let Err(e) =
+                docker_client().resize_exec(&id, rows, cols).await
+              {
+                warn!("{e:#}");
+              }
+              continue;
+            }
+            // Plain text is also accepted as input.
+            Err(_) => text.into_bytes(),
+          }
+        }
+        Message::Close(_) => break,
+        _ => continue,
+      };
+      if input.write_all(&bytes).await.is_err() {
+        break;
+      }
+    }
+  };
+
+  // Ends when either the shell exits or the client disconnects.
+  tokio::select! {
+    _ = forward_output => {}
+    _ = forward_input => {}
+  }
+
+  let _ = ws_sender.close().await;
+}
@@ -38,8 +38,15 @@ pub struct GetLoginOptionsResponse {
   pub github: bool,
   /// Whether google login is enabled.
   pub google: bool,
+  /// Whether OpenID Connect login is enabled.
+  pub oidc: bool,
+  /// Whether LDAP login is enabled.
+  pub ldap: bool,
   /// Whether user registration (Sign Up) has been disabled
   pub registration_disabled: bool,
+  /// Whether admins which log in with local auth
+  /// must enable two-factor authentication (TOTP).
+  pub require_admin_totp: bool,
 }
 
 //
@@ -88,7 +95,63 @@ pub struct LoginLocalUser {
 
 /// The response for [LoginLocalUser]
 #[typeshare]
-pub type LoginLocalUserResponse = JwtResponse;
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LoginLocalUserResponse {
+  /// A token the user can use to authenticate their requests.
+  /// Empty if the user has two-factor authentication enabled.
+  #[serde(default)]
+  pub jwt: String,
+  /// Given if the user has two-factor authentication enabled.
+  /// Pass it to [LoginTotp] along with a code to finish the login.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub totp_token: Option<String>,
+}
+
+//
+
+/// Finish logging in a local user with two-factor authentication enabled.
+/// Response: [LoginTotpResponse].
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Request, EmptyTraits,
+)]
+#[empty_traits(KomodoAuthRequest)]
+#[response(LoginTotpResponse)]
+pub struct LoginTotp {
+  /// The `totp_token` from [LoginLocalUserResponse].
+  pub token: String,
+  /// The current TOTP code, or an unused recovery code.
+  pub code: String,
+}
+
+/// The response for [LoginTotp]
+#[typeshare]
+pub type LoginTotpResponse = JwtResponse;
+
+//
+
+/// Login as an LDAP / Active Directory user.
+/// The credentials are verified by binding to the directory.
+/// The Komodo user is created on first login.
+/// Response: [LoginLdapUserResponse].
+///
+/// Note. This method is only available if the core api has `ldap` enabled.
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Request, EmptyTraits,
+)]
+#[empty_traits(KomodoAuthRequest)]
+#[response(LoginLdapUserResponse)]
+pub struct LoginLdapUser {
+  /// The user's directory username
+  pub username: String,
+  /// The user's directory password
+  pub password: String,
+}
+
+/// The response for [LoginLdapUser]
+#[typeshare]
+pub type LoginLdapUserResponse = JwtResponse;
 
 //
 
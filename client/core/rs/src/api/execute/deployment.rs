@@ -41,6 +41,39 @@ pub struct Deploy {
 
 //
 
+/// Rolls the deployment back to a previous known-good deploy.
+/// Response: [Update].
+///
+/// 1. Finds the target record in the deployment history.
+/// By default, this is the most recent successful deploy with a
+/// different image / config than the latest one.
+/// 2. Restores the deployment config from the record,
+/// with any attached Build pinned to the recorded version.
+/// 3. Runs [Deploy] with the restored config.
+///
+/// Requires Write permissions on the deployment to restore the config.
+#[typeshare]
+#[derive(
+  Serialize,
+  Deserialize,
+  Debug,
+  Clone,
+  PartialEq,
+  Request,
+  EmptyTraits,
+  Parser,
+)]
+#[empty_traits(KomodoExecuteRequest)]
+#[response(Update)]
+pub struct RollbackDeployment {
+  /// Name or id
+  pub deployment: String,
+  /// Optionally roll back to a specific deployment history record id.
+  pub record: Option<String>,
+}
+
+//
+
 /// Starts the container for the target deployment. Response: [Update]
 ///
 /// 1. Runs `docker start ${container_name}`.
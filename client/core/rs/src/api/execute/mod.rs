@@ -61,6 +61,7 @@ pub enum Execution {
 
   // DEPLOYMENT
   Deploy(Deploy),
+  RollbackDeployment(RollbackDeployment),
   StartDeployment(StartDeployment),
   RestartDeployment(RestartDeployment),
   PauseDeployment(PauseDeployment),
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use clap::Parser;
 use derive_empty_traits::EmptyTraits;
 use resolver_api::derive::Request;
@@ -25,4 +27,10 @@ use super::KomodoExecuteRequest;
 pub struct RunProcedure {
   /// Id or name
   pub procedure: String,
+  /// Arguments to interpolate into the procedure executions.
+  /// An execution can reference an argument with `[[ARG_NAME]]`,
+  /// eg. a Deploy execution targeting `[[env]]-api`.
+  #[serde(default)]
+  #[arg(skip)]
+  pub args: HashMap<String, String>,
 }
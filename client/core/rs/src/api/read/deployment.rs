@@ -6,7 +6,7 @@ use typeshare::typeshare;
 use crate::entities::{
   deployment::{
     Deployment, DeploymentActionState, DeploymentListItem,
-    DeploymentQuery, DeploymentState,
+    DeploymentQuery, DeploymentRecord, DeploymentState,
   },
   docker::container::{ContainerListItem, ContainerStats},
   update::Log,
@@ -203,6 +203,34 @@ pub type GetDeploymentActionStateResponse = DeploymentActionState;
 
 //
 
+/// List the recorded successful deploys of the deployment,
+/// most recent first. Response: [ListDeploymentHistoryResponse].
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Request, EmptyTraits,
+)]
+#[empty_traits(KomodoReadRequest)]
+#[response(ListDeploymentHistoryResponse)]
+pub struct ListDeploymentHistory {
+  /// Id or name
+  #[serde(alias = "id", alias = "name")]
+  pub deployment: String,
+  /// The maximum number of records to return.
+  /// Default: 20.
+  /// Max: 100.
+  #[serde(default = "default_history_limit")]
+  pub limit: U64,
+}
+
+fn default_history_limit() -> u64 {
+  20
+}
+
+#[typeshare]
+pub type ListDeploymentHistoryResponse = Vec<DeploymentRecord>;
+
+//
+
 /// Gets a summary of data relating to all deployments.
 /// Response: [GetDeploymentsSummaryResponse].
 #[typeshare]
@@ -15,6 +15,7 @@ mod repo;
 mod search;
 mod server;
 mod server_template;
+mod silence;
 mod stack;
 mod sync;
 mod tag;
@@ -36,6 +37,7 @@ pub use repo::*;
 pub use search::*;
 pub use server::*;
 pub use server_template::*;
+pub use silence::*;
 pub use stack::*;
 pub use sync::*;
 pub use tag::*;
@@ -0,0 +1,43 @@
+use derive_empty_traits::EmptyTraits;
+use resolver_api::derive::Request;
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::entities::silence::Silence;
+
+use super::KomodoReadRequest;
+
+/// Get a specific silence by id.
+/// Response: [Silence].
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Request, EmptyTraits,
+)]
+#[empty_traits(KomodoReadRequest)]
+#[response(GetSilenceResponse)]
+pub struct GetSilence {
+  /// The id of the silence.
+  pub id: String,
+}
+
+#[typeshare]
+pub type GetSilenceResponse = Silence;
+
+//
+
+/// List the configured silences, sorted by start time (newest first).
+/// Response: [ListSilencesResponse].
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Default, Request, EmptyTraits,
+)]
+#[empty_traits(KomodoReadRequest)]
+#[response(ListSilencesResponse)]
+pub struct ListSilences {
+  /// Only include the silences which are currently in effect.
+  #[serde(default)]
+  pub active: bool,
+}
+
+#[typeshare]
+pub type ListSilencesResponse = Vec<Silence>;
@@ -3,7 +3,9 @@ use resolver_api::{derive::Request, HasResponse};
 use serde::{Deserialize, Serialize};
 use typeshare::typeshare;
 
-use crate::entities::{NoData, ResourceTarget, I64};
+use crate::entities::{
+  api_key::ApiKeyScope, NoData, ResourceTarget, I64,
+};
 
 pub trait KomodoUserRequest: HasResponse {}
 
@@ -62,6 +64,12 @@ pub struct CreateApiKey {
   /// Default is 0, which means no expiry.
   #[serde(default)]
   pub expires: I64,
+
+  /// Restrict what the api key can be used for.
+  /// Resources and tags can be given by name or id.
+  /// Default is unrestricted.
+  #[serde(default)]
+  pub scope: ApiKeyScope,
 }
 
 /// Response for [CreateApiKey].
@@ -95,3 +103,73 @@ pub struct DeleteApiKey {
 
 #[typeshare]
 pub type DeleteApiKeyResponse = NoData;
+
+//
+
+/// Begin enrolling the calling (local) user in two-factor authentication.
+/// Generates a new TOTP secret, which is not used until enrollment
+/// is confirmed with [ConfirmTotpEnrollment].
+/// Response: [BeginTotpEnrollmentResponse].
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Request, EmptyTraits,
+)]
+#[empty_traits(KomodoUserRequest)]
+#[response(BeginTotpEnrollmentResponse)]
+pub struct BeginTotpEnrollment {}
+
+/// Response for [BeginTotpEnrollment].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BeginTotpEnrollmentResponse {
+  /// The base32 encoded secret, for manual entry in an authenticator app.
+  pub secret: String,
+  /// The `otpauth://` provisioning uri.
+  pub uri: String,
+}
+
+//
+
+/// Confirm two-factor authentication enrollment with a code from
+/// the authenticator app, enabling TOTP for the calling user.
+/// Response: [ConfirmTotpEnrollmentResponse].
+///
+/// Note. After the response is served, there will be no way
+/// to get the recovery codes later.
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Request, EmptyTraits,
+)]
+#[empty_traits(KomodoUserRequest)]
+#[response(ConfirmTotpEnrollmentResponse)]
+pub struct ConfirmTotpEnrollment {
+  /// The current TOTP code.
+  pub code: String,
+}
+
+/// Response for [ConfirmTotpEnrollment].
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfirmTotpEnrollmentResponse {
+  /// Single use codes which can be used in place of a TOTP code,
+  /// eg. if the authenticator device is lost.
+  pub recovery_codes: Vec<String>,
+}
+
+//
+
+/// Disable two-factor authentication for the calling user.
+/// Response: [NoData].
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Request, EmptyTraits,
+)]
+#[empty_traits(KomodoUserRequest)]
+#[response(DisableTotpResponse)]
+pub struct DisableTotp {
+  /// The current TOTP code, or an unused recovery code.
+  pub code: String,
+}
+
+#[typeshare]
+pub type DisableTotpResponse = NoData;
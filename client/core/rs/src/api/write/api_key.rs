@@ -5,7 +5,7 @@ use typeshare::typeshare;
 
 use crate::{
   api::user::CreateApiKeyResponse,
-  entities::{NoData, I64},
+  entities::{api_key::ApiKeyScope, NoData, I64},
 };
 
 use super::KomodoWriteRequest;
@@ -29,6 +29,11 @@ pub struct CreateApiKeyForServiceUser {
   /// Default is 0, which means no expiry.
   #[serde(default)]
   pub expires: I64,
+  /// Restrict what the api key can be used for.
+  /// Resources and tags can be given by name or id.
+  /// Default is unrestricted.
+  #[serde(default)]
+  pub scope: ApiKeyScope,
 }
 
 #[typeshare]
@@ -10,6 +10,7 @@ mod provider;
 mod repo;
 mod server;
 mod server_template;
+mod silence;
 mod stack;
 mod sync;
 mod tags;
@@ -29,6 +30,7 @@ pub use provider::*;
 pub use repo::*;
 pub use server::*;
 pub use server_template::*;
+pub use silence::*;
 pub use stack::*;
 pub use sync::*;
 pub use tags::*;
@@ -54,6 +54,29 @@ pub type UpdatePermissionOnResourceTypeResponse = NoData;
 
 //
 
+/// **Admin only.** Update a user or user groups permission level
+/// on all resources with a tag.
+/// Response: [NoData].
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Request, EmptyTraits,
+)]
+#[empty_traits(KomodoWriteRequest)]
+#[response(UpdatePermissionOnTagResponse)]
+pub struct UpdatePermissionOnTag {
+  /// Specify the user or user group.
+  pub user_target: UserTarget,
+  /// Id or name of the tag.
+  pub tag: String,
+  /// The permission level on resources with the tag.
+  pub permission: PermissionLevel,
+}
+
+#[typeshare]
+pub type UpdatePermissionOnTagResponse = NoData;
+
+//
+
 /// **Admin only.** Update a user's "base" permissions, eg. "enabled".
 /// Response: [NoData].
 #[typeshare]
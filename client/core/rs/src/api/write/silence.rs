@@ -0,0 +1,95 @@
+use derive_empty_traits::EmptyTraits;
+use resolver_api::derive::Request;
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::entities::{
+  alert::AlertDataVariant, silence::Silence, ResourceTarget, I64,
+};
+
+use super::KomodoWriteRequest;
+
+/// **Admin only.** Create a silence, to stop matching alerts
+/// from being sent between `start_ts` and `end_ts`.
+/// Response: [Silence].
+#[typeshare]
+#[derive(
+  Debug, Clone, Serialize, Deserialize, Request, EmptyTraits,
+)]
+#[empty_traits(KomodoWriteRequest)]
+#[response(CreateSilenceResponse)]
+pub struct CreateSilence {
+  /// Why the alerts are silenced.
+  #[serde(default)]
+  pub reason: String,
+  /// Unix timestamp in milliseconds the silence begins.
+  pub start_ts: I64,
+  /// Unix timestamp in milliseconds the silence ends.
+  pub end_ts: I64,
+  /// Only silence alerts on these resources.
+  #[serde(default)]
+  pub resources: Vec<ResourceTarget>,
+  /// Only silence alerts on resources with these tags.
+  /// Accepts tag id or name.
+  #[serde(default)]
+  pub tags: Vec<String>,
+  /// Only silence these alert types.
+  #[serde(default)]
+  pub alert_types: Vec<AlertDataVariant>,
+}
+
+#[typeshare]
+pub type CreateSilenceResponse = Silence;
+
+//
+
+/// **Admin only.** Update a silence. Response: [Silence].
+///
+/// Note. To end a silence early, set `end_ts` to the current time.
+#[typeshare]
+#[derive(
+  Debug, Clone, Serialize, Deserialize, Request, EmptyTraits,
+)]
+#[empty_traits(KomodoWriteRequest)]
+#[response(UpdateSilenceResponse)]
+pub struct UpdateSilence {
+  /// The id of the silence to update.
+  pub id: String,
+  /// Why the alerts are silenced.
+  #[serde(default)]
+  pub reason: String,
+  /// Unix timestamp in milliseconds the silence begins.
+  pub start_ts: I64,
+  /// Unix timestamp in milliseconds the silence ends.
+  pub end_ts: I64,
+  /// Only silence alerts on these resources.
+  #[serde(default)]
+  pub resources: Vec<ResourceTarget>,
+  /// Only silence alerts on resources with these tags.
+  /// Accepts tag id or name.
+  #[serde(default)]
+  pub tags: Vec<String>,
+  /// Only silence these alert types.
+  #[serde(default)]
+  pub alert_types: Vec<AlertDataVariant>,
+}
+
+#[typeshare]
+pub type UpdateSilenceResponse = Silence;
+
+//
+
+/// **Admin only.** Delete a silence. Response: [Silence].
+#[typeshare]
+#[derive(
+  Debug, Clone, Serialize, Deserialize, Request, EmptyTraits,
+)]
+#[empty_traits(KomodoWriteRequest)]
+#[response(DeleteSilenceResponse)]
+pub struct DeleteSilence {
+  /// The id of the silence to delete.
+  pub id: String,
+}
+
+#[typeshare]
+pub type DeleteSilenceResponse = Silence;
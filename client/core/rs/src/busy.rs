@@ -26,6 +26,10 @@ impl Busy for ServerActionState {
 impl Busy for DeploymentActionState {
   fn busy(&self) -> bool {
     self.deploying
+      || self.deploying_next
+      || self.health_checking
+      || self.switching
+      || self.removing_old
       || self.starting
       || self.restarting
       || self.pausing
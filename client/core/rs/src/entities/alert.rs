@@ -5,11 +5,11 @@ use serde::{Deserialize, Serialize};
 use strum::{Display, EnumString};
 use typeshare::typeshare;
 
-use crate::entities::{MongoId, I64};
+use crate::entities::{MongoId, I64, U64};
 
 use super::{
-  _Serror, deployment::DeploymentState, stack::StackState,
-  ResourceTarget, Version,
+  _Serror, build::VulnerabilitySeverity, deployment::DeploymentState,
+  stack::StackState, ResourceTarget, Version,
 };
 
 /// Representation of an alert in the system.
@@ -54,6 +54,11 @@ pub struct Alert {
 
   /// The timestamp of alert resolution
   pub resolved_ts: Option<I64>,
+
+  /// Whether the alert matched an active [Silence][super::silence::Silence].
+  /// Silenced alerts are recorded, but not sent to any alerters.
+  #[serde(default)]
+  pub silenced: bool,
 }
 
 /// The variants of data related to the alert.
@@ -186,6 +191,24 @@ pub enum AlertData {
     version: Version,
   },
 
+  /// A build's image scan found more vulnerabilities than allowed
+  BuildVulnerabilities {
+    /// The id of the build
+    id: String,
+    /// The name of the build
+    name: String,
+    /// The version which was scanned
+    version: Version,
+    /// The scanned image
+    image: String,
+    /// The configured `scan_alert_severity`
+    severity: VulnerabilitySeverity,
+    /// The number of vulnerabilities at or above `severity`
+    count: U64,
+    /// The configured `scan_alert_threshold`
+    threshold: U64,
+  },
+
   /// A repo has failed
   RepoBuildFailed {
     /// The id of the repo
@@ -24,7 +24,7 @@ pub type AlerterListItem = ResourceListItem<AlerterListItemInfo>;
 pub struct AlerterListItemInfo {
   /// Whether alerter is enabled for sending alerts
   pub enabled: bool,
-  /// The type of the alerter, eg. `Slack`, `Custom`
+  /// The type of the alerter, eg. `Slack`, `Discord`, `Custom`
   pub endpoint_type: AlerterEndpointVariant,
 }
 
@@ -112,6 +112,21 @@ pub enum AlerterEndpoint {
 
   /// Send alert to a slack app
   Slack(SlackAlerterEndpoint),
+
+  /// Send alert to a discord webhook
+  Discord(DiscordAlerterEndpoint),
+
+  /// Send alert to a microsoft teams webhook
+  Teams(TeamsAlerterEndpoint),
+
+  /// Send alert to an ntfy topic
+  Ntfy(NtfyAlerterEndpoint),
+
+  /// Send alert to a gotify server
+  Gotify(GotifyAlerterEndpoint),
+
+  /// Send alert as an email over SMTP
+  Email(EmailAlerterEndpoint),
 }
 
 impl Default for AlerterEndpoint {
@@ -170,6 +185,218 @@ fn default_slack_url() -> String {
   )
 }
 
+/// Configuration for a discord alerter.
+#[typeshare]
+#[derive(
+  Debug, Clone, PartialEq, Serialize, Deserialize, Builder,
+)]
+pub struct DiscordAlerterEndpoint {
+  /// The discord webhook url
+  #[serde(default = "default_discord_url")]
+  #[builder(default = "default_discord_url()")]
+  pub url: String,
+}
+
+impl Default for DiscordAlerterEndpoint {
+  fn default() -> Self {
+    Self {
+      url: default_discord_url(),
+    }
+  }
+}
+
+fn default_discord_url() -> String {
+  String::from(
+    "https://discord.com/api/webhooks/XXXXXXXXXXXX/XXXX-XXXXXXXXXX",
+  )
+}
+
+/// Configuration for a microsoft teams alerter.
+#[typeshare]
+#[derive(
+  Debug, Clone, PartialEq, Serialize, Deserialize, Builder,
+)]
+pub struct TeamsAlerterEndpoint {
+  /// The teams incoming webhook / workflow url.
+  /// Alerts are posted as adaptive cards.
+  #[serde(default = "default_teams_url")]
+  #[builder(default = "default_teams_url()")]
+  pub url: String,
+}
+
+impl Default for TeamsAlerterEndpoint {
+  fn default() -> Self {
+    Self {
+      url: default_teams_url(),
+    }
+  }
+}
+
+fn default_teams_url() -> String {
+  String::from(
+    "https://xxxxxxxx.webhook.office.com/webhookb2/XXXXXXXX",
+  )
+}
+
+/// Configuration for an ntfy alerter.
+#[typeshare]
+#[derive(
+  Debug, Clone, PartialEq, Serialize, Deserialize, Builder,
+)]
+pub struct NtfyAlerterEndpoint {
+  /// The full url of the ntfy topic to publish to,
+  /// eg. `https://ntfy.sh/komodo-alerts`.
+  #[serde(default = "default_ntfy_url")]
+  #[builder(default = "default_ntfy_url()")]
+  pub url: String,
+
+  /// Optional access token, if the topic requires authentication.
+  /// Sent as `Authorization: Bearer <token>`.
+  #[serde(default)]
+  #[builder(default)]
+  pub token: String,
+}
+
+impl Default for NtfyAlerterEndpoint {
+  fn default() -> Self {
+    Self {
+      url: default_ntfy_url(),
+      token: Default::default(),
+    }
+  }
+}
+
+fn default_ntfy_url() -> String {
+  String::from("https://ntfy.sh/komodo")
+}
+
+/// Configuration for a gotify alerter.
+#[typeshare]
+#[derive(
+  Debug, Clone, PartialEq, Serialize, Deserialize, Builder,
+)]
+pub struct GotifyAlerterEndpoint {
+  /// The base url of the gotify server, eg. `https://gotify.example.com`
+  #[serde(default = "default_gotify_url")]
+  #[builder(default = "default_gotify_url()")]
+  pub url: String,
+
+  /// The gotify application token to send messages with.
+  #[serde(default)]
+  #[builder(default)]
+  pub token: String,
+}
+
+impl Default for GotifyAlerterEndpoint {
+  fn default() -> Self {
+    Self {
+      url: default_gotify_url(),
+      token: Default::default(),
+    }
+  }
+}
+
+fn default_gotify_url() -> String {
+  String::from("http://localhost:8080")
+}
+
+/// Configuration for an email alerter.
+#[typeshare]
+#[derive(
+  Debug, Clone, PartialEq, Serialize, Deserialize, Builder,
+)]
+pub struct EmailAlerterEndpoint {
+  /// The SMTP server host, eg. `smtp.example.com`
+  #[serde(default)]
+  #[builder(default)]
+  pub host: String,
+
+  /// The SMTP server port.
+  /// Default: `587`
+  #[serde(default = "default_smtp_port")]
+  #[builder(default = "default_smtp_port()")]
+  pub port: u16,
+
+  /// How to secure the connection to the SMTP server.
+  /// Default: `StartTls`
+  #[serde(default)]
+  #[builder(default)]
+  pub security: SmtpSecurity,
+
+  /// The username to authenticate with.
+  /// If empty, will not authenticate.
+  #[serde(default)]
+  #[builder(default)]
+  pub username: String,
+
+  /// The password to authenticate with. Must reference a Variable
+  /// or core config secret, eg. `[[SMTP_PASSWORD]]`, which is
+  /// interpolated when the email is sent.
+  #[serde(default)]
+  #[builder(default)]
+  pub password: String,
+
+  /// The sender address, eg. `Komodo <komodo@example.com>`
+  #[serde(default)]
+  #[builder(default)]
+  pub from: String,
+
+  /// The recipient addresses.
+  #[serde(default)]
+  #[builder(default)]
+  pub to: Vec<String>,
+
+  /// Instead of sending an email for every alert,
+  /// group all the alerts opened / resolved during each
+  /// monitoring cycle into a single digest email.
+  #[serde(default)]
+  #[builder(default)]
+  pub digest: bool,
+}
+
+impl Default for EmailAlerterEndpoint {
+  fn default() -> Self {
+    Self {
+      host: Default::default(),
+      port: default_smtp_port(),
+      security: Default::default(),
+      username: Default::default(),
+      password: Default::default(),
+      from: Default::default(),
+      to: Default::default(),
+      digest: Default::default(),
+    }
+  }
+}
+
+fn default_smtp_port() -> u16 {
+  587
+}
+
+/// How the email alerter secures the SMTP connection.
+#[typeshare]
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  Serialize,
+  Deserialize,
+  Display,
+  EnumString,
+)]
+pub enum SmtpSecurity {
+  /// Plaintext connection. Only use with trusted local relays.
+  None,
+  /// Upgrade the connection with STARTTLS (usually port 587).
+  #[default]
+  StartTls,
+  /// Implicit TLS (usually port 465).
+  Tls,
+}
+
 // QUERY
 
 #[typeshare]
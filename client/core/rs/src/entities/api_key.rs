@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display};
 use typeshare::typeshare;
 
-use super::I64;
+use super::{
+  permission::PermissionLevel, ResourceTarget, ResourceTargetVariant,
+  I64,
+};
 
 /// An api key used to authenticate requests via request headers.
 #[typeshare]
@@ -30,6 +34,12 @@ pub struct ApiKey {
 
   /// Expiry of key, or 0 if never expires
   pub expires: I64,
+
+  /// Restricts what the api key can be used for.
+  /// The default scope is unrestricted, giving the key
+  /// all of the owning user's permissions.
+  #[serde(default)]
+  pub scope: ApiKeyScope,
 }
 
 impl ApiKey {
@@ -37,3 +47,120 @@ impl ApiKey {
     self.secret.clear()
   }
 }
+
+/// Restrictions on what an [ApiKey] can be used for.
+/// These only ever reduce the permissions of the owning user.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApiKeyScope {
+  /// The request families the key may call.
+  /// Empty means all families are allowed.
+  ///
+  /// Note. Scoped keys can never call the `/user` api,
+  /// so they cannot be used to create new api keys.
+  #[serde(default)]
+  pub requests: Vec<ApiKeyRequestType>,
+
+  /// The resources the key may access.
+  /// If both this and `tags` are empty, all resources are allowed.
+  #[serde(default)]
+  pub resources: Vec<ResourceTarget>,
+
+  /// Tag ids. Resources with any of these tags may be accessed
+  /// in addition to those in `resources`.
+  #[serde(default)]
+  pub tags: Vec<String>,
+
+  /// The maximum permission level the key has on any resource.
+  /// Default is Write, ie no restriction.
+  #[serde(default = "default_max_permission")]
+  pub max_permission: PermissionLevel,
+}
+
+fn default_max_permission() -> PermissionLevel {
+  PermissionLevel::Write
+}
+
+impl Default for ApiKeyScope {
+  fn default() -> Self {
+    Self {
+      requests: Default::default(),
+      resources: Default::default(),
+      tags: Default::default(),
+      max_permission: default_max_permission(),
+    }
+  }
+}
+
+impl ApiKeyScope {
+  /// Whether the scope places no restrictions on the key.
+  pub fn is_unrestricted(&self) -> bool {
+    self.requests.is_empty() && !self.restricts_resources()
+  }
+
+  /// Whether the scope limits which resources the key may access,
+  /// or the permission level on them.
+  pub fn restricts_resources(&self) -> bool {
+    !self.resources.is_empty()
+      || !self.tags.is_empty()
+      || self.max_permission < PermissionLevel::Write
+  }
+
+  /// Whether the key may call requests of this type.
+  pub fn allows_request(&self, request: ApiKeyRequestType) -> bool {
+    self.requests.is_empty() || self.requests.contains(&request)
+  }
+
+  /// Clamps the permission level on a resource
+  /// to what the scope allows.
+  pub fn clamp_permission(
+    &self,
+    resource_type: ResourceTargetVariant,
+    resource_id: &str,
+    resource_tags: &[String],
+    level: PermissionLevel,
+  ) -> PermissionLevel {
+    if self.allows_resource(resource_type, resource_id, resource_tags)
+    {
+      level.min(self.max_permission)
+    } else {
+      PermissionLevel::None
+    }
+  }
+
+  fn allows_resource(
+    &self,
+    resource_type: ResourceTargetVariant,
+    resource_id: &str,
+    resource_tags: &[String],
+  ) -> bool {
+    (self.resources.is_empty() && self.tags.is_empty())
+      || self.resources.iter().any(|target| {
+        let (variant, id) = target.extract_variant_id();
+        variant == resource_type && id == resource_id
+      })
+      || resource_tags.iter().any(|tag| self.tags.contains(tag))
+  }
+}
+
+/// The request families an [ApiKey] can be scoped to.
+#[typeshare]
+#[derive(
+  Serialize,
+  Deserialize,
+  Debug,
+  Display,
+  AsRefStr,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+)]
+pub enum ApiKeyRequestType {
+  /// The `/read` api
+  Read,
+  /// The `/execute` api
+  Execute,
+  /// The `/write` api
+  Write,
+}
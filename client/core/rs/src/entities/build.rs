@@ -3,10 +3,10 @@ use derive_builder::Builder;
 use derive_default_builder::DefaultBuilder;
 use partial_derive2::Partial;
 use serde::{Deserialize, Serialize};
-use strum::Display;
+use strum::{Display, EnumString};
 use typeshare::typeshare;
 
-use crate::entities::I64;
+use crate::entities::{I64, U64};
 
 use super::{
   resource::{Resource, ResourceListItem, ResourceQuery},
@@ -70,6 +70,116 @@ pub struct BuildInfo {
   pub latest_hash: Option<String>,
   /// Latest remote commit message, or null
   pub latest_message: Option<String>,
+  /// The result of the latest vulnerability scan, or null.
+  #[serde(default)]
+  pub latest_scan: Option<VulnerabilityScan>,
+}
+
+/// The tool used to scan built images for vulnerabilities.
+#[typeshare]
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  Serialize,
+  Deserialize,
+  Display,
+  EnumString,
+)]
+pub enum ImageScanner {
+  /// Don't scan the image
+  #[default]
+  None,
+  /// Scan with `trivy image`. See `<https://trivy.dev>`.
+  Trivy,
+  /// Scan with `grype`. See `<https://github.com/anchore/grype>`.
+  Grype,
+}
+
+/// Severity of a vulnerability, as reported by the scanner.
+#[typeshare]
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  PartialOrd,
+  Ord,
+  Serialize,
+  Deserialize,
+  Display,
+  EnumString,
+)]
+pub enum VulnerabilitySeverity {
+  #[default]
+  Unknown,
+  Low,
+  Medium,
+  High,
+  Critical,
+}
+
+/// The result of scanning a built image for vulnerabilities.
+#[typeshare]
+#[derive(
+  Serialize, Deserialize, Debug, Clone, Default, PartialEq,
+)]
+pub struct VulnerabilityScan {
+  /// Unix timestamp in milliseconds of the scan
+  pub ts: I64,
+  /// The scanner used
+  pub scanner: ImageScanner,
+  /// The image reference which was scanned
+  pub image: String,
+  /// The image reference holding the SBOM attestation, or null.
+  /// Inspect it with `docker buildx imagetools inspect <sbom> --format '{{ json .SBOM }}'`.
+  pub sbom: Option<String>,
+  /// The number of critical vulnerabilities
+  pub critical: U64,
+  /// The number of high vulnerabilities
+  pub high: U64,
+  /// The number of medium vulnerabilities
+  pub medium: U64,
+  /// The number of low vulnerabilities
+  pub low: U64,
+  /// The number of vulnerabilities with unknown / negligible severity
+  pub unknown: U64,
+}
+
+impl VulnerabilityScan {
+  /// Adds a vulnerability of the given severity to the counts.
+  pub fn add(&mut self, severity: VulnerabilitySeverity) {
+    match severity {
+      VulnerabilitySeverity::Critical => self.critical += 1,
+      VulnerabilitySeverity::High => self.high += 1,
+      VulnerabilitySeverity::Medium => self.medium += 1,
+      VulnerabilitySeverity::Low => self.low += 1,
+      VulnerabilitySeverity::Unknown => self.unknown += 1,
+    }
+  }
+
+  /// The number of vulnerabilities at or above the given severity.
+  pub fn count_at_least(
+    &self,
+    severity: VulnerabilitySeverity,
+  ) -> U64 {
+    [
+      (VulnerabilitySeverity::Critical, self.critical),
+      (VulnerabilitySeverity::High, self.high),
+      (VulnerabilitySeverity::Medium, self.medium),
+      (VulnerabilitySeverity::Low, self.low),
+      (VulnerabilitySeverity::Unknown, self.unknown),
+    ]
+    .into_iter()
+    .filter(|(s, _)| *s >= severity)
+    .map(|(_, count)| count)
+    .sum()
+  }
 }
 
 #[typeshare(serialized_as = "Partial<BuildConfig>")]
@@ -209,6 +319,91 @@ pub struct BuildConfig {
   #[builder(default)]
   pub use_buildx: bool,
 
+  /// The platforms to build the image for, eg `linux/amd64`, `linux/arm64`.
+  /// When set, the image is built using `docker buildx build --platform ...`,
+  /// and all platforms are pushed together under a multi-arch manifest.
+  ///
+  /// If empty, builds for the platform of the builder server.
+  /// Building for multiple platforms requires an image registry to push to.
+  ///
+  /// Note. Building for a platform different than the builder server's
+  /// requires QEMU emulation to be installed on the server.
+  #[serde(default)]
+  #[builder(default)]
+  pub platforms: Vec<String>,
+
+  /// The buildx builder instance used to build for `platforms`, or export `cache_to`.
+  /// If it doesn't exist on the builder server, it is created
+  /// using the `docker-container` driver, which supports multi-platform builds.
+  /// May only contain letters, numbers, `_` and `-`.
+  /// Default: `komodo`
+  #[serde(default = "default_buildx_builder")]
+  #[builder(default = "default_buildx_builder()")]
+  #[partial_default(default_buildx_builder())]
+  pub buildx_builder: String,
+
+  /// BuildKit cache sources, passed to `--cache-from`.
+  /// Eg. `type=registry,ref=ghcr.io/org/app:buildcache`
+  /// or `type=local,src=/var/cache/komodo/app`.
+  #[serde(default)]
+  #[builder(default)]
+  pub cache_from: Vec<String>,
+
+  /// BuildKit cache destinations, passed to `--cache-to`.
+  /// Eg. `type=registry,ref=ghcr.io/org/app:buildcache,mode=max`
+  /// or `type=local,dest=/var/cache/komodo/app`.
+  ///
+  /// Exporting the cache requires building with the buildx builder instance
+  /// (see `buildx_builder`), which is used automatically when this is set.
+  #[serde(default)]
+  #[builder(default)]
+  pub cache_to: Vec<String>,
+
+  /// The registry to login to for registry based caches,
+  /// if it is different than the `image_registry`.
+  #[serde(default)]
+  #[builder(default)]
+  pub cache_registry: ImageRegistry,
+
+  /// Scan the image for vulnerabilities after a successful build.
+  /// The scanner must be installed on the builder.
+  #[serde(default)]
+  #[builder(default)]
+  pub scanner: ImageScanner,
+
+  /// Override the command used to run the scanner.
+  /// `[[IMAGE]]` is replaced with the image reference.
+  /// It must print the scanner's json report to stdout.
+  ///
+  /// Default:
+  /// - Trivy: `trivy image --quiet --format json [[IMAGE]]`
+  /// - Grype: `grype [[IMAGE]] --output json`
+  #[serde(default)]
+  #[builder(default)]
+  pub scan_command: String,
+
+  /// Count the vulnerabilities at or above this severity
+  /// towards the `scan_alert_threshold`.
+  /// Default: `High`
+  #[serde(default = "default_scan_alert_severity")]
+  #[builder(default = "default_scan_alert_severity()")]
+  #[partial_default(default_scan_alert_severity())]
+  pub scan_alert_severity: VulnerabilitySeverity,
+
+  /// Send a `BuildVulnerabilities` alert when the scan finds more than
+  /// this many vulnerabilities at or above `scan_alert_severity`.
+  /// Default: `0`
+  #[serde(default)]
+  #[builder(default)]
+  pub scan_alert_threshold: U64,
+
+  /// Attach an SBOM attestation to the pushed image (`--sbom=true`).
+  /// This builds with the buildx builder instance (see `buildx_builder`),
+  /// and requires an image registry.
+  #[serde(default)]
+  #[builder(default)]
+  pub sbom: bool,
+
   /// Any extra docker cli arguments to be included in the build command
   #[serde(default)]
   #[builder(default)]
@@ -296,6 +491,14 @@ fn default_webhook_enabled() -> bool {
   true
 }
 
+fn default_buildx_builder() -> String {
+  String::from("komodo")
+}
+
+fn default_scan_alert_severity() -> VulnerabilitySeverity {
+  VulnerabilitySeverity::High
+}
+
 impl Default for BuildConfig {
   fn default() -> Self {
     Self {
@@ -320,6 +523,16 @@ impl Default for BuildConfig {
       labels: Default::default(),
       extra_args: Default::default(),
       use_buildx: Default::default(),
+      platforms: Default::default(),
+      buildx_builder: default_buildx_builder(),
+      cache_from: Default::default(),
+      cache_to: Default::default(),
+      cache_registry: Default::default(),
+      scanner: Default::default(),
+      scan_command: Default::default(),
+      scan_alert_severity: default_scan_alert_severity(),
+      scan_alert_threshold: Default::default(),
+      sbom: Default::default(),
       image_registry: Default::default(),
       webhook_enabled: default_webhook_enabled(),
       webhook_secret: Default::default(),
@@ -73,6 +73,11 @@ pub struct Env {
   /// Override `webhook_base_url`
   pub komodo_webhook_base_url: Option<String>,
 
+  /// Override `enable_metrics`
+  pub komodo_enable_metrics: Option<bool>,
+  /// Override `metrics_token`
+  pub komodo_metrics_token: Option<String>,
+
   /// Override `logging.level`
   pub komodo_logging_level: Option<LogLevel>,
   /// Override `logging.stdio`
@@ -93,6 +98,8 @@ pub struct Env {
 
   /// Override `local_auth`
   pub komodo_local_auth: Option<bool>,
+  /// Override `require_admin_totp`
+  pub komodo_require_admin_totp: Option<bool>,
 
   /// Override `google_oauth.enabled`
   pub komodo_google_oauth_enabled: Option<bool>,
@@ -108,6 +115,46 @@ pub struct Env {
   /// Override `github_oauth.secret`
   pub komodo_github_oauth_secret: Option<String>,
 
+  /// Override `oidc.enabled`
+  pub komodo_oidc_enabled: Option<bool>,
+  /// Override `oidc.discovery_url`
+  pub komodo_oidc_discovery_url: Option<String>,
+  /// Override `oidc.client_id`
+  pub komodo_oidc_client_id: Option<String>,
+  /// Override `oidc.client_secret`
+  pub komodo_oidc_client_secret: Option<String>,
+  /// Override `oidc.scopes`. Accepts comma seperated list.
+  pub komodo_oidc_scopes: Option<Vec<String>>,
+  /// Override `oidc.username_claim`
+  pub komodo_oidc_username_claim: Option<String>,
+  /// Override `oidc.groups_claim`
+  pub komodo_oidc_groups_claim: Option<String>,
+  /// Override `oidc.managed_groups`. Accepts comma seperated list.
+  pub komodo_oidc_managed_groups: Option<Vec<String>>,
+
+  /// Override `ldap.enabled`
+  pub komodo_ldap_enabled: Option<bool>,
+  /// Override `ldap.url`
+  pub komodo_ldap_url: Option<String>,
+  /// Override `ldap.bind_dn`
+  pub komodo_ldap_bind_dn: Option<String>,
+  /// Override `ldap.search_base`
+  pub komodo_ldap_search_base: Option<String>,
+  /// Override `ldap.user_filter`
+  pub komodo_ldap_user_filter: Option<String>,
+  /// Override `ldap.starttls`
+  pub komodo_ldap_starttls: Option<bool>,
+  /// Override `ldap.tls_skip_verify`
+  pub komodo_ldap_tls_skip_verify: Option<bool>,
+  /// Override `ldap.group_attribute`
+  pub komodo_ldap_group_attribute: Option<String>,
+  /// Override `ldap.managed_groups`. Accepts comma seperated list.
+  pub komodo_ldap_managed_groups: Option<Vec<String>>,
+  /// Override `ldap.service_bind_dn`
+  pub komodo_ldap_service_bind_dn: Option<String>,
+  /// Override `ldap.service_bind_password`
+  pub komodo_ldap_service_bind_password: Option<String>,
+
   /// Override `github_webhook_app.app_id`
   pub komodo_github_webhook_app_app_id: Option<i64>,
   /// Override `github_webhook_app.installations[i].id`. Accepts comma seperated list.
@@ -212,6 +259,12 @@ pub struct CoreConfig {
   #[serde(default)]
   pub local_auth: bool,
 
+  /// Require admins which log in with local auth to enable
+  /// two-factor authentication (TOTP). Until they do,
+  /// they can only use the api to enroll.
+  #[serde(default)]
+  pub require_admin_totp: bool,
+
   /// Enable transparent mode, which gives all (enabled) users read access to all resources.
   #[serde(default)]
   pub transparent_mode: bool,
@@ -248,6 +301,15 @@ pub struct CoreConfig {
   #[serde(default)]
   pub github_oauth: OauthCredentials,
 
+  /// Configure a generic OpenID Connect provider,
+  /// eg. Authentik or Keycloak.
+  #[serde(default)]
+  pub oidc: OidcConfig,
+
+  /// Configure LDAP / Active Directory login.
+  #[serde(default)]
+  pub ldap: LdapConfig,
+
   // ============
   // = Webhooks =
   // ============
@@ -277,6 +339,20 @@ pub struct CoreConfig {
   #[serde(default)]
   pub logging: LogConfig,
 
+  // ===========
+  // = Metrics =
+  // ===========
+  /// Expose Prometheus / OpenMetrics metrics at `/metrics`.
+  /// Default: false
+  #[serde(default)]
+  pub enable_metrics: bool,
+
+  /// If provided, scrapers must pass it as a bearer token,
+  /// ie. `Authorization: Bearer <metrics_token>`.
+  /// Default: "" (empty string, no auth)
+  #[serde(default)]
+  pub metrics_token: String,
+
   // ===========
   // = Pruning =
   // ===========
@@ -424,11 +500,14 @@ impl CoreConfig {
       keep_stats_for_days: config.keep_stats_for_days,
       keep_alerts_for_days: config.keep_alerts_for_days,
       logging: config.logging,
+      enable_metrics: config.enable_metrics,
+      metrics_token: empty_or_redacted(&config.metrics_token),
       transparent_mode: config.transparent_mode,
       ui_write_disabled: config.ui_write_disabled,
       enable_new_users: config.enable_new_users,
       disable_user_registration: config.disable_user_registration,
       local_auth: config.local_auth,
+      require_admin_totp: config.require_admin_totp,
       google_oauth: OauthCredentials {
         enabled: config.google_oauth.enabled,
         id: empty_or_redacted(&config.google_oauth.id),
@@ -439,6 +518,16 @@ impl CoreConfig {
         id: empty_or_redacted(&config.github_oauth.id),
         secret: empty_or_redacted(&config.github_oauth.id),
       },
+      oidc: OidcConfig {
+        client_secret: empty_or_redacted(&config.oidc.client_secret),
+        ..config.oidc
+      },
+      ldap: LdapConfig {
+        service_bind_password: empty_or_redacted(
+          &config.ldap.service_bind_password,
+        ),
+        ..config.ldap
+      },
       webhook_secret: empty_or_redacted(&config.webhook_secret),
       webhook_base_url: config.webhook_base_url,
       github_webhook_app: config.github_webhook_app,
@@ -526,6 +615,150 @@ pub struct OauthCredentials {
   pub secret: String,
 }
 
+/// Configuration for a generic OpenID Connect provider.
+/// Login uses the authorization code flow with PKCE.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcConfig {
+  /// Whether OIDC login is available for usage.
+  #[serde(default)]
+  pub enabled: bool,
+  /// The provider issuer url, eg. `https://auth.example.com/application/o/komodo/`.
+  /// The full `/.well-known/openid-configuration` url is also accepted.
+  #[serde(default)]
+  pub discovery_url: String,
+  /// The OIDC client id.
+  #[serde(default)]
+  pub client_id: String,
+  /// The OIDC client secret.
+  #[serde(default)]
+  pub client_secret: String,
+  /// The scopes to request.
+  /// Default: `openid`, `profile`, `email`.
+  #[serde(default = "default_oidc_scopes")]
+  pub scopes: Vec<String>,
+  /// The id token claim used as the Komodo username
+  /// for newly created users.
+  /// Default: `preferred_username`.
+  #[serde(default = "default_oidc_username_claim")]
+  pub username_claim: String,
+  /// The id token claim containing the user's groups.
+  /// When set, membership in the `managed_groups` is synced
+  /// on every login. The claim may need to be
+  /// requested with an additional scope, eg. `groups`.
+  /// Default: empty (disabled).
+  #[serde(default)]
+  pub groups_claim: String,
+  /// The names of the Komodo User Groups whose membership
+  /// is managed by the provider. Membership in other
+  /// User Groups is never changed on login.
+  /// Default: empty (no groups are managed).
+  #[serde(default)]
+  pub managed_groups: Vec<String>,
+}
+
+fn default_oidc_scopes() -> Vec<String> {
+  ["openid", "profile", "email"]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn default_oidc_username_claim() -> String {
+  String::from("preferred_username")
+}
+
+impl Default for OidcConfig {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      discovery_url: Default::default(),
+      client_id: Default::default(),
+      client_secret: Default::default(),
+      scopes: default_oidc_scopes(),
+      username_claim: default_oidc_username_claim(),
+      groups_claim: Default::default(),
+      managed_groups: Default::default(),
+    }
+  }
+}
+
+/// Configuration for LDAP / Active Directory login.
+/// Users log in by binding to the directory with their own credentials.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LdapConfig {
+  /// Whether LDAP login is available for usage.
+  #[serde(default)]
+  pub enabled: bool,
+  /// The directory url, eg. `ldaps://ldap.example.com:636`.
+  #[serde(default)]
+  pub url: String,
+  /// The DN to bind as the user, where `{username}`
+  /// is replaced with the (escaped) login username.
+  /// Examples:
+  ///   - `uid={username},ou=people,dc=example,dc=com`
+  ///   - `{username}@corp.example.com` (Active Directory)
+  #[serde(default)]
+  pub bind_dn: String,
+  /// The base DN to search for the user entry after binding,
+  /// eg. `dc=example,dc=com`. Required.
+  #[serde(default)]
+  pub search_base: String,
+  /// The filter used to find the user entry under `search_base`,
+  /// where `{username}` is replaced with the (escaped) login username.
+  /// Default: `(uid={username})`.
+  /// For Active Directory, use `(sAMAccountName={username})`.
+  #[serde(default = "default_ldap_user_filter")]
+  pub user_filter: String,
+  /// Upgrade an `ldap://` connection using StartTLS.
+  #[serde(default)]
+  pub starttls: bool,
+  /// Skip verification of the directory TLS certificate.
+  /// Only use for testing.
+  #[serde(default)]
+  pub tls_skip_verify: bool,
+  /// The user entry attribute listing the user's groups, eg. `memberOf`.
+  /// When set, membership in the `managed_groups` is synced
+  /// on every login, matching on the group CN.
+  /// Default: empty (disabled).
+  #[serde(default)]
+  pub group_attribute: String,
+  /// The names of the Komodo User Groups whose membership
+  /// is managed by the directory. Membership in other
+  /// User Groups is never changed on login.
+  /// Default: empty (no groups are managed).
+  #[serde(default)]
+  pub managed_groups: Vec<String>,
+  /// A service account DN used to check that users' directory accounts
+  /// still exist and are enabled while they use Komodo. Required.
+  #[serde(default)]
+  pub service_bind_dn: String,
+  /// The password for the service account.
+  #[serde(default)]
+  pub service_bind_password: String,
+}
+
+fn default_ldap_user_filter() -> String {
+  String::from("(uid={username})")
+}
+
+impl Default for LdapConfig {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      url: Default::default(),
+      bind_dn: Default::default(),
+      search_base: Default::default(),
+      user_filter: default_ldap_user_filter(),
+      starttls: false,
+      tls_skip_verify: false,
+      group_attribute: Default::default(),
+      managed_groups: Default::default(),
+      service_bind_dn: Default::default(),
+      service_bind_password: Default::default(),
+    }
+  }
+}
+
 /// Provide mongo connection information.
 /// Must provide ONE of:
 /// 1. `uri`
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use typeshare::typeshare;
 
+use super::provider::GitProviderType;
+
 pub mod core;
 pub mod periphery;
 
@@ -23,6 +25,10 @@ pub struct GitProvider {
   /// Whether to use https. Default: true.
   #[serde(default = "default_git_https")]
   pub https: bool,
+  /// The type of git provider, used to manage repo webhooks
+  /// on Gitlab / Gitea. Default: `Github`.
+  #[serde(default)]
+  pub provider_type: GitProviderType,
   /// The account username. Required.
   #[serde(alias = "account")]
   pub accounts: Vec<ProviderAccount>,
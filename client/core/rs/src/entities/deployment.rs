@@ -14,7 +14,7 @@ use typeshare::typeshare;
 use super::{
   docker::container::ContainerStateStatusEnum,
   resource::{Resource, ResourceListItem, ResourceQuery},
-  EnvironmentVar, TerminationSignal, Version,
+  EnvironmentVar, MongoId, TerminationSignal, Version, I64,
 };
 
 #[typeshare]
@@ -102,6 +102,11 @@ pub struct DeploymentConfig {
   #[builder(default)]
   pub restart: RestartMode,
 
+  /// How to replace the running container on redeploy.
+  #[serde(default)]
+  #[builder(default)]
+  pub strategy: DeploymentStrategy,
+
   /// This is interpolated at the end of the `docker run` command,
   /// which means they are either passed to the containers inner process,
   /// or replaces the container command, depending on use of ENTRYPOINT or CMD in dockerfile.
@@ -185,6 +190,32 @@ pub struct DeploymentConfig {
   ))]
   #[builder(default)]
   pub labels: Vec<EnvironmentVar>,
+
+  /// Wait for the container to pass a health check after `docker run`.
+  /// If it doesn't pass before the timeout, the deploy is marked as failed.
+  #[serde(default)]
+  #[builder(default)]
+  pub health_gate: HealthGate,
+
+  /// The target probed from the server by the Http / Tcp health gates.
+  ///  - Http: A url, eg. `http://localhost:8080/health`. Passes on any 2xx response.
+  ///  - Tcp: An address, eg. `localhost:5432`. Passes once a connection is accepted.
+  #[serde(default)]
+  #[builder(default)]
+  pub health_gate_target: String,
+
+  /// The seconds to wait for the health gate to pass.
+  /// Default: 60
+  #[serde(default = "default_health_gate_timeout")]
+  #[builder(default = "default_health_gate_timeout()")]
+  #[partial_default(default_health_gate_timeout())]
+  pub health_gate_timeout: i32,
+
+  /// Keep the previous container (stopped) until the health gate passes,
+  /// and restore it if the deploy fails.
+  #[serde(default)]
+  #[builder(default)]
+  pub health_gate_restore: bool,
 }
 
 impl DeploymentConfig {
@@ -209,6 +240,10 @@ fn default_network() -> String {
   String::from("host")
 }
 
+fn default_health_gate_timeout() -> i32 {
+  60
+}
+
 impl Default for DeploymentConfig {
   fn default() -> Self {
     Self {
@@ -228,8 +263,13 @@ impl Default for DeploymentConfig {
       labels: Default::default(),
       network: default_network(),
       restart: Default::default(),
+      strategy: Default::default(),
       command: Default::default(),
       extra_args: Default::default(),
+      health_gate: Default::default(),
+      health_gate_target: Default::default(),
+      health_gate_timeout: default_health_gate_timeout(),
+      health_gate_restore: Default::default(),
     }
   }
 }
@@ -494,6 +534,62 @@ impl From<ContainerStateStatusEnum> for DeploymentState {
   }
 }
 
+/// How the running container is replaced on redeploy.
+#[typeshare]
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  Serialize,
+  Deserialize,
+  Display,
+  EnumString,
+)]
+pub enum DeploymentStrategy {
+  /// Stop and remove the old container, then start the new one.
+  #[default]
+  Recreate,
+  /// Start the new container alongside the old one, wait for
+  /// it to pass the health gate, move the network alias over,
+  /// and only then remove the old container.
+  ///
+  /// The containers run at the same time, so the deployment
+  /// can't use the `host` network or publish ports.
+  ///
+  /// With `health_gate` set to `None`, the new container is
+  /// promoted as soon as it is running, whether or not it is ready.
+  BlueGreen,
+}
+
+/// How to check the container is healthy after deploying.
+#[typeshare]
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  Serialize,
+  Deserialize,
+  Display,
+  EnumString,
+)]
+pub enum HealthGate {
+  /// Don't wait for the container to become healthy.
+  #[default]
+  None,
+  /// Wait for the status of the image / container HEALTHCHECK to be `healthy`.
+  Docker,
+  /// Wait for a 2xx response to a GET request to the target url.
+  Http,
+  /// Wait for a connection to the target address to be accepted.
+  Tcp,
+}
+
 #[typeshare]
 #[derive(
   Serialize,
@@ -709,6 +805,14 @@ impl<'de> Visitor<'de> for OptionTermSignalLabelVisitor {
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct DeploymentActionState {
   pub deploying: bool,
+  /// BlueGreen: Starting the new container next to the old one.
+  pub deploying_next: bool,
+  /// BlueGreen: Waiting for the new container to pass the health gate.
+  pub health_checking: bool,
+  /// BlueGreen: Moving the network alias to the new container.
+  pub switching: bool,
+  /// BlueGreen: Removing the old container.
+  pub removing_old: bool,
   pub starting: bool,
   pub restarting: bool,
   pub pausing: bool,
@@ -718,6 +822,64 @@ pub struct DeploymentActionState {
   pub renaming: bool,
 }
 
+/// A successful deploy of a deployment, recorded so the deployment
+/// can be rolled back to a previous known-good image and config.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(
+  feature = "mongo",
+  derive(mongo_indexed::derive::MongoIndexed)
+)]
+#[cfg_attr(feature = "mongo", collection_name(DeploymentHistory))]
+pub struct DeploymentRecord {
+  /// The Mongo ID of the record.
+  /// This field is de/serialized from/to JSON as
+  /// `{ "_id": { "$oid": "..." }, ...(rest of serialized DeploymentRecord) }`
+  #[serde(
+    default,
+    rename = "_id",
+    skip_serializing_if = "String::is_empty",
+    with = "bson::serde_helpers::hex_string_as_object_id"
+  )]
+  pub id: MongoId,
+
+  /// The id of the deployment which was deployed.
+  #[cfg_attr(feature = "mongo", index)]
+  pub deployment_id: String,
+
+  /// Unix timestamp in milliseconds of the deploy.
+  #[cfg_attr(feature = "mongo", index)]
+  pub ts: I64,
+
+  /// The full image which was deployed, eg. `mbecker2020/komodo:1.2.3`.
+  #[serde(default)]
+  pub image: String,
+
+  /// The version of the attached Build which was deployed,
+  /// if the deployment uses a Build.
+  #[serde(default)]
+  pub version: Version,
+
+  /// Sha256 hash (hex) of the deployed config.
+  /// Records with the same hash deployed the same image and config.
+  #[serde(default)]
+  pub config_hash: String,
+
+  /// The deployed config, before variable / secret interpolation.
+  /// Attached Builds are pinned to the deployed version.
+  #[serde(default)]
+  pub config: DeploymentConfig,
+
+  /// The id of the Update for the deploy.
+  #[serde(default)]
+  pub update_id: String,
+
+  /// Whether the deployment was later rolled back past this record.
+  /// These records are skipped when finding the rollback target.
+  #[serde(default)]
+  pub rolled_back: bool,
+}
+
 #[typeshare]
 pub type DeploymentQuery = ResourceQuery<DeploymentQuerySpecifics>;
 
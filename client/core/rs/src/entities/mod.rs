@@ -50,6 +50,8 @@ pub mod resource;
 pub mod server;
 /// Subtypes of [ServerTemplate][server_template::ServerTemplate].
 pub mod server_template;
+/// Subtypes of [Silence][silence::Silence]
+pub mod silence;
 /// Subtypes of [Stack][stack::Stack]
 pub mod stack;
 /// Subtypes for server stats reporting.
@@ -763,6 +765,7 @@ pub enum Operation {
   UpdateDeployment,
   DeleteDeployment,
   Deploy,
+  RollbackDeployment,
   StartDeployment,
   RestartDeployment,
   PauseDeployment,
@@ -770,6 +773,7 @@ pub enum Operation {
   StopDeployment,
   DestroyDeployment,
   RenameDeployment,
+  ExecDeployment,
 
   // repo
   CreateRepo,
@@ -823,12 +827,18 @@ pub enum Operation {
   PauseStackService,
   UnpauseStackService,
   StopStackService,
+  ExecStackService,
 
   // variable
   CreateVariable,
   UpdateVariableValue,
   DeleteVariable,
 
+  // silence
+  CreateSilence,
+  UpdateSilence,
+  DeleteSilence,
+
   // git provider
   CreateGitProviderAccount,
   UpdateGitProviderAccount,
@@ -9,7 +9,10 @@ use typeshare::typeshare;
 use crate::api::execute::Execution;
 
 use super::{
+  deployment::DeploymentState,
   resource::{Resource, ResourceListItem, ResourceQuery},
+  server::ServerState,
+  stack::StackState,
   I64,
 };
 
@@ -23,6 +26,11 @@ pub struct ProcedureListItemInfo {
   pub stages: I64,
   /// Reflect whether last run successful / currently running.
   pub state: ProcedureState,
+  /// If the procedure has a schedule configured,
+  /// the unix timestamp in milliseconds of the next scheduled run.
+  pub next_scheduled_run: Option<I64>,
+  /// If the configured schedule is invalid, the error.
+  pub schedule_error: Option<String>,
 }
 
 #[typeshare]
@@ -72,6 +80,35 @@ pub struct ProcedureConfig {
   #[serde(default)]
   #[builder(default)]
   pub webhook_secret: String,
+
+  /// Choose whether to specify the schedule as a cron expression,
+  /// or as a fixed interval.
+  #[serde(default)]
+  #[builder(default)]
+  pub schedule_format: ScheduleFormat,
+
+  /// Optionally run the procedure on a schedule.
+  /// If empty, the procedure is not scheduled.
+  ///
+  /// - `Cron`: A cron expression, eg. `0 0 3 * * *` (every day at 03:00).
+  ///   Supports the 5 field (minute precision) and 6 field (second precision) formats.
+  /// - `Interval`: A timelength, eg. `1-hr`, `1-day`.
+  ///   Runs are aligned to the unix epoch, eg. `1-hr` runs at the top of every hour.
+  #[serde(default)]
+  #[builder(default)]
+  pub schedule: String,
+
+  /// Whether the schedule is enabled.
+  #[serde(default = "default_schedule_enabled")]
+  #[builder(default = "default_schedule_enabled()")]
+  #[partial_default(default_schedule_enabled())]
+  pub schedule_enabled: bool,
+
+  /// The IANA timezone to evaluate the cron schedule in,
+  /// eg. `America/New_York`. If empty, uses UTC.
+  #[serde(default)]
+  #[builder(default)]
+  pub schedule_timezone: String,
 }
 
 impl ProcedureConfig {
@@ -84,16 +121,45 @@ fn default_webhook_enabled() -> bool {
   true
 }
 
+fn default_schedule_enabled() -> bool {
+  true
+}
+
 impl Default for ProcedureConfig {
   fn default() -> Self {
     Self {
       stages: Default::default(),
       webhook_enabled: default_webhook_enabled(),
       webhook_secret: Default::default(),
+      schedule_format: Default::default(),
+      schedule: Default::default(),
+      schedule_enabled: default_schedule_enabled(),
+      schedule_timezone: Default::default(),
     }
   }
 }
 
+/// How the procedure schedule is specified.
+#[typeshare]
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  Serialize,
+  Deserialize,
+  Display,
+)]
+pub enum ScheduleFormat {
+  /// A cron expression, eg. `0 0 3 * * *`
+  #[default]
+  Cron,
+  /// A fixed interval, eg. `1-hr`
+  Interval,
+}
+
 /// A single stage of a procedure. Runs a list of executions in parallel.
 #[typeshare]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -103,11 +169,79 @@ pub struct ProcedureStage {
   /// Whether the stage should be run as part of the procedure.
   #[serde(default = "default_enabled")]
   pub enabled: bool,
+  /// Run the stage depending on the result of the previous stages.
+  /// Default: `Success`
+  #[serde(default)]
+  pub run_on: StageRunOn,
+  /// An additional condition evaluated right before the stage runs.
+  /// If it isn't met, the stage is skipped.
+  #[serde(default)]
+  pub condition: StageCondition,
   /// The executions in the stage
   #[serde(default)]
   pub executions: Vec<EnabledExecution>,
 }
 
+/// Run the stage depending on the result of the previous stages.
+#[typeshare]
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  Serialize,
+  Deserialize,
+  Display,
+)]
+pub enum StageRunOn {
+  /// Only run if all the previous stages succeeded.
+  #[default]
+  Success,
+  /// Only run if a previous stage failed.
+  /// The procedure will still be marked as failed.
+  Failure,
+  /// Run whether or not the previous stages succeeded.
+  Always,
+}
+
+/// A condition evaluated right before the stage runs.
+#[typeshare]
+#[derive(
+  Debug, Clone, Default, PartialEq, Serialize, Deserialize,
+)]
+#[serde(tag = "type", content = "params")]
+pub enum StageCondition {
+  /// No condition, always run.
+  #[default]
+  None,
+  /// Only run if an earlier stage of this procedure run
+  /// built it successfully.
+  BuildNewVersion {
+    /// Id or name
+    build: String,
+  },
+  /// Only run if the server has the given state.
+  ServerState {
+    /// Id or name
+    server: String,
+    state: ServerState,
+  },
+  /// Only run if the deployment has the given state.
+  DeploymentState {
+    /// Id or name
+    deployment: String,
+    state: DeploymentState,
+  },
+  /// Only run if the stack has the given state.
+  StackState {
+    /// Id or name
+    stack: String,
+    state: StackState,
+  },
+}
+
 /// Allows to enable / disabled procedures in the sequence / parallel vec on the fly
 #[typeshare]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -117,6 +251,24 @@ pub struct EnabledExecution {
   /// Whether the execution is enabled to run in the procedure.
   #[serde(default = "default_enabled")]
   pub enabled: bool,
+  /// The number of times to retry the execution if it fails.
+  /// Default: `0` (no retries)
+  #[serde(default)]
+  pub retries: I64,
+  /// The time to wait between retries, in milliseconds.
+  #[serde(default)]
+  pub retry_delay_ms: I64,
+  /// Fail the attempt if it doesn't finish within this many milliseconds.
+  /// If `0`, the attempt has no timeout.
+  /// Builds and repo builds are cancelled on timeout, other
+  /// executions keep running, so a timed out attempt is never retried.
+  #[serde(default)]
+  pub timeout_ms: I64,
+  /// If the execution still fails after all retries,
+  /// log the error and let the procedure continue
+  /// instead of failing the procedure.
+  #[serde(default)]
+  pub continue_on_error: bool,
 }
 
 fn default_enabled() -> bool {
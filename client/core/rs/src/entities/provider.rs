@@ -49,6 +49,34 @@ pub struct GitProviderAccount {
   /// If the database / host can be accessed this is insecure.
   #[serde(default)]
   pub token: String,
+  /// The type of git provider. For Gitlab and Gitea,
+  /// the token is also used to manage repo webhooks.
+  #[serde(default)]
+  pub provider_type: GitProviderType,
+}
+
+/// The type of git provider.
+#[typeshare]
+#[derive(
+  Serialize,
+  Deserialize,
+  Debug,
+  Clone,
+  Copy,
+  Default,
+  PartialEq,
+  Eq,
+  Hash,
+  PartialOrd,
+  Ord,
+)]
+pub enum GitProviderType {
+  /// Github webhooks are managed using the Github webhook app.
+  #[default]
+  Github,
+  Gitlab,
+  /// Also works for Forgejo.
+  Gitea,
 }
 
 fn default_git_domain() -> String {
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use super::{alert::AlertDataVariant, MongoId, ResourceTarget, I64};
+
+/// A window of time during which matching alerts are silenced,
+/// eg. for planned maintenance.
+///
+/// Silenced alerts are still recorded with `silenced: true`,
+/// but are not sent to any alerters.
+///
+/// An alert matches the silence if it matches **all** of the
+/// configured filters. Empty filters match every alert.
+#[typeshare]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(
+  feature = "mongo",
+  derive(mongo_indexed::derive::MongoIndexed)
+)]
+pub struct Silence {
+  /// The Mongo ID of the silence.
+  /// This field is de/serialized from/to JSON as
+  /// `{ "_id": { "$oid": "..." }, ...(rest of serialized Silence) }`
+  #[serde(
+    default,
+    rename = "_id",
+    skip_serializing_if = "String::is_empty",
+    with = "bson::serde_helpers::hex_string_as_object_id"
+  )]
+  pub id: MongoId,
+
+  /// Why the alerts are silenced, eg. "Database migration".
+  #[serde(default)]
+  pub reason: String,
+
+  /// Unix timestamp in milliseconds the silence begins.
+  #[cfg_attr(feature = "mongo", index)]
+  pub start_ts: I64,
+
+  /// Unix timestamp in milliseconds the silence ends.
+  #[cfg_attr(feature = "mongo", index)]
+  pub end_ts: I64,
+
+  /// Only silence alerts on these resources.
+  /// If empty, matches alerts on all resources.
+  #[serde(default)]
+  pub resources: Vec<ResourceTarget>,
+
+  /// Only silence alerts on resources with any of these tag ids.
+  /// If empty, matches alerts on all resources.
+  #[serde(default)]
+  pub tags: Vec<String>,
+
+  /// Only silence these alert types.
+  /// If empty, matches all alert types.
+  #[serde(default)]
+  pub alert_types: Vec<AlertDataVariant>,
+
+  /// The id of the user who created the silence.
+  #[serde(default)]
+  pub created_by: String,
+
+  /// Unix timestamp in milliseconds the silence was created.
+  #[serde(default)]
+  pub created_at: I64,
+}
@@ -159,6 +159,11 @@ pub struct UserGroupToml {
   #[serde(default)]
   pub all: HashMap<ResourceTargetVariant, PermissionLevel>,
 
+  /// Give the user group elevated permissions on all resources with a certain tag.
+  /// Maps tag name to the permission level.
+  #[serde(default)]
+  pub tags: HashMap<String, PermissionLevel>,
+
   /// Permissions given to the group
   #[serde(default, alias = "permission")]
   pub permissions: Vec<PermissionToml>,
@@ -196,6 +196,20 @@ impl Log {
   }
 }
 
+/// Incremental output of a running command.
+/// Streamed from Periphery to Core while the command runs.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LogOutput {
+  /// The stage of the Log the output belongs to
+  pub stage: String,
+  /// The command which is executing
+  pub command: String,
+  /// New output in the standard channel
+  pub stdout: String,
+  /// New output in the error channel
+  pub stderr: String,
+}
+
 /// An update's status
 #[typeshare]
 #[derive(
@@ -5,9 +5,7 @@ use typeshare::typeshare;
 
 use crate::entities::{MongoId, I64};
 
-use super::{
-  permission::PermissionLevel, ResourceTargetVariant,
-};
+use super::{permission::PermissionLevel, ResourceTargetVariant};
 
 #[typeshare]
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -18,6 +16,7 @@ use super::{
 #[cfg_attr(feature = "mongo", doc_index({ "config.type": 1 }))]
 #[cfg_attr(feature = "mongo", sparse_doc_index({ "config.data.google_id": 1 }))]
 #[cfg_attr(feature = "mongo", sparse_doc_index({ "config.data.github_id": 1 }))]
+#[cfg_attr(feature = "mongo", sparse_doc_index({ "config.data.oidc_id": 1 }))]
 pub struct User {
   /// The Mongo ID of the User.
   /// This field is de/serialized from/to JSON as
@@ -66,6 +65,11 @@ pub struct User {
   #[serde(default)]
   pub all: HashMap<ResourceTargetVariant, PermissionLevel>,
 
+  /// Give the user elevated permissions on all resources with a certain tag.
+  /// Maps tag id to the permission level.
+  #[serde(default)]
+  pub tags: HashMap<String, PermissionLevel>,
+
   #[serde(default)]
   pub updated_at: I64,
 }
@@ -73,8 +77,14 @@ pub struct User {
 impl User {
   /// Prepares user object for transport by removing any sensitive fields
   pub fn sanitize(&mut self) {
-    if let UserConfig::Local { .. } = &self.config {
-      self.config = UserConfig::default();
+    if let UserConfig::Local { totp_enabled, .. } = &self.config {
+      self.config = UserConfig::Local {
+        password: String::new(),
+        totp_enabled: *totp_enabled,
+        totp_secret: String::new(),
+        totp_recovery_codes: Vec::new(),
+        totp_last_step: 0,
+      };
     }
   }
 
@@ -222,7 +232,23 @@ pub fn repo_user() -> &'static User {
 #[serde(tag = "type", content = "data")]
 pub enum UserConfig {
   /// User that logs in with username / password
-  Local { password: String },
+  Local {
+    password: String,
+    /// Whether the user has enabled two-factor authentication (TOTP).
+    #[serde(default)]
+    totp_enabled: bool,
+    /// The base32 encoded TOTP secret.
+    /// Set during enrollment, before TOTP is enabled.
+    #[serde(default)]
+    totp_secret: String,
+    /// Hashes of the unused TOTP recovery codes.
+    #[serde(default)]
+    totp_recovery_codes: Vec<String>,
+    /// The time step of the last accepted TOTP code.
+    /// Codes at or before this step are rejected, so they can't be replayed.
+    #[serde(default)]
+    totp_last_step: I64,
+  },
 
   /// User that logs in via Google Oauth
   Google { google_id: String, avatar: String },
@@ -230,6 +256,18 @@ pub enum UserConfig {
   /// User that logs in via Github Oauth
   Github { github_id: String, avatar: String },
 
+  /// User that logs in via an OpenID Connect provider.
+  /// The `provider` is the issuer url, and `oidc_id` is the `sub` claim.
+  Oidc {
+    provider: String,
+    oidc_id: String,
+    avatar: String,
+  },
+
+  /// User that logs in via LDAP / Active Directory.
+  /// The `dn` is the user's directory entry.
+  Ldap { dn: String },
+
   /// Non-human managed user, can have it's own permissions / api keys
   Service { description: String },
 }
@@ -238,6 +276,10 @@ impl Default for UserConfig {
   fn default() -> Self {
     Self::Local {
       password: String::new(),
+      totp_enabled: false,
+      totp_secret: String::new(),
+      totp_recovery_codes: Vec::new(),
+      totp_last_step: 0,
     }
   }
 }
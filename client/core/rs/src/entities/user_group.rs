@@ -43,6 +43,11 @@ pub struct UserGroup {
   #[serde(default)]
   pub all: HashMap<ResourceTargetVariant, PermissionLevel>,
 
+  /// Give the user group elevated permissions on all resources with a certain tag.
+  /// Maps tag id to the permission level.
+  #[serde(default)]
+  pub tags: HashMap<String, PermissionLevel>,
+
   /// Unix time (ms) when user group last updated
   #[serde(default)]
   pub updated_at: I64,
@@ -12,7 +12,10 @@ use tracing::{info, info_span, warn, Instrument};
 use typeshare::typeshare;
 use uuid::Uuid;
 
-use crate::{entities::update::UpdateListItem, KomodoClient};
+use crate::{
+  entities::{update::UpdateListItem, SearchCombinator, I64, U64},
+  KomodoClient,
+};
 
 #[typeshare]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +37,146 @@ impl WsLoginMessage {
   }
 }
 
+/// Control messages sent as Text over the container terminal websockets,
+/// `/ws/terminal/deployment` and `/ws/terminal/stack`.
+/// Binary messages carry the raw terminal input / output.
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "params")]
+pub enum TerminalMessage {
+  /// Resize the terminal
+  Resize { rows: u16, cols: u16 },
+}
+
+/// Query params for `/ws/terminal/deployment`
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentTerminalQuery {
+  /// Id or name
+  pub deployment: String,
+  /// The shell to run in the container.
+  /// Default: `sh`
+  #[serde(default)]
+  pub shell: Option<String>,
+}
+
+/// Query params for `/ws/terminal/stack`
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StackTerminalQuery {
+  /// Id or name
+  pub stack: String,
+  /// The service name
+  pub service: String,
+  /// The shell to run in the container.
+  /// Default: `sh`
+  #[serde(default)]
+  pub shell: Option<String>,
+}
+
+/// Query params for `/ws/log/deployment`
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeploymentLogQuery {
+  /// Id or name
+  pub deployment: String,
+  /// The number of existing lines to send before following.
+  /// Default: `100`
+  #[serde(default = "default_log_tail")]
+  pub tail: U64,
+  /// Prefix each line with its timestamp.
+  #[serde(default)]
+  pub timestamps: bool,
+  /// Only include lines after this unix timestamp (ms).
+  pub since: Option<I64>,
+  /// Only include lines before this unix timestamp (ms).
+  /// The log will stop following once reached.
+  pub until: Option<I64>,
+}
+
+/// Query params for `/ws/log/stack`
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StackLogQuery {
+  /// Id or name
+  pub stack: String,
+  /// The service name
+  pub service: String,
+  /// The number of existing lines to send before following.
+  /// Default: `100`
+  #[serde(default = "default_log_tail")]
+  pub tail: U64,
+  /// Prefix each line with its timestamp.
+  #[serde(default)]
+  pub timestamps: bool,
+  /// Only include lines after this unix timestamp (ms).
+  pub since: Option<I64>,
+  /// Only include lines before this unix timestamp (ms).
+  /// The log will stop following once reached.
+  pub until: Option<I64>,
+}
+
+fn default_log_tail() -> U64 {
+  100
+}
+
+/// Messages the client may send as Text over the log websockets,
+/// `/ws/log/deployment` and `/ws/log/stack`.
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "params")]
+pub enum LogFollowMessage {
+  /// Replace the search filter applied to incoming lines.
+  /// Send empty `terms` to clear it.
+  Search(LogSearch),
+}
+
+/// Filter lines of a followed log by search terms.
+#[typeshare]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogSearch {
+  /// The terms to search for.
+  pub terms: Vec<String>,
+  /// When searching for multiple terms, can use `AND` or `OR` combinator.
+  ///
+  /// - `AND`: Only include lines with **all** terms present in that line.
+  /// - `OR`: Include lines that have one or more matches in the terms.
+  #[serde(default)]
+  pub combinator: SearchCombinator,
+  /// Invert the results, ie return all lines that DON'T match the terms / combinator.
+  #[serde(default)]
+  pub invert: bool,
+}
+
+impl LogSearch {
+  /// Whether the line passes the filter.
+  /// Terms are matched as plain substrings.
+  pub fn matches(&self, line: &str) -> bool {
+    if self.terms.is_empty() {
+      return true;
+    }
+    let matches = match self.combinator {
+      SearchCombinator::Or => {
+        self.terms.iter().any(|term| line.contains(term.as_str()))
+      }
+      SearchCombinator::And => {
+        self.terms.iter().all(|term| line.contains(term.as_str()))
+      }
+    };
+    matches != self.invert
+  }
+}
+
+/// Sent by Core as Text for each line of a followed log.
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogLine {
+  /// Whether the line was written to stderr.
+  pub stderr: bool,
+  /// The line contents, without trailing newline.
+  pub line: String,
+}
+
 #[derive(Debug, Clone)]
 pub enum UpdateWsMessage {
   Update(UpdateListItem),
@@ -4,6 +4,8 @@ export type AuthResponses = {
   GetLoginOptions: Types.GetLoginOptionsResponse;
   CreateLocalUser: Types.CreateLocalUserResponse;
   LoginLocalUser: Types.LoginLocalUserResponse;
+  LoginTotp: Types.LoginTotpResponse;
+  LoginLdapUser: Types.LoginLdapUserResponse;
   ExchangeForJwt: Types.ExchangeForJwtResponse;
   GetUser: Types.GetUserResponse;
 };
@@ -13,6 +15,9 @@ export type UserResponses = {
   SetLastSeenUpdate: Types.SetLastSeenUpdateResponse;
   CreateApiKey: Types.CreateApiKeyResponse;
   DeleteApiKey: Types.DeleteApiKeyResponse;
+  BeginTotpEnrollment: Types.BeginTotpEnrollmentResponse;
+  ConfirmTotpEnrollment: Types.ConfirmTotpEnrollmentResponse;
+  DisableTotp: Types.DisableTotpResponse;
 };
 
 export type ReadResponses = {
@@ -81,6 +86,7 @@ export type ReadResponses = {
   GetDeployment: Types.GetDeploymentResponse;
   GetDeploymentContainer: Types.GetDeploymentContainerResponse;
   GetDeploymentActionState: Types.GetDeploymentActionStateResponse;
+  ListDeploymentHistory: Types.ListDeploymentHistoryResponse;
   GetDeploymentStats: Types.GetDeploymentStatsResponse;
   GetDeploymentLog: Types.GetDeploymentLogResponse;
   SearchDeploymentLog: Types.SearchDeploymentLogResponse;
@@ -165,6 +171,10 @@ export type ReadResponses = {
   GetVariable: Types.GetVariableResponse;
   ListVariables: Types.ListVariablesResponse;
 
+  // ==== SILENCE ====
+  GetSilence: Types.GetSilenceResponse;
+  ListSilences: Types.ListSilencesResponse;
+
   // ==== PROVIDER ====
   GetGitProviderAccount: Types.GetGitProviderAccountResponse;
   ListGitProviderAccounts: Types.ListGitProviderAccountsResponse;
@@ -190,6 +200,7 @@ export type WriteResponses = {
   // ==== PERMISSIONS ====
   UpdateUserBasePermissions: Types.UpdateUserBasePermissionsResponse;
   UpdatePermissionOnResourceType: Types.UpdatePermissionOnResourceTypeResponse;
+  UpdatePermissionOnTag: Types.UpdatePermissionOnTagResponse;
   UpdatePermissionOnTarget: Types.UpdatePermissionOnTargetResponse;
 
   // ==== DESCRIPTION ====
@@ -284,6 +295,11 @@ export type WriteResponses = {
   UpdateVariableIsSecret: Types.UpdateVariableIsSecretResponse;
   DeleteVariable: Types.DeleteVariableResponse;
 
+  // ==== SILENCE ====
+  CreateSilence: Types.CreateSilenceResponse;
+  UpdateSilence: Types.UpdateSilenceResponse;
+  DeleteSilence: Types.DeleteSilenceResponse;
+
   // ==== PROVIDERS ====
   CreateGitProviderAccount: Types.CreateGitProviderAccountResponse;
   UpdateGitProviderAccount: Types.UpdateGitProviderAccountResponse;
@@ -319,6 +335,7 @@ export type ExecuteResponses = {
 
   // ==== DEPLOYMENT ====
   Deploy: Types.Update;
+  RollbackDeployment: Types.Update;
   StartDeployment: Types.Update;
   RestartDeployment: Types.Update;
   PauseDeployment: Types.Update;
@@ -11,8 +11,11 @@ export interface JwtResponse {
 /** Response for [CreateLocalUser]. */
 export type CreateLocalUserResponse = JwtResponse;
 
-/** The response for [LoginLocalUser] */
-export type LoginLocalUserResponse = JwtResponse;
+/** The response for [LoginTotp] */
+export type LoginTotpResponse = JwtResponse;
+
+/** The response for [LoginLdapUser] */
+export type LoginLdapUserResponse = JwtResponse;
 
 /** Response for [ExchangeForJwt]. */
 export type ExchangeForJwtResponse = JwtResponse;
@@ -27,6 +30,20 @@ export type UserConfig =
 	/** User that logs in with username / password */
 	| { type: "Local", data: {
 	password: string;
+	/** Whether the user has enabled two-factor authentication (TOTP). */
+	totp_enabled?: boolean;
+	/**
+	 * The base32 encoded TOTP secret.
+	 * Set during enrollment, before TOTP is enabled.
+	 */
+	totp_secret?: string;
+	/** Hashes of the unused TOTP recovery codes. */
+	totp_recovery_codes?: string[];
+	/**
+	 * The time step of the last accepted TOTP code.
+	 * Codes at or before this step are rejected, so they can't be replayed.
+	 */
+	totp_last_step?: I64;
 }}
 	/** User that logs in via Google Oauth */
 	| { type: "Google", data: {
@@ -37,6 +54,22 @@ export type UserConfig =
 	| { type: "Github", data: {
 	github_id: string;
 	avatar: string;
+}}
+	/**
+	 * User that logs in via an OpenID Connect provider.
+	 * The `provider` is the issuer url, and `oidc_id` is the `sub` claim.
+	 */
+	| { type: "Oidc", data: {
+	provider: string;
+	oidc_id: string;
+	avatar: string;
+}}
+	/**
+	 * User that logs in via LDAP / Active Directory.
+	 * The `dn` is the user's directory entry.
+	 */
+	| { type: "Ldap", data: {
+	dn: string;
 }}
 	/** Non-human managed user, can have it's own permissions / api keys */
 	| { type: "Service", data: {
@@ -82,6 +115,11 @@ export interface User {
 	recents?: Record<ResourceTarget["type"], string[]>;
 	/** Give the user elevated permissions on all resources of a certain type */
 	all?: Record<ResourceTarget["type"], PermissionLevel>;
+	/**
+	 * Give the user elevated permissions on all resources with a certain tag.
+	 * Maps tag id to the permission level.
+	 */
+	tags?: Record<string, PermissionLevel>;
 	updated_at?: I64;
 }
 
@@ -218,6 +256,23 @@ export type AlertData =
 	name: string;
 	/** The version that failed to build */
 	version: Version;
+}}
+	/** A build's image scan found more vulnerabilities than allowed */
+	| { type: "BuildVulnerabilities", data: {
+	/** The id of the build */
+	id: string;
+	/** The name of the build */
+	name: string;
+	/** The version which was scanned */
+	version: Version;
+	/** The scanned image */
+	image: string;
+	/** The configured `scan_alert_severity` */
+	severity: VulnerabilitySeverity;
+	/** The number of vulnerabilities at or above `severity` */
+	count: U64;
+	/** The configured `scan_alert_threshold` */
+	threshold: U64;
 }}
 	/** A repo has failed */
 	| { type: "RepoBuildFailed", data: {
@@ -247,6 +302,11 @@ export interface Alert {
 	data: AlertData;
 	/** The timestamp of alert resolution */
 	resolved_ts?: I64;
+	/**
+	 * Whether the alert matched an active [Silence][super::silence::Silence].
+	 * Silenced alerts are recorded, but not sent to any alerters.
+	 */
+	silenced?: boolean;
 }
 
 export type GetAlertResponse = Alert;
@@ -284,7 +344,17 @@ export type AlerterEndpoint =
 	/** Send alert serialized to JSON to an http endpoint. */
 	| { type: "Custom", params: CustomAlerterEndpoint }
 	/** Send alert to a slack app */
-	| { type: "Slack", params: SlackAlerterEndpoint };
+	| { type: "Slack", params: SlackAlerterEndpoint }
+	/** Send alert to a discord webhook */
+	| { type: "Discord", params: DiscordAlerterEndpoint }
+	/** Send alert to a microsoft teams webhook */
+	| { type: "Teams", params: TeamsAlerterEndpoint }
+	/** Send alert to an ntfy topic */
+	| { type: "Ntfy", params: NtfyAlerterEndpoint }
+	/** Send alert to a gotify server */
+	| { type: "Gotify", params: GotifyAlerterEndpoint }
+	/** Send alert as an email over SMTP */
+	| { type: "Email", params: EmailAlerterEndpoint };
 
 export interface AlerterConfig {
 	/** Whether the alerter is enabled */
@@ -329,7 +399,7 @@ export interface ResourceListItem<Info> {
 export interface AlerterListItemInfo {
 	/** Whether alerter is enabled for sending alerts */
 	enabled: boolean;
-	/** The type of the alerter, eg. `Slack`, `Custom` */
+	/** The type of the alerter, eg. `Slack`, `Discord`, `Custom` */
 	endpoint_type: AlerterEndpoint["type"];
 }
 
@@ -363,6 +433,27 @@ export type ImageRegistry =
 	 */
 	| { type: "AwsEcr", params: string };
 
+/** The tool used to scan built images for vulnerabilities. */
+export enum ImageScanner {
+	/** Don't scan the image */
+	None = "None",
+	/** Scan with `trivy image`. See `<https://trivy.dev>`. */
+	Trivy = "Trivy",
+	/** Scan with `grype`. See `<https://github.com/anchore/grype>`. */
+	Grype = "Grype",
+}
+
+/** Severity of a vulnerability, as reported by the scanner. */
+export enum VulnerabilitySeverity {
+	Unknown = "Unknown",
+	Low = "Low",
+	Medium = "Medium",
+	High = "High",
+	Critical = "Critical",
+}
+
+export type U64 = number;
+
 export interface EnvironmentVar {
 	variable: string;
 	value: string;
@@ -444,6 +535,79 @@ export interface BuildConfig {
 	skip_secret_interp?: boolean;
 	/** Whether to use buildx to build (eg `docker buildx build ...`) */
 	use_buildx?: boolean;
+	/**
+	 * The platforms to build the image for, eg `linux/amd64`, `linux/arm64`.
+	 * When set, the image is built using `docker buildx build --platform ...`,
+	 * and all platforms are pushed together under a multi-arch manifest.
+	 * 
+	 * If empty, builds for the platform of the builder server.
+	 * Building for multiple platforms requires an image registry to push to.
+	 * 
+	 * Note. Building for a platform different than the builder server's
+	 * requires QEMU emulation to be installed on the server.
+	 */
+	platforms?: string[];
+	/**
+	 * The buildx builder instance used to build for `platforms`, or export `cache_to`.
+	 * If it doesn't exist on the builder server, it is created
+	 * using the `docker-container` driver, which supports multi-platform builds.
+	 * May only contain letters, numbers, `_` and `-`.
+	 * Default: `komodo`
+	 */
+	buildx_builder: string;
+	/**
+	 * BuildKit cache sources, passed to `--cache-from`.
+	 * Eg. `type=registry,ref=ghcr.io/org/app:buildcache`
+	 * or `type=local,src=/var/cache/komodo/app`.
+	 */
+	cache_from?: string[];
+	/**
+	 * BuildKit cache destinations, passed to `--cache-to`.
+	 * Eg. `type=registry,ref=ghcr.io/org/app:buildcache,mode=max`
+	 * or `type=local,dest=/var/cache/komodo/app`.
+	 * 
+	 * Exporting the cache requires building with the buildx builder instance
+	 * (see `buildx_builder`), which is used automatically when this is set.
+	 */
+	cache_to?: string[];
+	/**
+	 * The registry to login to for registry based caches,
+	 * if it is different than the `image_registry`.
+	 */
+	cache_registry?: ImageRegistry;
+	/**
+	 * Scan the image for vulnerabilities after a successful build.
+	 * The scanner must be installed on the builder.
+	 */
+	scanner?: ImageScanner;
+	/**
+	 * Override the command used to run the scanner.
+	 * `[[IMAGE]]` is replaced with the image reference.
+	 * It must print the scanner's json report to stdout.
+	 * 
+	 * Default:
+	 * - Trivy: `trivy image --quiet --format json [[IMAGE]]`
+	 * - Grype: `grype [[IMAGE]] --output json`
+	 */
+	scan_command?: string;
+	/**
+	 * Count the vulnerabilities at or above this severity
+	 * towards the `scan_alert_threshold`.
+	 * Default: `High`
+	 */
+	scan_alert_severity: VulnerabilitySeverity;
+	/**
+	 * Send a `BuildVulnerabilities` alert when the scan finds more than
+	 * this many vulnerabilities at or above `scan_alert_severity`.
+	 * Default: `0`
+	 */
+	scan_alert_threshold?: U64;
+	/**
+	 * Attach an SBOM attestation to the pushed image (`--sbom=true`).
+	 * This builds with the buildx builder instance (see `buildx_builder`),
+	 * and requires an image registry.
+	 */
+	sbom?: boolean;
 	/** Any extra docker cli arguments to be included in the build command */
 	extra_args?: string[];
 	/**
@@ -469,6 +633,31 @@ export interface BuildConfig {
 	labels?: EnvironmentVar[] | string;
 }
 
+/** The result of scanning a built image for vulnerabilities. */
+export interface VulnerabilityScan {
+	/** Unix timestamp in milliseconds of the scan */
+	ts: I64;
+	/** The scanner used */
+	scanner: ImageScanner;
+	/** The image reference which was scanned */
+	image: string;
+	/**
+	 * The image reference holding the SBOM attestation, or null.
+	 * Inspect it with `docker buildx imagetools inspect <sbom> --format '{{ json .SBOM }}'`.
+	 */
+	sbom?: string;
+	/** The number of critical vulnerabilities */
+	critical: U64;
+	/** The number of high vulnerabilities */
+	high: U64;
+	/** The number of medium vulnerabilities */
+	medium: U64;
+	/** The number of low vulnerabilities */
+	low: U64;
+	/** The number of vulnerabilities with unknown / negligible severity */
+	unknown: U64;
+}
+
 export interface BuildInfo {
 	last_built_at: I64;
 	/** Latest built short commit hash, or null. */
@@ -479,6 +668,8 @@ export interface BuildInfo {
 	latest_hash?: string;
 	/** Latest remote commit message, or null */
 	latest_message?: string;
+	/** The result of the latest vulnerability scan, or null. */
+	latest_scan?: VulnerabilityScan;
 }
 
 export type Build = Resource<BuildConfig, BuildInfo>;
@@ -588,6 +779,24 @@ export enum RestartMode {
 	UnlessStopped = "unless-stopped",
 }
 
+/** How the running container is replaced on redeploy. */
+export enum DeploymentStrategy {
+	/** Stop and remove the old container, then start the new one. */
+	Recreate = "Recreate",
+	/**
+	 * Start the new container alongside the old one, wait for
+	 * it to pass the health gate, move the network alias over,
+	 * and only then remove the old container.
+	 * 
+	 * The containers run at the same time, so the deployment
+	 * can't use the `host` network or publish ports.
+	 * 
+	 * With `health_gate` set to `None`, the new container is
+	 * promoted as soon as it is running, whether or not it is ready.
+	 */
+	BlueGreen = "BlueGreen",
+}
+
 export enum TerminationSignal {
 	SigHup = "SIGHUP",
 	SigInt = "SIGINT",
@@ -607,6 +816,18 @@ export interface Conversion {
 	container: string;
 }
 
+/** How to check the container is healthy after deploying. */
+export enum HealthGate {
+	/** Don't wait for the container to become healthy. */
+	None = "None",
+	/** Wait for the status of the image / container HEALTHCHECK to be `healthy`. */
+	Docker = "Docker",
+	/** Wait for a 2xx response to a GET request to the target url. */
+	Http = "Http",
+	/** Wait for a connection to the target address to be accepted. */
+	Tcp = "Tcp",
+}
+
 export interface DeploymentConfig {
 	/** The id of server the deployment is deployed on. */
 	server_id?: string;
@@ -639,6 +860,8 @@ export interface DeploymentConfig {
 	network: string;
 	/** The restart mode given to the container. */
 	restart?: RestartMode;
+	/** How to replace the running container on redeploy. */
+	strategy?: DeploymentStrategy;
 	/**
 	 * This is interpolated at the end of the `docker run` command,
 	 * which means they are either passed to the containers inner process,
@@ -675,6 +898,27 @@ export interface DeploymentConfig {
 	environment?: EnvironmentVar[] | string;
 	/** The docker labels given to the container. */
 	labels?: EnvironmentVar[] | string;
+	/**
+	 * Wait for the container to pass a health check after `docker run`.
+	 * If it doesn't pass before the timeout, the deploy is marked as failed.
+	 */
+	health_gate?: HealthGate;
+	/**
+	 * The target probed from the server by the Http / Tcp health gates.
+	 * - Http: A url, eg. `http://localhost:8080/health`. Passes on any 2xx response.
+	 * - Tcp: An address, eg. `localhost:5432`. Passes once a connection is accepted.
+	 */
+	health_gate_target?: string;
+	/**
+	 * The seconds to wait for the health gate to pass.
+	 * Default: 60
+	 */
+	health_gate_timeout: number;
+	/**
+	 * Keep the previous container (stopped) until the health gate passes,
+	 * and restore it if the deploy fails.
+	 */
+	health_gate_restore?: boolean;
 }
 
 export type Deployment = Resource<DeploymentConfig, undefined>;
@@ -756,6 +1000,14 @@ export type GetDeploymentStatsResponse = ContainerStats;
 
 export interface DeploymentActionState {
 	deploying: boolean;
+	/** BlueGreen: Starting the new container next to the old one. */
+	deploying_next: boolean;
+	/** BlueGreen: Waiting for the new container to pass the health gate. */
+	health_checking: boolean;
+	/** BlueGreen: Moving the network alias to the new container. */
+	switching: boolean;
+	/** BlueGreen: Removing the old container. */
+	removing_old: boolean;
 	starting: boolean;
 	restarting: boolean;
 	pausing: boolean;
@@ -767,8 +1019,60 @@ export interface DeploymentActionState {
 
 export type GetDeploymentActionStateResponse = DeploymentActionState;
 
+/**
+ * A successful deploy of a deployment, recorded so the deployment
+ * can be rolled back to a previous known-good image and config.
+ */
+export interface DeploymentRecord {
+	/**
+	 * The Mongo ID of the record.
+	 * This field is de/serialized from/to JSON as
+	 * `{ "_id": { "$oid": "..." }, ...(rest of serialized DeploymentRecord) }`
+	 */
+	_id?: MongoId;
+	/** The id of the deployment which was deployed. */
+	deployment_id: string;
+	/** Unix timestamp in milliseconds of the deploy. */
+	ts: I64;
+	/** The full image which was deployed, eg. `mbecker2020/komodo:1.2.3`. */
+	image?: string;
+	/**
+	 * The version of the attached Build which was deployed,
+	 * if the deployment uses a Build.
+	 */
+	version?: Version;
+	/**
+	 * Sha256 hash (hex) of the deployed config.
+	 * Records with the same hash deployed the same image and config.
+	 */
+	config_hash?: string;
+	/**
+	 * The deployed config, before variable / secret interpolation.
+	 * Attached Builds are pinned to the deployed version.
+	 */
+	config?: DeploymentConfig;
+	/** The id of the Update for the deploy. */
+	update_id?: string;
+	/**
+	 * Whether the deployment was later rolled back past this record.
+	 * These records are skipped when finding the rollback target.
+	 */
+	rolled_back?: boolean;
+}
+
+export type ListDeploymentHistoryResponse = DeploymentRecord[];
+
 export type ListCommonDeploymentExtraArgsResponse = string[];
 
+/** The type of git provider. */
+export enum GitProviderType {
+	/** Github webhooks are managed using the Github webhook app. */
+	Github = "Github",
+	Gitlab = "Gitlab",
+	/** Also works for Forgejo. */
+	Gitea = "Gitea",
+}
+
 export interface ProviderAccount {
 	/** The account username. Required. */
 	username: string;
@@ -781,6 +1085,11 @@ export interface GitProvider {
 	domain: string;
 	/** Whether to use https. Default: true. */
 	https: boolean;
+	/**
+	 * The type of git provider, used to manage repo webhooks
+	 * on Gitlab / Gitea. Default: `Github`.
+	 */
+	provider_type?: GitProviderType;
 	/** The account username. Required. */
 	accounts: ProviderAccount[];
 }
@@ -829,6 +1138,50 @@ export type GetPermissionLevelResponse = PermissionLevel;
 
 export type ListUserTargetPermissionsResponse = Permission[];
 
+/** Run the stage depending on the result of the previous stages. */
+export enum StageRunOn {
+	/** Only run if all the previous stages succeeded. */
+	Success = "Success",
+	/**
+	 * Only run if a previous stage failed.
+	 * The procedure will still be marked as failed.
+	 */
+	Failure = "Failure",
+	/** Run whether or not the previous stages succeeded. */
+	Always = "Always",
+}
+
+/** A condition evaluated right before the stage runs. */
+export type StageCondition = 
+	/** No condition, always run. */
+	| { type: "None", params?: undefined }
+	/**
+	 * Only run if an earlier stage of this procedure run
+	 * built it successfully.
+	 */
+	| { type: "BuildNewVersion", params: {
+	/** Id or name */
+	build: string;
+}}
+	/** Only run if the server has the given state. */
+	| { type: "ServerState", params: {
+	/** Id or name */
+	server: string;
+	state: ServerState;
+}}
+	/** Only run if the deployment has the given state. */
+	| { type: "DeploymentState", params: {
+	/** Id or name */
+	deployment: string;
+	state: DeploymentState;
+}}
+	/** Only run if the stack has the given state. */
+	| { type: "StackState", params: {
+	/** Id or name */
+	stack: string;
+	state: StackState;
+}};
+
 /** A wrapper for all Komodo exections. */
 export type Execution = 
 	/** The "null" execution. Does nothing. */
@@ -837,6 +1190,7 @@ export type Execution =
 	| { type: "RunBuild", params: RunBuild }
 	| { type: "CancelBuild", params: CancelBuild }
 	| { type: "Deploy", params: Deploy }
+	| { type: "RollbackDeployment", params: RollbackDeployment }
 	| { type: "StartDeployment", params: StartDeployment }
 	| { type: "RestartDeployment", params: RestartDeployment }
 	| { type: "PauseDeployment", params: PauseDeployment }
@@ -884,6 +1238,26 @@ export interface EnabledExecution {
 	execution: Execution;
 	/** Whether the execution is enabled to run in the procedure. */
 	enabled: boolean;
+	/**
+	 * The number of times to retry the execution if it fails.
+	 * Default: `0` (no retries)
+	 */
+	retries?: I64;
+	/** The time to wait between retries, in milliseconds. */
+	retry_delay_ms?: I64;
+	/**
+	 * Fail the attempt if it doesn't finish within this many milliseconds.
+	 * If `0`, the attempt has no timeout.
+	 * Builds and repo builds are cancelled on timeout, other
+	 * executions keep running, so a timed out attempt is never retried.
+	 */
+	timeout_ms?: I64;
+	/**
+	 * If the execution still fails after all retries,
+	 * log the error and let the procedure continue
+	 * instead of failing the procedure.
+	 */
+	continue_on_error?: boolean;
 }
 
 /** A single stage of a procedure. Runs a list of executions in parallel. */
@@ -892,10 +1266,28 @@ export interface ProcedureStage {
 	name: string;
 	/** Whether the stage should be run as part of the procedure. */
 	enabled: boolean;
+	/**
+	 * Run the stage depending on the result of the previous stages.
+	 * Default: `Success`
+	 */
+	run_on?: StageRunOn;
+	/**
+	 * An additional condition evaluated right before the stage runs.
+	 * If it isn't met, the stage is skipped.
+	 */
+	condition?: StageCondition;
 	/** The executions in the stage */
 	executions?: EnabledExecution[];
 }
 
+/** How the procedure schedule is specified. */
+export enum ScheduleFormat {
+	/** A cron expression, eg. `0 0 3 * * *` */
+	Cron = "Cron",
+	/** A fixed interval, eg. `1-hr` */
+	Interval = "Interval",
+}
+
 /** Config for the [Procedure] */
 export interface ProcedureConfig {
 	/** The stages to be run by the procedure. */
@@ -907,6 +1299,28 @@ export interface ProcedureConfig {
 	 * If its an empty string, use the default secret from the config.
 	 */
 	webhook_secret?: string;
+	/**
+	 * Choose whether to specify the schedule as a cron expression,
+	 * or as a fixed interval.
+	 */
+	schedule_format?: ScheduleFormat;
+	/**
+	 * Optionally run the procedure on a schedule.
+	 * If empty, the procedure is not scheduled.
+	 * 
+	 * - `Cron`: A cron expression, eg. `0 0 3 * * *` (every day at 03:00).
+	 * Supports the 5 field (minute precision) and 6 field (second precision) formats.
+	 * - `Interval`: A timelength, eg. `1-hr`, `1-day`.
+	 * Runs are aligned to the unix epoch, eg. `1-hr` runs at the top of every hour.
+	 */
+	schedule?: string;
+	/** Whether the schedule is enabled. */
+	schedule_enabled: boolean;
+	/**
+	 * The IANA timezone to evaluate the cron schedule in,
+	 * eg. `America/New_York`. If empty, uses UTC.
+	 */
+	schedule_timezone?: string;
 }
 
 /**
@@ -933,6 +1347,13 @@ export interface ProcedureListItemInfo {
 	stages: I64;
 	/** Reflect whether last run successful / currently running. */
 	state: ProcedureState;
+	/**
+	 * If the procedure has a schedule configured,
+	 * the unix timestamp in milliseconds of the next scheduled run.
+	 */
+	next_scheduled_run?: I64;
+	/** If the configured schedule is invalid, the error. */
+	schedule_error?: string;
 }
 
 export type ProcedureListItem = ResourceListItem<ProcedureListItemInfo>;
@@ -974,6 +1395,11 @@ export interface GitProviderAccount {
 	 * If the database / host can be accessed this is insecure.
 	 */
 	token?: string;
+	/**
+	 * The type of git provider. For Gitlab and Gitea,
+	 * the token is also used to manage repo webhooks.
+	 */
+	provider_type?: GitProviderType;
 }
 
 export type GetGitProviderAccountResponse = GitProviderAccount;
@@ -2019,8 +2445,6 @@ export interface VolumeListItem {
 
 export type ListDockerVolumesResponse = VolumeListItem[];
 
-export type U64 = number;
-
 /** The version number of the object such as node, service, etc. This is needed to avoid conflicting writes. The client must send the version number along with the modified specification when updating these objects.  This approach ensures safe concurrency and determinism in that the change on the object may not be applied if the version number has changed from the last read. In other words, if two update requests specify the same base version, only one of the requests can succeed. As a result, two separate update requests that happen at the same time will not unintentionally overwrite each other. */
 export interface ObjectVersion {
 	Index?: U64;
@@ -2303,6 +2727,54 @@ export type ListServerTemplatesResponse = ServerTemplateListItem[];
 
 export type ListFullServerTemplatesResponse = ServerTemplate[];
 
+/**
+ * A window of time during which matching alerts are silenced,
+ * eg. for planned maintenance.
+ * 
+ * Silenced alerts are still recorded with `silenced: true`,
+ * but are not sent to any alerters.
+ * 
+ * An alert matches the silence if it matches **all** of the
+ * configured filters. Empty filters match every alert.
+ */
+export interface Silence {
+	/**
+	 * The Mongo ID of the silence.
+	 * This field is de/serialized from/to JSON as
+	 * `{ "_id": { "$oid": "..." }, ...(rest of serialized Silence) }`
+	 */
+	_id?: MongoId;
+	/** Why the alerts are silenced, eg. "Database migration". */
+	reason?: string;
+	/** Unix timestamp in milliseconds the silence begins. */
+	start_ts: I64;
+	/** Unix timestamp in milliseconds the silence ends. */
+	end_ts: I64;
+	/**
+	 * Only silence alerts on these resources.
+	 * If empty, matches alerts on all resources.
+	 */
+	resources?: ResourceTarget[];
+	/**
+	 * Only silence alerts on resources with any of these tag ids.
+	 * If empty, matches alerts on all resources.
+	 */
+	tags?: string[];
+	/**
+	 * Only silence these alert types.
+	 * If empty, matches all alert types.
+	 */
+	alert_types?: AlertData["type"][];
+	/** The id of the user who created the silence. */
+	created_by?: string;
+	/** Unix timestamp in milliseconds the silence was created. */
+	created_at?: I64;
+}
+
+export type GetSilenceResponse = Silence;
+
+export type ListSilencesResponse = Silence[];
+
 /** The compose file configuration. */
 export interface StackConfig {
 	/** The server to deploy the stack on. */
@@ -2775,6 +3247,7 @@ export enum Operation {
 	UpdateDeployment = "UpdateDeployment",
 	DeleteDeployment = "DeleteDeployment",
 	Deploy = "Deploy",
+	RollbackDeployment = "RollbackDeployment",
 	StartDeployment = "StartDeployment",
 	RestartDeployment = "RestartDeployment",
 	PauseDeployment = "PauseDeployment",
@@ -2782,6 +3255,7 @@ export enum Operation {
 	StopDeployment = "StopDeployment",
 	DestroyDeployment = "DestroyDeployment",
 	RenameDeployment = "RenameDeployment",
+	ExecDeployment = "ExecDeployment",
 	CreateRepo = "CreateRepo",
 	UpdateRepo = "UpdateRepo",
 	DeleteRepo = "DeleteRepo",
@@ -2821,9 +3295,13 @@ export enum Operation {
 	PauseStackService = "PauseStackService",
 	UnpauseStackService = "UnpauseStackService",
 	StopStackService = "StopStackService",
+	ExecStackService = "ExecStackService",
 	CreateVariable = "CreateVariable",
 	UpdateVariableValue = "UpdateVariableValue",
 	DeleteVariable = "DeleteVariable",
+	CreateSilence = "CreateSilence",
+	UpdateSilence = "UpdateSilence",
+	DeleteSilence = "DeleteSilence",
 	CreateGitProviderAccount = "CreateGitProviderAccount",
 	UpdateGitProviderAccount = "UpdateGitProviderAccount",
 	DeleteGitProviderAccount = "DeleteGitProviderAccount",
@@ -2888,6 +3366,46 @@ export interface Update {
 
 export type GetUpdateResponse = Update;
 
+/** The request families an [ApiKey] can be scoped to. */
+export enum ApiKeyRequestType {
+	/** The `/read` api */
+	Read = "Read",
+	/** The `/execute` api */
+	Execute = "Execute",
+	/** The `/write` api */
+	Write = "Write",
+}
+
+/**
+ * Restrictions on what an [ApiKey] can be used for.
+ * These only ever reduce the permissions of the owning user.
+ */
+export interface ApiKeyScope {
+	/**
+	 * The request families the key may call.
+	 * Empty means all families are allowed.
+	 * 
+	 * Note. Scoped keys can never call the `/user` api,
+	 * so they cannot be used to create new api keys.
+	 */
+	requests?: ApiKeyRequestType[];
+	/**
+	 * The resources the key may access.
+	 * If both this and `tags` are empty, all resources are allowed.
+	 */
+	resources?: ResourceTarget[];
+	/**
+	 * Tag ids. Resources with any of these tags may be accessed
+	 * in addition to those in `resources`.
+	 */
+	tags?: string[];
+	/**
+	 * The maximum permission level the key has on any resource.
+	 * Default is Write, ie no restriction.
+	 */
+	max_permission: PermissionLevel;
+}
+
 /** An api key used to authenticate requests via request headers. */
 export interface ApiKey {
 	/** Unique key associated with secret */
@@ -2902,6 +3420,12 @@ export interface ApiKey {
 	created_at: I64;
 	/** Expiry of key, or 0 if never expires */
 	expires: I64;
+	/**
+	 * Restricts what the api key can be used for.
+	 * The default scope is unrestricted, giving the key
+	 * all of the owning user's permissions.
+	 */
+	scope?: ApiKeyScope;
 }
 
 export type ListApiKeysResponse = ApiKey[];
@@ -2933,6 +3457,11 @@ export interface UserGroup {
 	users: string[];
 	/** Give the user group elevated permissions on all resources of a certain type */
 	all?: Record<ResourceTarget["type"], PermissionLevel>;
+	/**
+	 * Give the user group elevated permissions on all resources with a certain tag.
+	 * Maps tag id to the permission level.
+	 */
+	tags?: Record<string, PermissionLevel>;
 	/** Unix time (ms) when user group last updated */
 	updated_at?: I64;
 }
@@ -2980,6 +3509,8 @@ export type SetLastSeenUpdateResponse = NoData;
 
 export type DeleteApiKeyResponse = NoData;
 
+export type DisableTotpResponse = NoData;
+
 /** Response for [CreateApiKey]. */
 export interface CreateApiKeyResponse {
 	/** X-API-KEY */
@@ -3007,6 +3538,8 @@ export type UpdatePermissionOnTargetResponse = NoData;
 
 export type UpdatePermissionOnResourceTypeResponse = NoData;
 
+export type UpdatePermissionOnTagResponse = NoData;
+
 export type UpdateUserBasePermissionsResponse = NoData;
 
 export type CreateProcedureResponse = Procedure;
@@ -3033,6 +3566,12 @@ export type CreateRepoWebhookResponse = NoData;
 
 export type DeleteRepoWebhookResponse = NoData;
 
+export type CreateSilenceResponse = Silence;
+
+export type UpdateSilenceResponse = Silence;
+
+export type DeleteSilenceResponse = Silence;
+
 export type CreateStackWebhookResponse = NoData;
 
 export type DeleteStackWebhookResponse = NoData;
@@ -3211,8 +3750,17 @@ export interface GetLoginOptionsResponse {
 	github: boolean;
 	/** Whether google login is enabled. */
 	google: boolean;
+	/** Whether OpenID Connect login is enabled. */
+	oidc: boolean;
+	/** Whether LDAP login is enabled. */
+	ldap: boolean;
 	/** Whether user registration (Sign Up) has been disabled */
 	registration_disabled: boolean;
+	/**
+	 * Whether admins which log in with local auth
+	 * must enable two-factor authentication (TOTP).
+	 */
+	require_admin_totp: boolean;
 }
 
 /**
@@ -3245,6 +3793,46 @@ export interface LoginLocalUser {
 	password: string;
 }
 
+/** The response for [LoginLocalUser] */
+export interface LoginLocalUserResponse {
+	/**
+	 * A token the user can use to authenticate their requests.
+	 * Empty if the user has two-factor authentication enabled.
+	 */
+	jwt?: string;
+	/**
+	 * Given if the user has two-factor authentication enabled.
+	 * Pass it to [LoginTotp] along with a code to finish the login.
+	 */
+	totp_token?: string;
+}
+
+/**
+ * Finish logging in a local user with two-factor authentication enabled.
+ * Response: [LoginTotpResponse].
+ */
+export interface LoginTotp {
+	/** The `totp_token` from [LoginLocalUserResponse]. */
+	token: string;
+	/** The current TOTP code, or an unused recovery code. */
+	code: string;
+}
+
+/**
+ * Login as an LDAP / Active Directory user.
+ * The credentials are verified by binding to the directory.
+ * The Komodo user is created on first login.
+ * Response: [LoginLdapUserResponse].
+ * 
+ * Note. This method is only available if the core api has `ldap` enabled.
+ */
+export interface LoginLdapUser {
+	/** The user's directory username */
+	username: string;
+	/** The user's directory password */
+	password: string;
+}
+
 /**
  * Exchange a single use exchange token (safe for transport in url query)
  * for a jwt.
@@ -3309,6 +3897,26 @@ export interface Deploy {
 	stop_time?: number;
 }
 
+/**
+ * Rolls the deployment back to a previous known-good deploy.
+ * Response: [Update].
+ * 
+ * 1. Finds the target record in the deployment history.
+ * By default, this is the most recent successful deploy with a
+ * different image / config than the latest one.
+ * 2. Restores the deployment config from the record,
+ * with any attached Build pinned to the recorded version.
+ * 3. Runs [Deploy] with the restored config.
+ * 
+ * Requires Write permissions on the deployment to restore the config.
+ */
+export interface RollbackDeployment {
+	/** Name or id */
+	deployment: string;
+	/** Optionally roll back to a specific deployment history record id. */
+	record?: string;
+}
+
 /**
  * Starts the container for the target deployment. Response: [Update]
  * 
@@ -3388,6 +3996,12 @@ export interface Sleep {
 export interface RunProcedure {
 	/** Id or name */
 	procedure: string;
+	/**
+	 * Arguments to interpolate into the procedure executions.
+	 * An execution can reference an argument with `[[ARG_NAME]]`,
+	 * eg. a Deploy execution targeting `[[env]]-api`.
+	 */
+	args?: Record<string, string>;
 }
 
 /**
@@ -4088,6 +4702,21 @@ export interface GetDeploymentActionState {
 	deployment: string;
 }
 
+/**
+ * List the recorded successful deploys of the deployment,
+ * most recent first. Response: [ListDeploymentHistoryResponse].
+ */
+export interface ListDeploymentHistory {
+	/** Id or name */
+	deployment: string;
+	/**
+	 * The maximum number of records to return.
+	 * Default: 20.
+	 * Max: 100.
+	 */
+	limit: U64;
+}
+
 /**
  * Gets a summary of data relating to all deployments.
  * Response: [GetDeploymentsSummaryResponse].
@@ -4718,6 +5347,24 @@ export interface GetServerTemplatesSummaryResponse {
 	total: number;
 }
 
+/**
+ * Get a specific silence by id.
+ * Response: [Silence].
+ */
+export interface GetSilence {
+	/** The id of the silence. */
+	id: string;
+}
+
+/**
+ * List the configured silences, sorted by start time (newest first).
+ * Response: [ListSilencesResponse].
+ */
+export interface ListSilences {
+	/** Only include the silences which are currently in effect. */
+	active?: boolean;
+}
+
 /** Get a specific stack. Response: [Stack]. */
 export interface GetStack {
 	/** Id or name */
@@ -5151,6 +5798,12 @@ export interface CreateApiKey {
 	 * Default is 0, which means no expiry.
 	 */
 	expires?: I64;
+	/**
+	 * Restrict what the api key can be used for.
+	 * Resources and tags can be given by name or id.
+	 * Default is unrestricted.
+	 */
+	scope?: ApiKeyScope;
 }
 
 /**
@@ -5162,6 +5815,54 @@ export interface DeleteApiKey {
 	key: string;
 }
 
+/**
+ * Begin enrolling the calling (local) user in two-factor authentication.
+ * Generates a new TOTP secret, which is not used until enrollment
+ * is confirmed with [ConfirmTotpEnrollment].
+ * Response: [BeginTotpEnrollmentResponse].
+ */
+export interface BeginTotpEnrollment {
+}
+
+/** Response for [BeginTotpEnrollment]. */
+export interface BeginTotpEnrollmentResponse {
+	/** The base32 encoded secret, for manual entry in an authenticator app. */
+	secret: string;
+	/** The `otpauth://` provisioning uri. */
+	uri: string;
+}
+
+/**
+ * Confirm two-factor authentication enrollment with a code from
+ * the authenticator app, enabling TOTP for the calling user.
+ * Response: [ConfirmTotpEnrollmentResponse].
+ * 
+ * Note. After the response is served, there will be no way
+ * to get the recovery codes later.
+ */
+export interface ConfirmTotpEnrollment {
+	/** The current TOTP code. */
+	code: string;
+}
+
+/** Response for [ConfirmTotpEnrollment]. */
+export interface ConfirmTotpEnrollmentResponse {
+	/**
+	 * Single use codes which can be used in place of a TOTP code,
+	 * eg. if the authenticator device is lost.
+	 */
+	recovery_codes: string[];
+}
+
+/**
+ * Disable two-factor authentication for the calling user.
+ * Response: [NoData].
+ */
+export interface DisableTotp {
+	/** The current TOTP code, or an unused recovery code. */
+	code: string;
+}
+
 /** Create an alerter. Response: [Alerter]. */
 export interface CreateAlerter {
 	/** The name given to newly created alerter. */
@@ -5219,6 +5920,12 @@ export interface CreateApiKeyForServiceUser {
 	 * Default is 0, which means no expiry.
 	 */
 	expires?: I64;
+	/**
+	 * Restrict what the api key can be used for.
+	 * Resources and tags can be given by name or id.
+	 * Default is unrestricted.
+	 */
+	scope?: ApiKeyScope;
 }
 
 /**
@@ -5449,6 +6156,20 @@ export interface UpdatePermissionOnResourceType {
 	permission: PermissionLevel;
 }
 
+/**
+ * **Admin only.** Update a user or user groups permission level
+ * on all resources with a tag.
+ * Response: [NoData].
+ */
+export interface UpdatePermissionOnTag {
+	/** Specify the user or user group. */
+	user_target: UserTarget;
+	/** Id or name of the tag. */
+	tag: string;
+	/** The permission level on resources with the tag. */
+	permission: PermissionLevel;
+}
+
 /**
  * **Admin only.** Update a user's "base" permissions, eg. "enabled".
  * Response: [NoData].
@@ -5753,6 +6474,60 @@ export interface UpdateServerTemplate {
 	config: PartialServerTemplateConfig;
 }
 
+/**
+ * **Admin only.** Create a silence, to stop matching alerts
+ * from being sent between `start_ts` and `end_ts`.
+ * Response: [Silence].
+ */
+export interface CreateSilence {
+	/** Why the alerts are silenced. */
+	reason?: string;
+	/** Unix timestamp in milliseconds the silence begins. */
+	start_ts: I64;
+	/** Unix timestamp in milliseconds the silence ends. */
+	end_ts: I64;
+	/** Only silence alerts on these resources. */
+	resources?: ResourceTarget[];
+	/**
+	 * Only silence alerts on resources with these tags.
+	 * Accepts tag id or name.
+	 */
+	tags?: string[];
+	/** Only silence these alert types. */
+	alert_types?: AlertData["type"][];
+}
+
+/**
+ * **Admin only.** Update a silence. Response: [Silence].
+ * 
+ * Note. To end a silence early, set `end_ts` to the current time.
+ */
+export interface UpdateSilence {
+	/** The id of the silence to update. */
+	id: string;
+	/** Why the alerts are silenced. */
+	reason?: string;
+	/** Unix timestamp in milliseconds the silence begins. */
+	start_ts: I64;
+	/** Unix timestamp in milliseconds the silence ends. */
+	end_ts: I64;
+	/** Only silence alerts on these resources. */
+	resources?: ResourceTarget[];
+	/**
+	 * Only silence alerts on resources with these tags.
+	 * Accepts tag id or name.
+	 */
+	tags?: string[];
+	/** Only silence these alert types. */
+	alert_types?: AlertData["type"][];
+}
+
+/** **Admin only.** Delete a silence. Response: [Silence]. */
+export interface DeleteSilence {
+	/** The id of the silence to delete. */
+	id: string;
+}
+
 /** Create a stack. Response: [Stack]. */
 export interface CreateStack {
 	/** The name given to newly created stack. */
@@ -6081,6 +6856,90 @@ export interface SlackAlerterEndpoint {
 	url: string;
 }
 
+/** Configuration for a discord alerter. */
+export interface DiscordAlerterEndpoint {
+	/** The discord webhook url */
+	url: string;
+}
+
+/** Configuration for a microsoft teams alerter. */
+export interface TeamsAlerterEndpoint {
+	/**
+	 * The teams incoming webhook / workflow url.
+	 * Alerts are posted as adaptive cards.
+	 */
+	url: string;
+}
+
+/** Configuration for an ntfy alerter. */
+export interface NtfyAlerterEndpoint {
+	/**
+	 * The full url of the ntfy topic to publish to,
+	 * eg. `https://ntfy.sh/komodo-alerts`.
+	 */
+	url: string;
+	/**
+	 * Optional access token, if the topic requires authentication.
+	 * Sent as `Authorization: Bearer <token>`.
+	 */
+	token?: string;
+}
+
+/** Configuration for a gotify alerter. */
+export interface GotifyAlerterEndpoint {
+	/** The base url of the gotify server, eg. `https://gotify.example.com` */
+	url: string;
+	/** The gotify application token to send messages with. */
+	token?: string;
+}
+
+/** How the email alerter secures the SMTP connection. */
+export enum SmtpSecurity {
+	/** Plaintext connection. Only use with trusted local relays. */
+	None = "None",
+	/** Upgrade the connection with STARTTLS (usually port 587). */
+	StartTls = "StartTls",
+	/** Implicit TLS (usually port 465). */
+	Tls = "Tls",
+}
+
+/** Configuration for an email alerter. */
+export interface EmailAlerterEndpoint {
+	/** The SMTP server host, eg. `smtp.example.com` */
+	host?: string;
+	/**
+	 * The SMTP server port.
+	 * Default: `587`
+	 */
+	port: number;
+	/**
+	 * How to secure the connection to the SMTP server.
+	 * Default: `StartTls`
+	 */
+	security?: SmtpSecurity;
+	/**
+	 * The username to authenticate with.
+	 * If empty, will not authenticate.
+	 */
+	username?: string;
+	/**
+	 * The password to authenticate with. Must reference a Variable
+	 * or core config secret, eg. `[[SMTP_PASSWORD]]`, which is
+	 * interpolated when the email is sent.
+	 */
+	password?: string;
+	/** The sender address, eg. `Komodo <komodo@example.com>` */
+	from?: string;
+	/** The recipient addresses. */
+	to?: string[];
+	/**
+	 * Instead of sending an email for every alert,
+	 * group all the alerts opened / resolved during each
+	 * monitoring cycle into a single digest email.
+	 */
+	digest?: boolean;
+}
+
 /** Configuration for a standard image registry */
 export interface StandardRegistryConfig {
 	/** Specify the registry provider domain. Default: `docker.io` */
@@ -6438,10 +7297,101 @@ export interface PendingSyncUpdatesDataErr {
 	message: string;
 }
 
+/** Query params for `/ws/terminal/deployment` */
+export interface DeploymentTerminalQuery {
+	/** Id or name */
+	deployment: string;
+	/**
+	 * The shell to run in the container.
+	 * Default: `sh`
+	 */
+	shell?: string;
+}
+
+/** Query params for `/ws/terminal/stack` */
+export interface StackTerminalQuery {
+	/** Id or name */
+	stack: string;
+	/** The service name */
+	service: string;
+	/**
+	 * The shell to run in the container.
+	 * Default: `sh`
+	 */
+	shell?: string;
+}
+
+/** Query params for `/ws/log/deployment` */
+export interface DeploymentLogQuery {
+	/** Id or name */
+	deployment: string;
+	/**
+	 * The number of existing lines to send before following.
+	 * Default: `100`
+	 */
+	tail: U64;
+	/** Prefix each line with its timestamp. */
+	timestamps?: boolean;
+	/** Only include lines after this unix timestamp (ms). */
+	since?: I64;
+	/**
+	 * Only include lines before this unix timestamp (ms).
+	 * The log will stop following once reached.
+	 */
+	until?: I64;
+}
+
+/** Query params for `/ws/log/stack` */
+export interface StackLogQuery {
+	/** Id or name */
+	stack: string;
+	/** The service name */
+	service: string;
+	/**
+	 * The number of existing lines to send before following.
+	 * Default: `100`
+	 */
+	tail: U64;
+	/** Prefix each line with its timestamp. */
+	timestamps?: boolean;
+	/** Only include lines after this unix timestamp (ms). */
+	since?: I64;
+	/**
+	 * Only include lines before this unix timestamp (ms).
+	 * The log will stop following once reached.
+	 */
+	until?: I64;
+}
+
+/** Filter lines of a followed log by search terms. */
+export interface LogSearch {
+	/** The terms to search for. */
+	terms: string[];
+	/**
+	 * When searching for multiple terms, can use `AND` or `OR` combinator.
+	 * 
+	 * - `AND`: Only include lines with **all** terms present in that line.
+	 * - `OR`: Include lines that have one or more matches in the terms.
+	 */
+	combinator?: SearchCombinator;
+	/** Invert the results, ie return all lines that DON'T match the terms / combinator. */
+	invert?: boolean;
+}
+
+/** Sent by Core as Text for each line of a followed log. */
+export interface LogLine {
+	/** Whether the line was written to stderr. */
+	stderr: boolean;
+	/** The line contents, without trailing newline. */
+	line: string;
+}
+
 export type AuthRequest = 
 	| { type: "GetLoginOptions", params: GetLoginOptions }
 	| { type: "CreateLocalUser", params: CreateLocalUser }
 	| { type: "LoginLocalUser", params: LoginLocalUser }
+	| { type: "LoginTotp", params: LoginTotp }
+	| { type: "LoginLdapUser", params: LoginLdapUser }
 	| { type: "ExchangeForJwt", params: ExchangeForJwt }
 	| { type: "GetUser", params: GetUser };
 
@@ -6468,6 +7418,7 @@ export type ExecuteRequest =
 	| { type: "PruneBuildx", params: PruneBuildx }
 	| { type: "PruneSystem", params: PruneSystem }
 	| { type: "Deploy", params: Deploy }
+	| { type: "RollbackDeployment", params: RollbackDeployment }
 	| { type: "StartDeployment", params: StartDeployment }
 	| { type: "RestartDeployment", params: RestartDeployment }
 	| { type: "PauseDeployment", params: PauseDeployment }
@@ -6543,6 +7494,7 @@ export type ReadRequest =
 	| { type: "GetDeployment", params: GetDeployment }
 	| { type: "GetDeploymentContainer", params: GetDeploymentContainer }
 	| { type: "GetDeploymentActionState", params: GetDeploymentActionState }
+	| { type: "ListDeploymentHistory", params: ListDeploymentHistory }
 	| { type: "GetDeploymentStats", params: GetDeploymentStats }
 	| { type: "GetDeploymentLog", params: GetDeploymentLog }
 	| { type: "SearchDeploymentLog", params: SearchDeploymentLog }
@@ -6597,6 +7549,8 @@ export type ReadRequest =
 	| { type: "ListUpdates", params: ListUpdates }
 	| { type: "ListAlerts", params: ListAlerts }
 	| { type: "GetAlert", params: GetAlert }
+	| { type: "GetSilence", params: GetSilence }
+	| { type: "ListSilences", params: ListSilences }
 	| { type: "GetSystemInformation", params: GetSystemInformation }
 	| { type: "GetSystemStats", params: GetSystemStats }
 	| { type: "ListSystemProcesses", params: ListSystemProcesses }
@@ -6611,7 +7565,10 @@ export type UserRequest =
 	| { type: "PushRecentlyViewed", params: PushRecentlyViewed }
 	| { type: "SetLastSeenUpdate", params: SetLastSeenUpdate }
 	| { type: "CreateApiKey", params: CreateApiKey }
-	| { type: "DeleteApiKey", params: DeleteApiKey };
+	| { type: "DeleteApiKey", params: DeleteApiKey }
+	| { type: "BeginTotpEnrollment", params: BeginTotpEnrollment }
+	| { type: "ConfirmTotpEnrollment", params: ConfirmTotpEnrollment }
+	| { type: "DisableTotp", params: DisableTotp };
 
 export type WriteRequest = 
 	| { type: "CreateServiceUser", params: CreateServiceUser }
@@ -6626,6 +7583,7 @@ export type WriteRequest =
 	| { type: "SetUsersInUserGroup", params: SetUsersInUserGroup }
 	| { type: "UpdateUserBasePermissions", params: UpdateUserBasePermissions }
 	| { type: "UpdatePermissionOnResourceType", params: UpdatePermissionOnResourceType }
+	| { type: "UpdatePermissionOnTag", params: UpdatePermissionOnTag }
 	| { type: "UpdatePermissionOnTarget", params: UpdatePermissionOnTarget }
 	| { type: "UpdateDescription", params: UpdateDescription }
 	| { type: "CreateServer", params: CreateServer }
@@ -6692,6 +7650,9 @@ export type WriteRequest =
 	| { type: "UpdateVariableDescription", params: UpdateVariableDescription }
 	| { type: "UpdateVariableIsSecret", params: UpdateVariableIsSecret }
 	| { type: "DeleteVariable", params: DeleteVariable }
+	| { type: "CreateSilence", params: CreateSilence }
+	| { type: "UpdateSilence", params: UpdateSilence }
+	| { type: "DeleteSilence", params: DeleteSilence }
 	| { type: "CreateGitProviderAccount", params: CreateGitProviderAccount }
 	| { type: "UpdateGitProviderAccount", params: UpdateGitProviderAccount }
 	| { type: "DeleteGitProviderAccount", params: DeleteGitProviderAccount }
@@ -6708,3 +7669,26 @@ export type WsLoginMessage =
 	secret: string;
 }};
 
+/**
+ * Control messages sent as Text over the container terminal websockets,
+ * `/ws/terminal/deployment` and `/ws/terminal/stack`.
+ * Binary messages carry the raw terminal input / output.
+ */
+export type TerminalMessage = 
+	/** Resize the terminal */
+	| { type: "Resize", params: {
+	rows: number;
+	cols: number;
+}};
+
+/**
+ * Messages the client may send as Text over the log websockets,
+ * `/ws/log/deployment` and `/ws/log/stack`.
+ */
+export type LogFollowMessage = 
+	/**
+	 * Replace the search filter applied to incoming lines.
+	 * Send empty `terms` to clear it.
+	 */
+	| { type: "Search", params: LogSearch };
+
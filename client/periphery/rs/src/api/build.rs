@@ -1,5 +1,7 @@
 use komodo_client::entities::{
-  config::core::AwsEcrConfig, update::Log,
+  build::{ImageScanner, VulnerabilityScan},
+  config::core::AwsEcrConfig,
+  update::Log,
 };
 use resolver_api::derive::Request;
 use serde::{Deserialize, Serialize};
@@ -12,6 +14,12 @@ pub struct Build {
   pub registry_token: Option<String>,
   /// Propogate AwsEcrConfig from core
   pub aws_ecr: Option<AwsEcrConfig>,
+  /// Override cache registry token with one sent from core.
+  #[serde(default)]
+  pub cache_registry_token: Option<String>,
+  /// Propogate AwsEcrConfig for the cache registry from core
+  #[serde(default)]
+  pub cache_aws_ecr: Option<AwsEcrConfig>,
   /// Propogate any secret replacers from core interpolation.
   #[serde(default)]
   pub replacers: Vec<(String, String)>,
@@ -24,6 +32,31 @@ pub type BuildResponse = Vec<Log>;
 
 //
 
+/// Scans a built image for vulnerabilities.
+#[derive(Serialize, Deserialize, Debug, Clone, Request)]
+#[response(ScanImageResponse)]
+pub struct ScanImage {
+  /// The image name, without tag
+  pub image_name: String,
+  /// The image tag to scan
+  pub tag: String,
+  pub scanner: ImageScanner,
+  /// Override the scanner command.
+  /// `[[IMAGE]]` is replaced with the image reference.
+  pub command: String,
+  /// Whether the image was pushed with an SBOM attestation.
+  pub sbom: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScanImageResponse {
+  pub logs: Vec<Log>,
+  /// Null if the scan failed.
+  pub scan: Option<VulnerabilityScan>,
+}
+
+//
+
 #[derive(Serialize, Deserialize, Debug, Clone, Request)]
 #[response(Log)]
 pub struct PruneBuilders {}
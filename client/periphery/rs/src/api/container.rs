@@ -1,7 +1,8 @@
 use komodo_client::entities::{
   config::core::AwsEcrConfig,
-  deployment::Deployment,
+  deployment::{Deployment, HealthGate},
   docker::container::{Container, ContainerStats},
+  to_komodo_name,
   update::Log,
   SearchCombinator, TerminationSignal,
 };
@@ -10,6 +11,32 @@ use serde::{Deserialize, Serialize};
 
 //
 
+/// Query params for the container terminal websocket, `/terminal`.
+/// Runs `shell` in the container with a TTY attached.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConnectContainerExec {
+  pub container: String,
+  pub shell: String,
+}
+
+//
+
+/// Query params for the container log websocket, `/log`.
+/// Follows the container log, sending each line as a `LogLine`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConnectContainerLog {
+  pub container: String,
+  pub tail: u64,
+  #[serde(default)]
+  pub timestamps: bool,
+  /// Unix timestamp in ms
+  pub since: Option<i64>,
+  /// Unix timestamp in ms
+  pub until: Option<i64>,
+}
+
+//
+
 #[derive(Serialize, Deserialize, Debug, Clone, Request)]
 #[response(Container)]
 pub struct InspectContainer {
@@ -80,6 +107,71 @@ pub struct Deploy {
 
 //
 
+/// Polls the container until it passes the health gate,
+/// it fails, or the timeout is reached.
+#[derive(Serialize, Deserialize, Debug, Clone, Request)]
+#[response(Log)]
+pub struct WaitContainerHealthy {
+  pub name: String,
+  pub gate: HealthGate,
+  /// The url / address for Http / Tcp gates.
+  pub target: String,
+  /// Timeout in seconds.
+  pub timeout: i32,
+}
+
+//
+
+/// Moves the deployment name network alias from the old container
+/// to the new BlueGreen container. Only user-defined networks
+/// support aliases, other networks just log that it was skipped.
+#[derive(Serialize, Deserialize, Debug, Clone, Request)]
+#[response(Log)]
+pub struct SwitchContainerAlias {
+  pub name: String,
+  pub network: String,
+}
+
+//
+
+/// Stops and removes the old container,
+/// and renames the new BlueGreen container to take its place.
+#[derive(Serialize, Deserialize, Debug, Clone, Request)]
+#[response(Log)]
+pub struct ReplaceContainer {
+  pub name: String,
+  pub signal: Option<TerminationSignal>,
+  pub time: Option<i32>,
+}
+
+/// The name the new container runs under during a BlueGreen deploy,
+/// before it replaces the old container.
+pub fn blue_green_container_name(name: &str) -> String {
+  format!("{}-next", to_komodo_name(name))
+}
+
+//
+
+/// Replaces the container with the previous container
+/// kept by a Deploy using `health_gate_restore`.
+#[derive(Serialize, Deserialize, Debug, Clone, Request)]
+#[response(Log)]
+pub struct RestorePreviousContainer {
+  pub name: String,
+}
+
+//
+
+/// Removes the previous container
+/// kept by a Deploy using `health_gate_restore`.
+#[derive(Serialize, Deserialize, Debug, Clone, Request)]
+#[response(Log)]
+pub struct RemovePreviousContainer {
+  pub name: String,
+}
+
+//
+
 #[derive(Serialize, Deserialize, Debug, Clone, Request)]
 #[response(Log)]
 pub struct StartContainer {
@@ -5,7 +5,7 @@ use komodo_client::entities::{
     network::NetworkListItem, volume::VolumeListItem,
   },
   stack::ComposeProject,
-  update::Log,
+  update::{Log, LogOutput},
   SystemCommand,
 };
 use resolver_api::derive::Request;
@@ -23,6 +23,21 @@ pub mod volume;
 
 //
 
+/// A line of the newline delimited json response
+/// of the streaming periphery endpoint, `/stream`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", content = "data")]
+pub enum StreamMessage {
+  /// Incremental output of a running command.
+  Output(LogOutput),
+  /// The serialized response, sent once the request completes.
+  Response(String),
+  /// The serialized error, sent if the request fails.
+  Error(String),
+}
+
+//
+
 #[derive(Serialize, Deserialize, Debug, Clone, Request)]
 #[response(GetHealthResponse)]
 pub struct GetHealth {}
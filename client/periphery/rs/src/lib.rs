@@ -1,10 +1,21 @@
 use std::{sync::OnceLock, time::Duration};
 
-use anyhow::Context;
+use anyhow::{anyhow, Context};
+use api::{
+  container::{ConnectContainerExec, ConnectContainerLog},
+  StreamMessage,
+};
+use komodo_client::entities::update::LogOutput;
 use reqwest::StatusCode;
 use resolver_api::HasResponse;
 use serde_json::json;
 use serror::deserialize_error;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+  connect_async,
+  tungstenite::{client::IntoClientRequest, http::HeaderValue},
+  MaybeTlsStream, WebSocketStream,
+};
 
 pub mod api;
 
@@ -14,6 +25,9 @@ fn http_client() -> &'static reqwest::Client {
   PERIPHERY_HTTP_CLIENT.get_or_init(Default::default)
 }
 
+pub type PeripheryWebsocket =
+  WebSocketStream<MaybeTlsStream<TcpStream>>;
+
 pub struct PeripheryClient {
   address: String,
   passkey: String,
@@ -46,6 +60,156 @@ impl PeripheryClient {
     self.request_inner(request, None).await
   }
 
+  /// Same as [PeripheryClient::request], but any command output
+  /// is passed to `on_output` as it is produced, using the `/stream` endpoint.
+  ///
+  /// Falls back to [PeripheryClient::request] for older Periphery
+  /// versions without the `/stream` endpoint.
+  #[tracing::instrument(
+    name = "PeripheryStreamingRequest",
+    level = "debug",
+    skip(self, on_output)
+  )]
+  pub async fn request_streaming<T: HasResponse>(
+    &self,
+    request: T,
+    mut on_output: impl FnMut(LogOutput),
+  ) -> anyhow::Result<T::Response> {
+    self.health_check().await?;
+    let req_type = T::req_type();
+    let mut res = http_client()
+      .post(format!("{}/stream", self.address))
+      .json(&json!({
+        "type": req_type,
+        "params": request
+      }))
+      .header("authorization", &self.passkey)
+      .send()
+      .await
+      .context("failed at streaming request to periphery")?;
+    let status = res.status();
+    if status == StatusCode::NOT_FOUND
+      || status == StatusCode::METHOD_NOT_ALLOWED
+    {
+      tracing::debug!(
+        "periphery does not support streaming, falling back"
+      );
+      return self.request_inner(request, None).await;
+    }
+    if status != StatusCode::OK {
+      let text = res
+        .text()
+        .await
+        .context("failed to convert response to text")?;
+      return Err(
+        deserialize_error(text)
+          .context(format!("request to periphery failed | {status}")),
+      );
+    }
+    let mut buffer = Vec::new();
+    while let Some(chunk) = res
+      .chunk()
+      .await
+      .context("failed to read periphery response stream")?
+    {
+      buffer.extend_from_slice(&chunk);
+      while let Some(index) = buffer.iter().position(|b| *b == b'\n')
+      {
+        let line = buffer.drain(..=index).collect::<Vec<_>>();
+        let message = serde_json::from_slice::<StreamMessage>(&line)
+          .with_context(|| {
+            format!(
+              "failed to parse stream message | type: {req_type}"
+            )
+          })?;
+        match message {
+          StreamMessage::Output(output) => on_output(output),
+          StreamMessage::Response(response) => {
+            return serde_json::from_str(&response).with_context(|| {
+              format!("failed to parse response to json | type: {req_type}")
+            })
+          }
+          StreamMessage::Error(error) => {
+            return Err(
+              deserialize_error(error)
+                .context("request to periphery failed"),
+            )
+          }
+        }
+      }
+    }
+    Err(anyhow!(
+      "periphery response stream ended without a response | type: {req_type}"
+    ))
+  }
+
+  /// Connects to the container terminal websocket.
+  /// Binary messages carry the raw terminal input / output,
+  /// Text messages carry `TerminalMessage` control messages.
+  #[tracing::instrument(level = "debug", skip(self))]
+  pub async fn connect_container_exec(
+    &self,
+    ConnectContainerExec { container, shell }: ConnectContainerExec,
+  ) -> anyhow::Result<PeripheryWebsocket> {
+    self
+      .connect_websocket(&format!(
+        "/terminal?container={}&shell={}",
+        urlencoding::encode(&container),
+        urlencoding::encode(&shell)
+      ))
+      .await
+  }
+
+  /// Connects to the container log websocket.
+  /// Each log line is sent as a Text `LogLine`.
+  #[tracing::instrument(level = "debug", skip(self))]
+  pub async fn connect_container_log(
+    &self,
+    ConnectContainerLog {
+      container,
+      tail,
+      timestamps,
+      since,
+      until,
+    }: ConnectContainerLog,
+  ) -> anyhow::Result<PeripheryWebsocket> {
+    let mut path = format!(
+      "/log?container={}&tail={tail}&timestamps={timestamps}",
+      urlencoding::encode(&container),
+    );
+    if let Some(since) = since {
+      path.push_str(&format!("&since={since}"));
+    }
+    if let Some(until) = until {
+      path.push_str(&format!("&until={until}"));
+    }
+    self.connect_websocket(&path).await
+  }
+
+  async fn connect_websocket(
+    &self,
+    path: &str,
+  ) -> anyhow::Result<PeripheryWebsocket> {
+    self.health_check().await?;
+    let address = self
+      .address
+      .replacen("http", "ws", 1)
+      .trim_end_matches('/')
+      .to_string();
+    let mut req = format!("{address}{path}")
+      .into_client_request()
+      .context("failed to create websocket request")?;
+    req.headers_mut().insert(
+      "authorization",
+      HeaderValue::from_str(&self.passkey)
+        .context("invalid passkey header value")?,
+    );
+    let (socket, _) = connect_async(req)
+      .await
+      .context("failed to connect to periphery websocket")?;
+    Ok(socket)
+  }
+
   #[tracing::instrument(level = "debug", skip(self))]
   pub async fn health_check(&self) -> anyhow::Result<()> {
     self
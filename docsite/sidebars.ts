@@ -60,6 +60,7 @@ const sidebars: SidebarsConfig = {
     "docker-compose",
     "sync-resources",
     "webhooks",
+    "metrics",
     "permissioning",
     "version-upgrades",
     "api",
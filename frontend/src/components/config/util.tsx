@@ -162,6 +162,37 @@ export const ConfigSwitch = ({
   </ConfigItem>
 );
 
+export const EnumSelectorConfig = <T extends string>({
+  label,
+  description,
+  value,
+  values,
+  onSelect,
+  disabled,
+}: {
+  label: string;
+  description: string;
+  value: T | undefined;
+  values: T[];
+  onSelect: (value: T) => void;
+  disabled: boolean;
+}) => (
+  <ConfigItem label={label} description={description}>
+    <Select value={value} onValueChange={onSelect} disabled={disabled}>
+      <SelectTrigger className="w-[200px]" disabled={disabled}>
+        <SelectValue placeholder="Select" />
+      </SelectTrigger>
+      <SelectContent>
+        {values.map((value) => (
+          <SelectItem key={value} value={value} className="cursor-pointer">
+            {value}
+          </SelectItem>
+        ))}
+      </SelectContent>
+    </Select>
+  </ConfigItem>
+);
+
 export const DoubleInput = <
   T extends object,
   K extends keyof T,
@@ -43,6 +43,10 @@ export const KeysTable = ({
                 " Days"
               : "Never",
         },
+        {
+          header: "Scope",
+          accessorFn: ({ scope }) => scope_summary(scope),
+        },
         {
           header: "Delete",
           cell: ({ row }) => <DeleteKey api_key={row.original.key} />,
@@ -51,3 +55,18 @@ export const KeysTable = ({
     />
   );
 };
+
+const scope_summary = (scope: Types.ApiKeyScope | undefined) => {
+  if (!scope) return "Unrestricted";
+  const parts = [];
+  if (scope.requests?.length) parts.push(scope.requests.join(" / "));
+  if (scope.resources?.length || scope.tags?.length) {
+    parts.push(
+      `${scope.resources?.length ?? 0} resources, ${scope.tags?.length ?? 0} tags`
+    );
+  }
+  if (scope.max_permission && scope.max_permission !== "Write") {
+    parts.push(`max ${scope.max_permission}`);
+  }
+  return parts.length ? parts.join(" | ") : "Unrestricted";
+};
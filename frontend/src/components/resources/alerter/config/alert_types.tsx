@@ -17,6 +17,7 @@ const ALERT_TYPES: Types.AlertData["type"][] = [
   "ContainerStateChange",
   "ResourceSyncPendingUpdates",
   "BuildFailed",
+  "BuildVulnerabilities",
   "AwsBuilderTerminationFailed",
 ];
 
@@ -4,6 +4,7 @@ import {
   AddExtraArgMenu,
   ConfigInput,
   ConfigItem,
+  EnumSelectorConfig,
   ImageRegistryConfig,
   InputList,
   ProviderSelectorConfig,
@@ -170,6 +171,161 @@ export const BuildConfig = ({
               },
             },
           },
+          {
+            label: "Platforms",
+            description:
+              "Build a multi-arch image for these platforms, eg. linux/amd64, linux/arm64. Leave empty to build for the builder's platform.",
+            contentHidden:
+              ((update.platforms ?? config.platforms)?.length ?? 0) === 0,
+            actions: !disabled && (
+              <Button
+                variant="secondary"
+                onClick={() =>
+                  set((update) => ({
+                    ...update,
+                    platforms: [
+                      ...(update.platforms ?? config.platforms ?? []),
+                      "",
+                    ],
+                  }))
+                }
+                className="flex items-center gap-2 w-[200px]"
+              >
+                <PlusCircle className="w-4 h-4" />
+                Add Platform
+              </Button>
+            ),
+            components: {
+              platforms: (values, set) => (
+                <InputList
+                  field="platforms"
+                  values={values ?? []}
+                  set={set}
+                  disabled={disabled}
+                  placeholder="linux/arm64"
+                />
+              ),
+              buildx_builder: {
+                description:
+                  "The buildx builder instance used for platform builds. Created with the docker-container driver if it doesn't exist.",
+                placeholder: "komodo",
+              },
+            },
+          },
+          {
+            label: "Build Cache",
+            description:
+              "Import and export the BuildKit cache, eg. type=registry,ref=ghcr.io/org/app:buildcache. Useful to keep the cache between builds on ephemeral builders.",
+            contentHidden:
+              ((update.cache_from ?? config.cache_from)?.length ?? 0) === 0 &&
+              ((update.cache_to ?? config.cache_to)?.length ?? 0) === 0,
+            actions: !disabled && (
+              <div className="flex items-center gap-4">
+                <Button
+                  variant="secondary"
+                  onClick={() =>
+                    set((update) => ({
+                      ...update,
+                      cache_from: [
+                        ...(update.cache_from ?? config.cache_from ?? []),
+                        "",
+                      ],
+                    }))
+                  }
+                  className="flex items-center gap-2 w-[200px]"
+                >
+                  <PlusCircle className="w-4 h-4" />
+                  Add Cache From
+                </Button>
+                <Button
+                  variant="secondary"
+                  onClick={() =>
+                    set((update) => ({
+                      ...update,
+                      cache_to: [
+                        ...(update.cache_to ?? config.cache_to ?? []),
+                        "",
+                      ],
+                    }))
+                  }
+                  className="flex items-center gap-2 w-[200px]"
+                >
+                  <PlusCircle className="w-4 h-4" />
+                  Add Cache To
+                </Button>
+              </div>
+            ),
+            components: {
+              cache_from: (values, set) => (
+                <InputList
+                  field="cache_from"
+                  values={values ?? []}
+                  set={set}
+                  disabled={disabled}
+                  placeholder="type=registry,ref=..."
+                />
+              ),
+              cache_to: (values, set) => (
+                <InputList
+                  field="cache_to"
+                  values={values ?? []}
+                  set={set}
+                  disabled={disabled}
+                  placeholder="type=registry,ref=...,mode=max"
+                />
+              ),
+              cache_registry: (registry, set) => (
+                <ImageRegistryConfig
+                  registry={registry}
+                  setRegistry={(cache_registry) => set({ cache_registry })}
+                  resource_id={update.builder_id ?? config.builder_id}
+                  disabled={disabled}
+                />
+              ),
+            },
+          },
+          {
+            label: "Vulnerability Scan",
+            description:
+              "Scan the image for vulnerabilities after a successful build. The scanner must be installed on the builder.",
+            components: {
+              scanner: (scanner, set) => (
+                <EnumSelectorConfig
+                  label="Scanner"
+                  description="The tool used to scan the image."
+                  value={scanner}
+                  values={Object.values(Types.ImageScanner)}
+                  onSelect={(scanner) => set({ scanner })}
+                  disabled={disabled}
+                />
+              ),
+              scan_command: {
+                description:
+                  "Optional. Override the scanner command. [[IMAGE]] is replaced with the image. Must print the json report to stdout.",
+                placeholder: "trivy image --quiet --format json [[IMAGE]]",
+              },
+              scan_alert_severity: (severity, set) => (
+                <EnumSelectorConfig
+                  label="Alert Severity"
+                  description="Count vulnerabilities at or above this severity towards the alert threshold."
+                  value={severity}
+                  values={Object.values(Types.VulnerabilitySeverity)}
+                  onSelect={(scan_alert_severity) =>
+                    set({ scan_alert_severity })
+                  }
+                  disabled={disabled}
+                />
+              ),
+              scan_alert_threshold: {
+                description:
+                  "Send an alert when the scan finds more than this many vulnerabilities at or above the alert severity.",
+              },
+              sbom: {
+                description:
+                  "Attach an SBOM attestation to the pushed image (--sbom=true). Requires an image registry.",
+              },
+            },
+          },
           {
             label: "Extra Args",
             description: (
@@ -412,7 +568,7 @@ export const BuildConfig = ({
           {
             label: "Secret Args",
             description:
-              "Pass secret args to 'docker build'. These can be used in the Dockerfile via ARG, and are visible in the final image.",
+              "Mount secrets in 'docker build'. These can be used in Dockerfile RUN commands via --mount=type=secret,id=SECRET_KEY, and are not visible in the final image.",
             components: {
               secret_args: (vars, set) => {
                 const args =
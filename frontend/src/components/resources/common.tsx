@@ -30,6 +30,13 @@ import { useState } from "react";
 import { Link, useNavigate } from "react-router-dom";
 import { ResourceComponents } from ".";
 import { Input } from "@ui/input";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@ui/select";
 import { useToast } from "@ui/use-toast";
 import { NewLayout } from "@components/layouts";
 import { Types } from "@komodo/client";
@@ -383,11 +390,26 @@ export const LabelsConfig = ({
   </div>
 );
 
+const WEBHOOK_PROVIDERS = ["github", "gitlab", "gitea", "bitbucket"];
+
 export const CopyGithubWebhook = ({ path }: { path: string }) => {
   const base_url = useRead("GetCoreInfo", {}).data?.webhook_base_url;
-  const url = base_url + "/listener/github" + path;
+  const [provider, setProvider] = useState("github");
+  const url = base_url + "/listener/" + provider + path;
   return (
     <div className="flex gap-2 items-center">
+      <Select value={provider} onValueChange={setProvider}>
+        <SelectTrigger className="w-[120px]">
+          <SelectValue />
+        </SelectTrigger>
+        <SelectContent>
+          {WEBHOOK_PROVIDERS.map((provider) => (
+            <SelectItem key={provider} value={provider}>
+              {provider}
+            </SelectItem>
+          ))}
+        </SelectContent>
+      </Select>
       <Input className="w-[400px] max-w-[70vw]" value={url} readOnly />
       <CopyButton content={url} />
     </div>
@@ -1,6 +1,16 @@
 import { ActionWithDialog, ConfirmButton } from "@components/util";
-import { Play, Trash, Pause, Rocket, Pen, RefreshCcw, Square } from "lucide-react";
+import {
+  Play,
+  Trash,
+  Pause,
+  Rocket,
+  Pen,
+  RefreshCcw,
+  Square,
+  Undo2,
+} from "lucide-react";
 import { useExecute, useInvalidate, useRead, useWrite } from "@lib/hooks";
+import { has_minimum_permissions } from "@lib/utils";
 import { Input } from "@ui/input";
 import { useToast } from "@ui/use-toast";
 import { useEffect, useState } from "react";
@@ -36,13 +46,14 @@ export const DeployDeployment = ({ id }: DeploymentId) => {
   ).data;
   const deployment_item = deployments?.find((d) => d.id === id);
 
-  const deploying = useRead(
+  const action_state = useRead(
     "GetDeploymentActionState",
     { deployment: id },
     { refetchInterval: 5000 }
-  ).data?.deploying;
+  ).data;
 
-  const pending = isPending || deploying;
+  const pending = isPending || action_state?.deploying;
+  const phase = action_state && blue_green_phase(action_state);
 
   if (!deployment) return null;
 
@@ -54,7 +65,7 @@ export const DeployDeployment = ({ id }: DeploymentId) => {
     return (
       <ActionWithDialog
         name={deployment.name}
-        title="Redeploy"
+        title={phase ?? "Redeploy"}
         icon={<Rocket className="h-4 w-4" />}
         onClick={() => deploy({ deployment: id, stop_signal: signal })}
         disabled={pending}
@@ -73,7 +84,7 @@ export const DeployDeployment = ({ id }: DeploymentId) => {
   } else {
     return (
       <ConfirmButton
-        title="Deploy"
+        title={phase ?? "Deploy"}
         icon={<Rocket className="h-4 w-4" />}
         onClick={() => deploy({ deployment: id })}
         disabled={pending}
@@ -83,6 +94,13 @@ export const DeployDeployment = ({ id }: DeploymentId) => {
   }
 };
 
+const blue_green_phase = (state: Types.DeploymentActionState) => {
+  if (state.deploying_next) return "Starting New";
+  if (state.health_checking) return "Health Checking";
+  if (state.switching) return "Switching";
+  if (state.removing_old) return "Removing Old";
+};
+
 export const DestroyDeployment = ({ id }: DeploymentId) => {
   const deployment = useRead("GetDeployment", { deployment: id }).data;
   const [signal, setSignal] = useState<Types.TerminationSignal>();
@@ -161,6 +179,43 @@ export const RestartDeployment = ({ id }: DeploymentId) => {
   );
 };
 
+export const RollbackDeployment = ({ id }: DeploymentId) => {
+  const deployment = useDeployment(id);
+  const { mutate: rollback, isPending } = useExecute("RollbackDeployment");
+  const history = useRead("ListDeploymentHistory", {
+    deployment: id,
+    limit: 2,
+  }).data;
+  const deploying = useRead(
+    "GetDeploymentActionState",
+    { deployment: id },
+    { refetchInterval: 5000 }
+  ).data?.deploying;
+  const perms = useRead("GetPermissionLevel", {
+    target: { type: "Deployment", id },
+  }).data;
+
+  const pending = isPending || deploying;
+
+  // Need a previous deploy to roll back to
+  if (!deployment || (history?.length ?? 0) < 2) return null;
+  // Rollback restores the config, so needs Write
+  if (!has_minimum_permissions(perms, Types.PermissionLevel.Write)) {
+    return null;
+  }
+
+  return (
+    <ActionWithDialog
+      name={deployment.name}
+      title="Rollback"
+      icon={<Undo2 className="h-4 w-4" />}
+      onClick={() => rollback({ deployment: id })}
+      disabled={pending}
+      loading={pending}
+    />
+  );
+};
+
 export const StartStopDeployment = ({ id }: DeploymentId) => {
   const deployment = useDeployment(id);
   const state = deployment?.info.state;
@@ -4,6 +4,7 @@ import { ReactNode, useState } from "react";
 import {
   AddExtraArgMenu,
   ConfigItem,
+  EnumSelectorConfig,
   InputList,
 } from "@components/config/util";
 import { ImageConfig } from "./components/image";
@@ -45,6 +46,10 @@ export const DeploymentConfig = ({
 
   const network = update.network ?? config.network;
   const hide_ports = network === "host" || network === "none";
+  const health_gate = update.health_gate ?? config.health_gate;
+  const probe_gate =
+    health_gate === Types.HealthGate.Http ||
+    health_gate === Types.HealthGate.Tcp;
 
   const disabled = global_disabled || perms !== Types.PermissionLevel.Write;
 
@@ -87,6 +92,16 @@ export const DeploymentConfig = ({
                   disabled={disabled}
                 />
               ),
+              strategy: (value, set) => (
+                <EnumSelectorConfig
+                  label="Strategy"
+                  description="Recreate stops the old container before starting the new one. BlueGreen starts the new container first, and removes the old one once the new one passes the health gate (or is running, with no health gate). BlueGreen can't use the host network or publish ports."
+                  value={value}
+                  values={Object.values(Types.DeploymentStrategy)}
+                  onSelect={(strategy) => set({ strategy })}
+                  disabled={disabled}
+                />
+              ),
               network: (value, set) => (
                 <NetworkModeSelector
                   server_id={update.server_id ?? config.server_id}
@@ -296,6 +311,40 @@ export const DeploymentConfig = ({
               ),
             },
           },
+          {
+            label: "Health Gate",
+            description:
+              "Wait for the container to pass a health check after deploying. The deploy fails if it doesn't pass before the timeout.",
+            components: {
+              health_gate: (value, set) => (
+                <EnumSelectorConfig
+                  label="Health Gate"
+                  description="Docker waits for the container HEALTHCHECK. Http / Tcp probe the target from the server."
+                  value={value}
+                  values={Object.values(Types.HealthGate)}
+                  onSelect={(health_gate) => set({ health_gate })}
+                  disabled={disabled}
+                />
+              ),
+              health_gate_target: probe_gate && {
+                description:
+                  health_gate === Types.HealthGate.Http
+                    ? "The url to GET. Passes on any 2xx response."
+                    : "The address to connect to. Passes once a connection is accepted.",
+                placeholder:
+                  health_gate === Types.HealthGate.Http
+                    ? "http://localhost:8080/health"
+                    : "localhost:5432",
+              },
+              health_gate_timeout: health_gate !== Types.HealthGate.None && {
+                description: "Seconds to wait for the health gate to pass.",
+              },
+              health_gate_restore: health_gate !== Types.HealthGate.None && {
+                description:
+                  "Keep the previous container until the health gate passes, and restore it if the deploy fails.",
+              },
+            },
+          },
           {
             label: "Settings",
             components: {
@@ -11,6 +11,7 @@ import {
   RenameDeployment,
   RestartDeployment,
   PauseUnpauseDeployment,
+  RollbackDeployment,
 } from "./actions";
 import { DeploymentLogs } from "./log";
 import {
@@ -205,6 +206,7 @@ export const DeploymentComponents: RequiredResourceComponents = {
       return <RunBuild id={build_id} />;
     },
     DeployDeployment,
+    RollbackDeployment,
     RestartDeployment,
     PauseUnpauseDeployment,
     StartStopDeployment,
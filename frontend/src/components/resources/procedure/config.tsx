@@ -30,6 +30,13 @@ import {
   CommandList,
 } from "@ui/command";
 import { Switch } from "@ui/switch";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@ui/select";
 import { DataTable } from "@ui/data-table";
 import { HoverCard, HoverCardContent, HoverCardTrigger } from "@ui/hover-card";
 import {
@@ -41,6 +48,7 @@ import {
 } from "@ui/dropdown-menu";
 import { DotsHorizontalIcon } from "@radix-ui/react-icons";
 import { filterBySplit } from "@lib/utils";
+import { TextUpdateMenu } from "@components/util";
 import { useToast } from "@ui/use-toast";
 
 export const ProcedureConfig = ({ id }: { id: string }) => {
@@ -240,6 +248,87 @@ const ProcedureConfigInner = ({
             </ConfigItem>
           </CardHeader>
         </Card>
+        <Card>
+          <CardHeader className="p-4">
+            <ConfigItem label="Schedule" className="items-start">
+              <div className="flex flex-col gap-4">
+                <div className="flex items-center justify-end gap-4 w-full">
+                  <div className="text-muted-foreground">Format:</div>
+                  <Select
+                    value={
+                      config.schedule_format ??
+                      procedure.config?.schedule_format
+                    }
+                    onValueChange={(schedule_format: Types.ScheduleFormat) =>
+                      setConfig({ ...config, schedule_format })
+                    }
+                    disabled={disabled}
+                  >
+                    <SelectTrigger className="w-[150px]" disabled={disabled}>
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      {Object.values(Types.ScheduleFormat).map((format) => (
+                        <SelectItem key={format} value={format}>
+                          {format}
+                        </SelectItem>
+                      ))}
+                    </SelectContent>
+                  </Select>
+                </div>
+                <div className="flex items-center justify-end gap-4 w-full">
+                  <div className="text-muted-foreground">Schedule:</div>
+                  <Input
+                    placeholder={
+                      (config.schedule_format ??
+                        procedure.config?.schedule_format) ===
+                      Types.ScheduleFormat.Interval
+                        ? "1-hr"
+                        : "0 0 3 * * *"
+                    }
+                    value={config.schedule ?? procedure.config?.schedule}
+                    onChange={(e) =>
+                      setConfig({ ...config, schedule: e.target.value })
+                    }
+                    disabled={disabled}
+                    className="w-[400px] max-w-full"
+                  />
+                </div>
+                <div className="flex items-center justify-end gap-4 w-full">
+                  <div className="text-muted-foreground">Timezone:</div>
+                  <Input
+                    placeholder="UTC"
+                    value={
+                      config.schedule_timezone ??
+                      procedure.config?.schedule_timezone
+                    }
+                    onChange={(e) =>
+                      setConfig({
+                        ...config,
+                        schedule_timezone: e.target.value,
+                      })
+                    }
+                    disabled={disabled}
+                    className="w-[400px] max-w-full"
+                  />
+                </div>
+                <div className="flex items-center justify-end gap-4 w-full">
+                  <div className="text-muted-foreground">Enabled:</div>
+                  <Switch
+                    checked={
+                      config.schedule_enabled ??
+                      procedure.config?.schedule_enabled
+                    }
+                    onCheckedChange={(schedule_enabled) =>
+                      setConfig({ ...config, schedule_enabled })
+                    }
+                    disabled={disabled}
+                  />
+                </div>
+              </div>
+            </ConfigItem>
+          </CardHeader>
+        </Card>
       </Section>
     </div>
   );
@@ -273,6 +362,25 @@ const Stage = ({
           className="w-[300px] text-md"
         />
         <div className="flex gap-4 items-center">
+          <div>Run On:</div>
+          <Select
+            value={stage.run_on ?? Types.StageRunOn.Success}
+            onValueChange={(run_on: Types.StageRunOn) =>
+              setStage({ ...stage, run_on })
+            }
+            disabled={disabled}
+          >
+            <SelectTrigger className="w-[120px]" disabled={disabled}>
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {Object.values(Types.StageRunOn).map((run_on) => (
+                <SelectItem key={run_on} value={run_on}>
+                  {run_on}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
           <div>Enabled:</div>
           <Switch
             checked={stage.enabled}
@@ -342,6 +450,11 @@ const Stage = ({
           </DropdownMenu>
         </div>
       </div>
+      <StageConditionConfig
+        condition={stage.condition ?? { type: "None" }}
+        setCondition={(condition) => setStage({ ...stage, condition })}
+        disabled={disabled}
+      />
       <DataTable
         tableKey="procedure-stage-executions"
         data={stage.executions!}
@@ -457,6 +570,93 @@ const Stage = ({
               </div>
             ),
           },
+          {
+            header: "Retries",
+            cell: ({ row: { original, index } }) => (
+              <Input
+                type="number"
+                value={original.retries ?? 0}
+                onChange={(e) =>
+                  setStage({
+                    ...stage,
+                    executions: stage.executions!.map((item, i) =>
+                      i === index
+                        ? { ...item, retries: Number(e.target.value) }
+                        : item
+                    ),
+                  })
+                }
+                className="w-[100px]"
+                disabled={disabled}
+              />
+            ),
+          },
+          {
+            header: "Retry Delay (ms)",
+            cell: ({ row: { original, index } }) => (
+              <Input
+                type="number"
+                value={original.retry_delay_ms ?? 0}
+                onChange={(e) =>
+                  setStage({
+                    ...stage,
+                    executions: stage.executions!.map((item, i) =>
+                      i === index
+                        ? { ...item, retry_delay_ms: Number(e.target.value) }
+                        : item
+                    ),
+                  })
+                }
+                className="w-[100px]"
+                disabled={disabled}
+              />
+            ),
+          },
+          {
+            header: "Timeout (ms)",
+            cell: ({ row: { original, index } }) => (
+              <Input
+                type="number"
+                value={original.timeout_ms ?? 0}
+                onChange={(e) =>
+                  setStage({
+                    ...stage,
+                    executions: stage.executions!.map((item, i) =>
+                      i === index
+                        ? { ...item, timeout_ms: Number(e.target.value) }
+                        : item
+                    ),
+                  })
+                }
+                className="w-[100px]"
+                disabled={disabled}
+              />
+            ),
+          },
+          {
+            header: "Continue on Error",
+            cell: ({
+              row: {
+                original: { continue_on_error },
+                index,
+              },
+            }) => (
+              <Switch
+                checked={continue_on_error}
+                onClick={() =>
+                  setStage({
+                    ...stage,
+                    executions: stage.executions!.map((item, i) =>
+                      i === index
+                        ? { ...item, continue_on_error: !continue_on_error }
+                        : item
+                    ),
+                  })
+                }
+                disabled={disabled}
+              />
+            ),
+          },
           {
             header: "Enabled",
             cell: ({
@@ -487,14 +687,217 @@ const Stage = ({
   );
 };
 
-const new_stage = () => ({
+/** Parses comma separated `key=value` pairs */
+const parse_args = (args: string) =>
+  Object.fromEntries(
+    args
+      .split(",")
+      .map((arg) => arg.trim())
+      .filter((arg) => arg.includes("="))
+      .map((arg) => {
+        const [name, ...value] = arg.split("=");
+        return [name.trim(), value.join("=").trim()];
+      })
+  );
+
+const STAGE_CONDITION_TYPES: Types.StageCondition["type"][] = [
+  "None",
+  "BuildNewVersion",
+  "ServerState",
+  "DeploymentState",
+  "StackState",
+];
+
+const default_stage_condition = (
+  type: Types.StageCondition["type"]
+): Types.StageCondition => {
+  switch (type) {
+    case "BuildNewVersion":
+      return { type, params: { build: "" } };
+    case "ServerState":
+      return { type, params: { server: "", state: Types.ServerState.Ok } };
+    case "DeploymentState":
+      return {
+        type,
+        params: { deployment: "", state: Types.DeploymentState.Running },
+      };
+    case "StackState":
+      return { type, params: { stack: "", state: Types.StackState.Running } };
+    default:
+      return { type: "None" };
+  }
+};
+
+const StageConditionConfig = ({
+  condition,
+  setCondition,
+  disabled,
+}: {
+  condition: Types.StageCondition;
+  setCondition: (condition: Types.StageCondition) => void;
+  disabled: boolean;
+}) => {
+  return (
+    <div className="flex gap-4 items-center">
+      <div>Condition:</div>
+      <Select
+        value={condition.type}
+        onValueChange={(type: Types.StageCondition["type"]) =>
+          setCondition(default_stage_condition(type))
+        }
+        disabled={disabled}
+      >
+        <SelectTrigger className="w-[180px]" disabled={disabled}>
+          <SelectValue />
+        </SelectTrigger>
+        <SelectContent>
+          {STAGE_CONDITION_TYPES.map((type) => (
+            <SelectItem key={type} value={type}>
+              {type}
+            </SelectItem>
+          ))}
+        </SelectContent>
+      </Select>
+      {condition.type === "BuildNewVersion" && (
+        <ResourceSelector
+          type="Build"
+          selected={condition.params.build}
+          onSelect={(build) =>
+            setCondition({ ...condition, params: { build } })
+          }
+          disabled={disabled}
+        />
+      )}
+      {condition.type === "ServerState" && (
+        <>
+          <ResourceSelector
+            type="Server"
+            selected={condition.params.server}
+            onSelect={(server) =>
+              setCondition({
+                ...condition,
+                params: { ...condition.params, server },
+              })
+            }
+            disabled={disabled}
+          />
+          <StateSelector
+            value={condition.params.state}
+            states={Object.values(Types.ServerState)}
+            onSelect={(state) =>
+              setCondition({
+                ...condition,
+                params: {
+                  ...condition.params,
+                  state: state as Types.ServerState,
+                },
+              })
+            }
+            disabled={disabled}
+          />
+        </>
+      )}
+      {condition.type === "DeploymentState" && (
+        <>
+          <ResourceSelector
+            type="Deployment"
+            selected={condition.params.deployment}
+            onSelect={(deployment) =>
+              setCondition({
+                ...condition,
+                params: { ...condition.params, deployment },
+              })
+            }
+            disabled={disabled}
+          />
+          <StateSelector
+            value={condition.params.state}
+            states={Object.values(Types.DeploymentState)}
+            onSelect={(state) =>
+              setCondition({
+                ...condition,
+                params: {
+                  ...condition.params,
+                  state: state as Types.DeploymentState,
+                },
+              })
+            }
+            disabled={disabled}
+          />
+        </>
+      )}
+      {condition.type === "StackState" && (
+        <>
+          <ResourceSelector
+            type="Stack"
+            selected={condition.params.stack}
+            onSelect={(stack) =>
+              setCondition({
+                ...condition,
+                params: { ...condition.params, stack },
+              })
+            }
+            disabled={disabled}
+          />
+          <StateSelector
+            value={condition.params.state}
+            states={Object.values(Types.StackState)}
+            onSelect={(state) =>
+              setCondition({
+                ...condition,
+                params: {
+                  ...condition.params,
+                  state: state as Types.StackState,
+                },
+              })
+            }
+            disabled={disabled}
+          />
+        </>
+      )}
+    </div>
+  );
+};
+
+const StateSelector = ({
+  value,
+  states,
+  onSelect,
+  disabled,
+}: {
+  value: string;
+  states: string[];
+  onSelect: (state: string) => void;
+  disabled: boolean;
+}) => (
+  <Select value={value} onValueChange={onSelect} disabled={disabled}>
+    <SelectTrigger className="w-[150px]" disabled={disabled}>
+      <SelectValue />
+    </SelectTrigger>
+    <SelectContent>
+      {states.map((state) => (
+        <SelectItem key={state} value={state}>
+          {state}
+        </SelectItem>
+      ))}
+    </SelectContent>
+  </Select>
+);
+
+const new_stage = (): Types.ProcedureStage => ({
   name: "Stage",
   enabled: true,
+  run_on: Types.StageRunOn.Success,
+  condition: { type: "None" },
   executions: [default_enabled_execution()],
 });
 
 const default_enabled_execution: () => Types.EnabledExecution = () => ({
   enabled: true,
+  retries: 0,
+  retry_delay_ms: 0,
+  timeout_ms: 0,
+  continue_on_error: false,
   execution: {
     type: "None",
     params: {},
@@ -504,6 +907,7 @@ const default_enabled_execution: () => Types.EnabledExecution = () => ({
 const EXECUTION_TYPES: Types.Execution["type"][] = [
   "RunBuild",
   "Deploy",
+  "RollbackDeployment",
   "RestartDeployment",
   "StartDeployment",
   "PauseDeployment",
@@ -626,14 +1030,26 @@ const TARGET_COMPONENTS: ExecutionConfigs = {
   },
   // Procedure
   RunProcedure: {
-    params: { procedure: "" },
+    params: { procedure: "", args: {} },
     Component: ({ params, setParams, disabled }) => (
-      <ResourceSelector
-        type="Procedure"
-        selected={params.procedure}
-        onSelect={(procedure) => setParams({ procedure })}
-        disabled={disabled}
-      />
+      <div className="flex gap-2 items-center">
+        <ResourceSelector
+          type="Procedure"
+          selected={params.procedure}
+          onSelect={(procedure) => setParams({ ...params, procedure })}
+          disabled={disabled}
+        />
+        <TextUpdateMenu
+          title="Procedure Args (comma separated key=value)"
+          value={Object.entries(params.args ?? {})
+            .map(([name, value]) => `${name}=${value}`)
+            .join(", ")}
+          onUpdate={(args) => setParams({ ...params, args: parse_args(args) })}
+          placeholder="env=staging"
+          triggerClassName="w-[200px]"
+          disabled={disabled}
+        />
+      </div>
     ),
   },
   // Build
@@ -673,6 +1089,17 @@ const TARGET_COMPONENTS: ExecutionConfigs = {
       );
     },
   },
+  RollbackDeployment: {
+    params: { deployment: "" },
+    Component: ({ params, setParams, disabled }) => (
+      <ResourceSelector
+        type="Deployment"
+        selected={params.deployment}
+        onSelect={(deployment) => setParams({ deployment })}
+        disabled={disabled}
+      />
+    ),
+  },
   StartDeployment: {
     params: { deployment: "" },
     Component: ({ params, setParams, disabled }) => (
@@ -93,7 +93,7 @@ export const ProcedureComponents: RequiredResourceComponents = {
           name={procedure.name}
           title={running ? "Running" : "Run"}
           icon={<Route className="h-4 w-4" />}
-          onClick={() => mutate({ procedure: id })}
+          onClick={() => mutate({ procedure: id, args: {} })}
           disabled={running || isPending}
           loading={running}
         />
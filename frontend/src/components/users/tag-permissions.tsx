@@ -0,0 +1,91 @@
+import { PermissionLevelSelector } from "@components/config/util";
+import { Section } from "@components/layouts";
+import { useInvalidate, useRead, useWrite } from "@lib/hooks";
+import { Types } from "@komodo/client";
+import { useToast } from "@ui/use-toast";
+
+export const UserTargetPermissionsOnTags = ({
+  user_target,
+}: {
+  user_target: Types.UserTarget;
+}) => {
+  const { toast } = useToast();
+  const inv = useInvalidate();
+
+  const { mutate } = useWrite("UpdatePermissionOnTag", {
+    onSuccess: () => {
+      toast({ title: "Updated permissions on tag" });
+      if (user_target.type === "User") {
+        inv(["FindUser", { user: user_target.id }]);
+      } else if (user_target.type === "UserGroup") {
+        inv(["GetUserGroup", { user_group: user_target.id }]);
+      }
+    },
+  });
+
+  const update = (tag: string, permission: Types.PermissionLevel) =>
+    mutate({ user_target, tag, permission });
+
+  if (user_target.type === "User") {
+    return <UserPermissionsOnTags user_id={user_target.id} update={update} />;
+  } else if (user_target.type === "UserGroup") {
+    return (
+      <UserGroupPermissionsOnTags group_id={user_target.id} update={update} />
+    );
+  }
+};
+
+const UserPermissionsOnTags = ({
+  user_id,
+  update,
+}: {
+  user_id: string;
+  update: (tag: string, permission: Types.PermissionLevel) => void;
+}) => {
+  const user = useRead("FindUser", { user: user_id }).data;
+  return <PermissionsOnTags tags={user?.tags} update={update} />;
+};
+
+const UserGroupPermissionsOnTags = ({
+  group_id,
+  update,
+}: {
+  group_id: string;
+  update: (tag: string, permission: Types.PermissionLevel) => void;
+}) => {
+  const group = useRead("GetUserGroup", { user_group: group_id }).data;
+  return <PermissionsOnTags tags={group?.tags} update={update} />;
+};
+
+const PermissionsOnTags = ({
+  tags,
+  update,
+}: {
+  tags: Types.User["tags"];
+  update: (tag: string, permission: Types.PermissionLevel) => void;
+}) => {
+  const all_tags = useRead("ListTags", {}).data;
+  if (!all_tags?.length) return null;
+  return (
+    <Section title="Tag Permissions">
+      <div className="p-1 grid gap-4 grid-cols-1 md:grid-cols-2 xl:grid-cols-3 2xl:grid-cols-4">
+        {all_tags.map((tag) => {
+          const id = tag._id?.$oid!;
+          const level = tags?.[id] ?? Types.PermissionLevel.None;
+          return (
+            <div
+              key={id}
+              className="flex items-center justify-between w-[270px]"
+            >
+              {tag.name}:
+              <PermissionLevelSelector
+                level={level}
+                onSelect={(level) => update(id, level)}
+              />
+            </div>
+          );
+        })}
+      </div>
+    </Section>
+  );
+};
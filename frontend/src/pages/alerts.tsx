@@ -19,7 +19,7 @@ import { useParams } from "react-router";
 const ALERT_TYPES: { [key: string]: Types.AlertData["type"][] } = {
   Server: ["ServerUnreachable", "ServerCpu", "ServerMem", "ServerDisk"],
   Deployment: ["ContainerStateChange"],
-  Build: ["BuildFailed"],
+  Build: ["BuildFailed", "BuildVulnerabilities"],
 };
 
 const FALLBACK_ALERT_TYPES = [
@@ -13,11 +13,11 @@ import { useAuth, useLoginOptions, useUserInvalidate } from "@lib/hooks";
 import { useState } from "react";
 import { ThemeToggle } from "@ui/theme";
 import { AUTH_TOKEN_STORAGE_KEY, KOMODO_BASE_URL } from "@main";
-import { Loader2, X } from "lucide-react";
+import { KeyRound, Loader2, X } from "lucide-react";
 import { cn } from "@lib/utils";
 import { useToast } from "@ui/use-toast";
 
-type OauthProvider = "Github" | "Google";
+type OauthProvider = "Github" | "Google" | "OIDC";
 
 const login_with_oauth = (provider: OauthProvider) => {
   const redirect = encodeURIComponent(location.href);
@@ -65,6 +65,8 @@ const useExchangeToken = () => {
 export const Login = () => {
   const options = useLoginOptions().data;
   const [creds, set] = useState({ username: "", password: "" });
+  const [totpToken, setTotpToken] = useState<string>();
+  const [totpCode, setTotpCode] = useState("");
   const userInvalidate = useUserInvalidate();
   const { toast } = useToast();
   const onSuccess = ({ jwt }: { jwt: string }) => {
@@ -94,7 +96,13 @@ export const Login = () => {
     }
   );
   const { mutate: login, isPending: loginPending } = useAuth("LoginLocalUser", {
-    onSuccess,
+    onSuccess: ({ jwt, totp_token }) => {
+      if (totp_token) {
+        setTotpToken(totp_token);
+      } else {
+        onSuccess({ jwt });
+      }
+    },
     onError: (e: any) => {
       const message = e?.response?.data?.error as string | undefined;
       if (message) {
@@ -112,6 +120,50 @@ export const Login = () => {
       }
     },
   });
+  const { mutate: ldapLogin, isPending: ldapLoginPending } = useAuth(
+    "LoginLdapUser",
+    {
+      onSuccess,
+      onError: (e: any) => {
+        const message = e?.response?.data?.error as string | undefined;
+        if (message) {
+          toast({
+            title: `Failed to login user. '${message}'`,
+            variant: "destructive",
+          });
+          console.error(e);
+        } else {
+          toast({
+            title: "Failed to login user. See console log for details.",
+            variant: "destructive",
+          });
+          console.error(e);
+        }
+      },
+    }
+  );
+
+  const { mutate: loginTotp, isPending: loginTotpPending } = useAuth(
+    "LoginTotp",
+    {
+      onSuccess,
+      onError: (e: any) => {
+        const message = e?.response?.data?.error as string | undefined;
+        toast({
+          title: message
+            ? `Failed to verify code. '${message}'`
+            : "Failed to verify code. See console log for details.",
+          variant: "destructive",
+        });
+        console.error(e);
+        setTotpCode("");
+        // The token is discarded after too many failed attempts.
+        if (message?.includes("totp token")) setTotpToken(undefined);
+      },
+    }
+  );
+  const submitTotp = () =>
+    totpToken && loginTotp({ token: totpToken, code: totpCode });
 
   // Handle exchange token loop to avoid showing login flash
   const exchangeTokenPending = useExchangeToken();
@@ -125,9 +177,14 @@ export const Login = () => {
 
   const no_auth_configured =
     options !== undefined &&
-    Object.values(options).every((value) => value === false);
+    !options.local &&
+    !options.github &&
+    !options.google &&
+    !options.oidc &&
+    !options.ldap;
 
-  const show_sign_up = options !== undefined && !options.registration_disabled;
+  const show_sign_up =
+    options !== undefined && options.local && !options.registration_disabled;
 
   // Otherwise just standard login
   return (
@@ -139,7 +196,7 @@ export const Login = () => {
       <div
         className={cn(
           "flex justify-center items-center container",
-          options?.local ? "mt-32" : "mt-64"
+          options?.local || options?.ldap ? "mt-32" : "mt-64"
         )}
       >
         <Card className="w-full max-w-[500px] place-self-center">
@@ -153,6 +210,7 @@ export const Login = () => {
                 [
                   [options?.google, "Google"],
                   [options?.github, "Github"],
+                  [options?.oidc, "OIDC"],
                 ] as Array<[boolean | undefined, OauthProvider]>
               ).map(
                 ([enabled, provider]) =>
@@ -164,11 +222,15 @@ export const Login = () => {
                       onClick={() => login_with_oauth(provider)}
                     >
                       {provider}
-                      <img
-                        src={`/icons/${provider.toLowerCase()}.svg`}
-                        alt={provider}
-                        className="w-4 h-4"
-                      />
+                      {provider === "OIDC" ? (
+                        <KeyRound className="w-4 h-4" />
+                      ) : (
+                        <img
+                          src={`/icons/${provider.toLowerCase()}.svg`}
+                          alt={provider}
+                          className="w-4 h-4"
+                        />
+                      )}
                     </Button>
                   )
               )}
@@ -180,7 +242,43 @@ export const Login = () => {
               )}
             </div>
           </CardHeader>
-          {options?.local && (
+          {totpToken && (
+            <>
+              <CardContent className="flex flex-col justify-center w-full gap-4">
+                <div className="flex flex-col gap-2">
+                  <Label htmlFor="totp-code">Two-Factor Code</Label>
+                  <Input
+                    id="totp-code"
+                    autoFocus
+                    autoComplete="one-time-code"
+                    placeholder="Authenticator or recovery code"
+                    value={totpCode}
+                    onChange={({ target }) => setTotpCode(target.value)}
+                    onKeyDown={(e) => e.key === "Enter" && submitTotp()}
+                  />
+                </div>
+              </CardContent>
+              <CardFooter className="flex gap-4 w-full justify-end">
+                <Button
+                  variant="outline"
+                  onClick={() => {
+                    setTotpToken(undefined);
+                    setTotpCode("");
+                  }}
+                >
+                  Back
+                </Button>
+                <Button
+                  variant="default"
+                  onClick={submitTotp}
+                  disabled={loginTotpPending || !totpCode}
+                >
+                  Verify
+                </Button>
+              </CardFooter>
+            </>
+          )}
+          {!totpToken && (options?.local || options?.ldap) && (
             <>
               <CardContent className="flex flex-col justify-center w-full gap-4">
                 <div className="flex flex-col gap-2">
@@ -202,7 +300,10 @@ export const Login = () => {
                     onChange={({ target }) =>
                       set((c) => ({ ...c, password: target.value }))
                     }
-                    onKeyDown={(e) => e.key === "Enter" && login(creds)}
+                    onKeyDown={(e) =>
+                      e.key === "Enter" &&
+                      (options?.local ? login(creds) : ldapLogin(creds))
+                    }
                   />
                 </div>
               </CardContent>
@@ -216,13 +317,24 @@ export const Login = () => {
                     Sign Up
                   </Button>
                 )}
-                <Button
-                  variant="default"
-                  onClick={() => login(creds)}
-                  disabled={loginPending}
-                >
-                  Log In
-                </Button>
+                {options?.ldap && (
+                  <Button
+                    variant={options?.local ? "outline" : "default"}
+                    onClick={() => ldapLogin(creds)}
+                    disabled={ldapLoginPending}
+                  >
+                    {options?.local ? "LDAP Log In" : "Log In"}
+                  </Button>
+                )}
+                {options?.local && (
+                  <Button
+                    variant="default"
+                    onClick={() => login(creds)}
+                    disabled={loginPending}
+                  >
+                    Log In
+                  </Button>
+                )}
               </CardFooter>
             </>
           )}
@@ -8,6 +8,7 @@ import { Page } from "@components/layouts";
 import { useState } from "react";
 import { Input } from "@ui/input";
 import { ProvidersPage } from "./providers";
+import { TwoFactor } from "./two_factor";
 
 export const Settings = () => {
   const user = useUser().data;
@@ -33,6 +34,9 @@ export const Settings = () => {
             )}
             {user?.admin && <TabsTrigger value="Users">Users</TabsTrigger>}
             <TabsTrigger value="Api Keys">Api Keys</TabsTrigger>
+            {user?.config.type === "Local" && (
+              <TabsTrigger value="Two-Factor">Two-Factor</TabsTrigger>
+            )}
           </TabsList>
 
           {currentView === "Variables" && <CreateVariable />}
@@ -67,6 +71,11 @@ export const Settings = () => {
         <TabsContent value="Api Keys">
           <Keys />
         </TabsContent>
+        {user?.config.type === "Local" && (
+          <TabsContent value="Two-Factor">
+            <TwoFactor />
+          </TabsContent>
+        )}
       </Tabs>
     </Page>
   );
@@ -21,6 +21,9 @@ import {
   DropdownMenuTrigger,
 } from "@ui/dropdown-menu";
 import { KeysTable } from "@components/keys/table";
+import { Types } from "@komodo/client";
+import { ToggleGroup, ToggleGroupItem } from "@ui/toggle-group";
+import { PermissionLevelSelector } from "@components/config/util";
 
 export const Keys = () => {
   useSetTitle("Api Keys");
@@ -36,6 +39,11 @@ export const CreateKey = () => {
   const [open, setOpen] = useState(false);
   const [name, setName] = useState("");
   const [expires, setExpires] = useState<ExpiresOptions>("never");
+  const [requests, setRequests] = useState<Types.ApiKeyRequestType[]>([]);
+  const [maxPermission, setMaxPermission] = useState(
+    Types.PermissionLevel.Write
+  );
+  const [tags, setTags] = useState("");
   const [submitted, setSubmitted] = useState<{ key: string; secret: string }>();
   const invalidate = useInvalidate();
   const { mutate, isPending } = useManageUser("CreateApiKey", {
@@ -51,12 +59,28 @@ export const CreateKey = () => {
     "1 year": now + ONE_DAY_MS * 365,
     never: 0,
   };
-  const submit = () => mutate({ name, expires: expiresOptions[expires] });
+  const submit = () =>
+    mutate({
+      name,
+      expires: expiresOptions[expires],
+      scope: {
+        requests,
+        resources: [],
+        tags: tags
+          .split(",")
+          .map((tag) => tag.trim())
+          .filter((tag) => tag),
+        max_permission: maxPermission,
+      },
+    });
   const onOpenChange = (open: boolean) => {
     setOpen(open);
     if (!open) {
       setName("");
       setExpires("never");
+      setRequests([]);
+      setMaxPermission(Types.PermissionLevel.Write);
+      setTags("");
       setSubmitted(undefined);
     }
   };
@@ -129,6 +153,42 @@ export const CreateKey = () => {
                   </DropdownMenuContent>
                 </DropdownMenu>
               </div>
+              <div className="flex items-center justify-between">
+                Requests
+                <ToggleGroup
+                  type="multiple"
+                  value={requests}
+                  onValueChange={(requests) =>
+                    setRequests(requests as Types.ApiKeyRequestType[])
+                  }
+                >
+                  {Object.values(Types.ApiKeyRequestType).map((request) => (
+                    <ToggleGroupItem key={request} value={request}>
+                      {request}
+                    </ToggleGroupItem>
+                  ))}
+                </ToggleGroup>
+              </div>
+              <div className="flex items-center justify-between">
+                Max Permission
+                <PermissionLevelSelector
+                  level={maxPermission}
+                  onSelect={setMaxPermission}
+                />
+              </div>
+              <div className="flex items-center justify-between">
+                Tags
+                <Input
+                  className="w-72"
+                  placeholder="Any resource"
+                  value={tags}
+                  onChange={(e) => setTags(e.target.value)}
+                />
+              </div>
+              <div className="text-sm text-muted-foreground">
+                Leave requests and tags empty to allow all. Scoped keys
+                cannot be used to manage api keys.
+              </div>
             </div>
             <DialogFooter className="flex justify-end">
               <Button className="gap-4" onClick={submit} disabled={isPending}>
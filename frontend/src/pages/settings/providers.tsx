@@ -19,6 +19,13 @@ import {
   DialogTrigger,
 } from "@ui/dialog";
 import { Input } from "@ui/input";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@ui/select";
 import { Switch } from "@ui/switch";
 import { useToast } from "@ui/use-toast";
 import { Check, Loader2, PlusCircle, Trash } from "lucide-react";
@@ -128,7 +135,12 @@ const Providers = ({ type }: { type: "GitProvider" | "DockerRegistry" }) => {
                         placeholder: "Input domain, eg. git.komo.do",
                         titleRight:
                           type === "GitProvider" ? (
-                            <UpdateHttps id={row.original._id?.$oid!} />
+                            <div className="flex items-center gap-4">
+                              <UpdateProviderType
+                                id={row.original._id?.$oid!}
+                              />
+                              <UpdateHttps id={row.original._id?.$oid!} />
+                            </div>
                           ) : undefined,
                         onUpdate: (domain) => {
                           if (row.original.domain === domain) {
@@ -276,6 +288,66 @@ const UpdateHttps = ({ id }: { id: string }) => {
   );
 };
 
+const PROVIDER_TYPES: Types.GitProviderType[] = [
+  Types.GitProviderType.Github,
+  Types.GitProviderType.Gitlab,
+  Types.GitProviderType.Gitea,
+];
+
+const ProviderTypeSelector = ({
+  value,
+  onSelect,
+}: {
+  value: Types.GitProviderType | undefined;
+  onSelect: (provider_type: Types.GitProviderType) => void;
+}) => (
+  <Select
+    value={value ?? Types.GitProviderType.Github}
+    onValueChange={(provider_type) =>
+      onSelect(provider_type as Types.GitProviderType)
+    }
+  >
+    <SelectTrigger className="w-32">
+      <SelectValue />
+    </SelectTrigger>
+    <SelectContent>
+      {PROVIDER_TYPES.map((provider_type) => (
+        <SelectItem key={provider_type} value={provider_type}>
+          {provider_type}
+        </SelectItem>
+      ))}
+    </SelectContent>
+  </Select>
+);
+
+const UpdateProviderType = ({ id }: { id: string }) => {
+  const account = useRead("ListGitProviderAccounts", {}).data?.find(
+    (account) => account._id?.$oid === id
+  ) as Types.GitProviderAccount;
+  const { toast } = useToast();
+  const inv = useInvalidate();
+  const { mutate: updateAccount } = useWrite("UpdateGitProviderAccount", {
+    onSuccess: () => {
+      inv(["ListGitProviderAccounts"], ["GetGitProviderAccount", { id }]);
+      toast({ title: "Updated account" });
+    },
+  });
+  return (
+    <div className="flex items-center gap-2">
+      <div>Provider:</div>
+      <ProviderTypeSelector
+        value={account.provider_type}
+        onSelect={(provider_type) =>
+          updateAccount({
+            id,
+            account: { provider_type },
+          })
+        }
+      />
+    </div>
+  );
+};
+
 const CreateAccount = ({
   type,
 }: {
@@ -285,6 +357,9 @@ const CreateAccount = ({
   const [open, setOpen] = useState(false);
   const [domain, setDomain] = useState("");
   const [https, setHttps] = useState(true);
+  const [provider_type, setProviderType] = useState(
+    Types.GitProviderType.Github
+  );
   const [username, setUsername] = useState("");
   const [token, setToken] = useState("");
   const invalidate = useInvalidate();
@@ -295,7 +370,13 @@ const CreateAccount = ({
       setOpen(false);
     },
   });
-  const submit = () => create({ account: { domain, https, username, token } });
+  const submit = () =>
+    create({
+      account:
+        type === "GitProvider"
+          ? { domain, https, provider_type, username, token }
+          : { domain, username, token },
+    });
   const form: Array<
     | undefined
     | [string, string, (e: ChangeEvent<HTMLInputElement>) => void, false]
@@ -337,6 +418,15 @@ const CreateAccount = ({
           <DialogTitle>Create {account_type}</DialogTitle>
         </DialogHeader>
         <div className="py-8 flex flex-col gap-4">
+          {type === "GitProvider" && (
+            <div className="flex items-center justify-between">
+              Provider
+              <ProviderTypeSelector
+                value={provider_type}
+                onSelect={setProviderType}
+              />
+            </div>
+          )}
           {form.map((item) => {
             if (!item) return;
             const [title, value, onChange, bool] = item;
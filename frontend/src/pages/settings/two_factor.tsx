@@ -0,0 +1,193 @@
+import { CopyButton } from "@components/util";
+import { useManageUser, useUser, useUserInvalidate } from "@lib/hooks";
+import { Button } from "@ui/button";
+import {
+  Card,
+  CardContent,
+  CardDescription,
+  CardFooter,
+  CardHeader,
+  CardTitle,
+} from "@ui/card";
+import { Input } from "@ui/input";
+import { useToast } from "@ui/use-toast";
+import { Check, Loader2, ShieldCheck, ShieldOff } from "lucide-react";
+import { useState } from "react";
+
+export const TwoFactor = () => {
+  const user = useUser().data;
+  if (user?.config.type !== "Local") {
+    return (
+      <div className="text-muted-foreground">
+        Two-factor authentication is only available for users who log in
+        with a username and password.
+      </div>
+    );
+  }
+  return user.config.data.totp_enabled ? <DisableTotp /> : <EnrollTotp />;
+};
+
+const EnrollTotp = () => {
+  const userInvalidate = useUserInvalidate();
+  const [code, setCode] = useState("");
+  const [recoveryCodes, setRecoveryCodes] = useState<string[]>();
+  const {
+    mutate: begin,
+    isPending: beginPending,
+    data: enrollment,
+  } = useManageUser("BeginTotpEnrollment");
+  const { mutate: confirm, isPending: confirmPending } = useManageUser(
+    "ConfirmTotpEnrollment",
+    {
+      onSuccess: ({ recovery_codes }) => setRecoveryCodes(recovery_codes),
+    }
+  );
+
+  if (recoveryCodes) {
+    return (
+      <Card className="max-w-[600px]">
+        <CardHeader>
+          <CardTitle>Recovery Codes</CardTitle>
+          <CardDescription>
+            Two-factor authentication is enabled. Store these recovery codes
+            somewhere safe. Each can be used once in place of a code if you
+            lose access to your authenticator app. They will not be shown
+            again.
+          </CardDescription>
+        </CardHeader>
+        <CardContent className="flex gap-4 items-start">
+          <pre className="grid grid-cols-2 gap-x-8 gap-y-1 font-mono">
+            {recoveryCodes.map((code) => (
+              <span key={code}>{code}</span>
+            ))}
+          </pre>
+          <CopyButton content={recoveryCodes.join("\n")} />
+        </CardContent>
+        <CardFooter className="flex justify-end">
+          <Button className="gap-4" onClick={userInvalidate}>
+            Done <Check className="w-4" />
+          </Button>
+        </CardFooter>
+      </Card>
+    );
+  }
+
+  return (
+    <Card className="max-w-[600px]">
+      <CardHeader>
+        <CardTitle>Two-Factor Authentication</CardTitle>
+        <CardDescription>
+          Require a code from an authenticator app in addition to your
+          password when logging in.
+        </CardDescription>
+      </CardHeader>
+      {enrollment ? (
+        <>
+          <CardContent className="flex flex-col gap-4">
+            <div className="text-sm text-muted-foreground">
+              Add this key to your authenticator app, then enter the code it
+              shows to finish enabling two-factor authentication.
+            </div>
+            <div className="flex items-center justify-between gap-4">
+              Key
+              <Input className="w-96" value={enrollment.secret} disabled />
+              <CopyButton content={enrollment.secret} />
+            </div>
+            <div className="flex items-center justify-between gap-4">
+              Uri
+              <Input className="w-96" value={enrollment.uri} disabled />
+              <CopyButton content={enrollment.uri} />
+            </div>
+            <div className="flex items-center justify-between gap-4">
+              Code
+              <Input
+                className="w-96"
+                value={code}
+                placeholder="123456"
+                onChange={(e) => setCode(e.target.value)}
+                onKeyDown={(e) => e.key === "Enter" && confirm({ code })}
+              />
+              <div className="w-10" />
+            </div>
+          </CardContent>
+          <CardFooter className="flex justify-end">
+            <Button
+              className="gap-4"
+              onClick={() => confirm({ code })}
+              disabled={confirmPending || !code}
+            >
+              Enable
+              {confirmPending ? (
+                <Loader2 className="w-4 animate-spin" />
+              ) : (
+                <ShieldCheck className="w-4" />
+              )}
+            </Button>
+          </CardFooter>
+        </>
+      ) : (
+        <CardFooter className="flex justify-end">
+          <Button
+            className="gap-4"
+            onClick={() => begin({})}
+            disabled={beginPending}
+          >
+            Set Up
+            {beginPending ? (
+              <Loader2 className="w-4 animate-spin" />
+            ) : (
+              <ShieldCheck className="w-4" />
+            )}
+          </Button>
+        </CardFooter>
+      )}
+    </Card>
+  );
+};
+
+const DisableTotp = () => {
+  const userInvalidate = useUserInvalidate();
+  const { toast } = useToast();
+  const [code, setCode] = useState("");
+  const { mutate, isPending } = useManageUser("DisableTotp", {
+    onSuccess: () => {
+      toast({ title: "Two-factor authentication disabled" });
+      userInvalidate();
+    },
+  });
+  return (
+    <Card className="max-w-[600px]">
+      <CardHeader>
+        <CardTitle>Two-Factor Authentication</CardTitle>
+        <CardDescription>
+          Two-factor authentication is enabled. Enter a code from your
+          authenticator app, or a recovery code, to disable it.
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="flex items-center justify-between gap-4">
+        Code
+        <Input
+          className="w-96"
+          value={code}
+          onChange={(e) => setCode(e.target.value)}
+          onKeyDown={(e) => e.key === "Enter" && mutate({ code })}
+        />
+      </CardContent>
+      <CardFooter className="flex justify-end">
+        <Button
+          variant="destructive"
+          className="gap-4"
+          onClick={() => mutate({ code })}
+          disabled={isPending || !code}
+        >
+          Disable
+          {isPending ? (
+            <Loader2 className="w-4 animate-spin" />
+          ) : (
+            <ShieldOff className="w-4" />
+          )}
+        </Button>
+      </CardFooter>
+    </Card>
+  );
+};
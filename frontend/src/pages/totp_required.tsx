@@ -0,0 +1,25 @@
+import { AUTH_TOKEN_STORAGE_KEY } from "@main";
+import { TwoFactor } from "@pages/settings/two_factor";
+import { Button } from "@ui/button";
+
+export const TotpRequired = () => {
+  return (
+    <div className="w-full h-screen flex justify-center items-center">
+      <div className="flex flex-col gap-4 justify-center items-center">
+        <div className="text-muted-foreground">
+          Admins must enable two-factor authentication before using Komodo.
+        </div>
+        <TwoFactor />
+        <Button
+          variant="outline"
+          onClick={() => {
+            localStorage.removeItem(AUTH_TOKEN_STORAGE_KEY);
+            location.reload();
+          }}
+        >
+          Log Out
+        </Button>
+      </div>
+    </div>
+  );
+};
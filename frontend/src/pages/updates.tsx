@@ -141,6 +141,7 @@ const OPERATIONS_BY_RESOURCE: { [key: string]: Types.Operation[] } = {
     Types.Operation.UpdateDeployment,
     Types.Operation.RenameDeployment,
     Types.Operation.Deploy,
+    Types.Operation.RollbackDeployment,
     Types.Operation.StartDeployment,
     Types.Operation.RestartDeployment,
     Types.Operation.PauseDeployment,
@@ -1,4 +1,5 @@
 import { UserTargetPermissionsOnResourceTypes } from "@components/users/resource-type-permissions";
+import { UserTargetPermissionsOnTags } from "@components/users/tag-permissions";
 import { ExportButton } from "@components/export";
 import { Page, Section } from "@components/layouts";
 import { PermissionsTable } from "@components/users/permissions-table";
@@ -89,6 +90,9 @@ export const UserGroupPage = () => {
       <UserTargetPermissionsOnResourceTypes
         user_target={{ type: "UserGroup", id: group._id?.$oid! }}
       />
+      <UserTargetPermissionsOnTags
+        user_target={{ type: "UserGroup", id: group._id?.$oid! }}
+      />
       <PermissionsTable user_target={{ type: "UserGroup", id: group_id }} />
       <div className="flex flex-col justify-end w-full gap-4">
         <div className="flex justify-end w-full">
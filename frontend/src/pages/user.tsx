@@ -1,4 +1,5 @@
 import { UserTargetPermissionsOnResourceTypes } from "@components/users/resource-type-permissions";
+import { UserTargetPermissionsOnTags } from "@components/users/tag-permissions";
 import { KeysTable } from "@components/keys/table";
 import { Page, Section } from "@components/layouts";
 import { PermissionsTable } from "@components/users/permissions-table";
@@ -88,6 +89,9 @@ export const UserPage = () => {
           <UserTargetPermissionsOnResourceTypes
             user_target={{ type: "User", id: user._id?.$oid! }}
           />
+          <UserTargetPermissionsOnTags
+            user_target={{ type: "User", id: user._id?.$oid! }}
+          />
           <PermissionsTable user_target={{ type: "User", id: user_id }} />
         </>
       )}
@@ -1,5 +1,5 @@
 import { Layout } from "@components/layouts";
-import { useUser } from "@lib/hooks";
+import { useLoginOptions, useUser } from "@lib/hooks";
 import { Login } from "@pages/login";
 import { Resource } from "@pages/resource";
 import { Resources } from "@pages/resources";
@@ -8,6 +8,7 @@ import { Tree } from "@pages/home/tree";
 import { Updates } from "@pages/updates";
 import { AllResources } from "@pages/home/all_resources";
 import { UserDisabled } from "@pages/user_disabled";
+import { TotpRequired } from "@pages/totp_required";
 import { Home } from "@pages/home";
 import { ResourceStats } from "@pages/resource_stats";
 import { Alerts } from "@pages/alerts";
@@ -78,10 +79,19 @@ const ROUTER = createBrowserRouter([
 
 export const Router = () => {
   const { data: user, isLoading } = useUser();
+  const options = useLoginOptions().data;
 
   if (isLoading && !user) return null;
   if (!user) return <Login />;
   if (!user.enabled) return <UserDisabled />;
+  if (
+    options?.require_admin_totp &&
+    user.admin &&
+    user.config.type === "Local" &&
+    !user.config.data.totp_enabled
+  ) {
+    return <TotpRequired />;
+  }
 
   return <RouterProvider router={ROUTER} />;
 };
@@ -1,5 +1,19 @@
-use komodo_client::entities::{komodo_timestamp, update::Log};
+use std::{future::Future, process::Stdio};
+
+use komodo_client::entities::{
+  komodo_timestamp,
+  update::{Log, LogOutput},
+};
 use run_command::{async_run_command, CommandOutput};
+use tokio::{
+  io::{AsyncBufReadExt, BufReader},
+  process::Command,
+  sync::mpsc::UnboundedSender,
+};
+
+tokio::task_local! {
+  static OUTPUT_SENDER: UnboundedSender<LogOutput>;
+}
 
 pub async fn run_komodo_command(stage: &str, command: String) -> Log {
   let start_ts = komodo_timestamp();
@@ -7,6 +21,147 @@ pub async fn run_komodo_command(stage: &str, command: String) -> Log {
   output_into_log(stage, command, start_ts, output)
 }
 
+/// Runs the future, sending the output of any
+/// [run_komodo_command_streaming] within to the sender as it is produced.
+pub async fn with_output_stream<F: Future>(
+  sender: UnboundedSender<LogOutput>,
+  f: F,
+) -> F::Output {
+  OUTPUT_SENDER.scope(sender, f).await
+}
+
+/// Same as [run_komodo_command], but when called within [with_output_stream],
+/// the output is also sent line by line while the command runs.
+///
+/// The replacers are applied to the command and output,
+/// both to what is streamed and to the returned Log.
+pub async fn run_komodo_command_streaming(
+  stage: &str,
+  command: String,
+  replacers: &Vec<(String, String)>,
+) -> Log {
+  let Ok(sender) = OUTPUT_SENDER.try_with(Clone::clone) else {
+    let mut log = run_komodo_command(stage, command).await;
+    log.command = svi::replace_in_string(&log.command, replacers);
+    log.stdout = svi::replace_in_string(&log.stdout, replacers);
+    log.stderr = svi::replace_in_string(&log.stderr, replacers);
+    return log;
+  };
+
+  let start_ts = komodo_timestamp();
+  let sanitized_command = svi::replace_in_string(&command, replacers);
+
+  let child = Command::new("sh")
+    .arg("-c")
+    .arg(&command)
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn();
+  let mut child = match child {
+    Ok(child) => child,
+    Err(e) => {
+      return output_into_log(
+        stage,
+        sanitized_command,
+        start_ts,
+        CommandOutput::from(Err(e)),
+      )
+    }
+  };
+
+  // unwrap ok: both are piped above
+  let mut stdout_reader =
+    BufReader::new(child.stdout.take().unwrap());
+  let mut stderr_reader =
+    BufReader::new(child.stderr.take().unwrap());
+  // Read raw bytes so invalid utf8 output can't stop the pipe
+  // from being drained, which would block the child.
+  let mut stdout_buf = Vec::new();
+  let mut stderr_buf = Vec::new();
+
+  let mut stdout = String::new();
+  let mut stderr = String::new();
+  let (mut stdout_done, mut stderr_done) = (false, false);
+
+  while !stdout_done || !stderr_done {
+    // read_until is cancel safe, partially read bytes stay in the buffer.
+    let (res, is_stdout) = tokio::select! {
+      res = stdout_reader.read_until(b'\n', &mut stdout_buf), if !stdout_done => (res, true),
+      res = stderr_reader.read_until(b'\n', &mut stderr_buf), if !stderr_done => (res, false),
+    };
+    let buf = if is_stdout {
+      &mut stdout_buf
+    } else {
+      &mut stderr_buf
+    };
+    match res {
+      Ok(0) => {
+        if is_stdout {
+          stdout_done = true;
+        } else {
+          stderr_done = true;
+        }
+        // Output may not end with a newline
+        if buf.is_empty() {
+          continue;
+        }
+      }
+      Ok(_) => {}
+      Err(e) => {
+        // Can't keep reading the pipe, kill the child
+        // so waiting on it below can't hang.
+        let _ = child.start_kill();
+        stderr.push_str(&format!(
+          "Failed to read command output, killing command | {e:#}\n"
+        ));
+        stdout_done = true;
+        stderr_done = true;
+        continue;
+      }
+    }
+    let line = String::from_utf8_lossy(buf);
+    let mut line = svi::replace_in_string(
+      line.strip_suffix('\n').unwrap_or(&line),
+      replacers,
+    );
+    line.push('\n');
+    buf.clear();
+    let mut output = LogOutput {
+      stage: stage.to_string(),
+      command: sanitized_command.clone(),
+      ..Default::default()
+    };
+    if is_stdout {
+      stdout.push_str(&line);
+      output.stdout = line;
+    } else {
+      stderr.push_str(&line);
+      output.stderr = line;
+    }
+    // The receiver may be gone if the request was dropped,
+    // the command still runs to completion.
+    let _ = sender.send(output);
+  }
+
+  let success = match child.wait().await {
+    Ok(status) => status.success(),
+    Err(e) => {
+      stderr.push_str(&format!("{e:#?}"));
+      false
+    }
+  };
+
+  Log {
+    stage: stage.to_string(),
+    command: sanitized_command,
+    stdout,
+    stderr,
+    success,
+    start_ts,
+    end_ts: komodo_timestamp(),
+  }
+}
+
 pub fn output_into_log(
   stage: &str,
   command: String,
@@ -19,6 +19,7 @@ pub enum Color {
   Red,
   Green,
   Blue,
+  Orange,
 }
 
 impl std::fmt::Display for Color {
@@ -29,6 +30,9 @@ impl std::fmt::Display for Color {
         f.write_str("text-green-700 dark:text-green-400")
       }
       Color::Blue => f.write_str("text-blue-700 dark:text-blue-400"),
+      Color::Orange => {
+        f.write_str("text-orange-700 dark:text-orange-400")
+      }
     }
   }
 }